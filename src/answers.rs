@@ -0,0 +1,189 @@
+//! Recorded answers for the real puzzle inputs, used to catch a refactor that changes a day's result.
+//!
+//! The answers for an input file are stored alongside it, e.g. `res/day-7-input.answers` for `res/day-7-input`. Each
+//! line is a part number and that part's answer, `1: 1427048`. Answers that span multiple lines, like the CRT output
+//! for day 10, have their newlines escaped as `\n`.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::solution::Answer;
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
+
+/// The result of comparing an answer with the recorded one
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Status {
+    /// The answer matches the recorded answer
+    Pass,
+    /// The answer differs, holds the answer that was recorded
+    Fail(String),
+    /// There is no answer recorded for this part yet
+    New,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail(expected) => write!(f, "FAIL (expected {})", expected),
+            Status::New => write!(f, "NEW"),
+        }
+    }
+}
+
+/// The known answers for one input file, keyed by part number
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct RecordedAnswers {
+    answers: BTreeMap<usize, String>,
+}
+
+impl RecordedAnswers {
+    /// Load the answers recorded for an input file, if there is no answers file yet nothing has been recorded
+    pub fn load(input_path: &Path, variant: Option<&str>) -> io::Result<Self> {
+        match fs::read_to_string(answers_path(input_path, variant)) {
+            Ok(contents) => Self::parse(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Write the answers to the file next to the input file
+    pub fn save(&self, input_path: &Path, variant: Option<&str>) -> io::Result<PathBuf> {
+        let path = answers_path(input_path, variant);
+        fs::write(&path, self.to_string())?;
+
+        Ok(path)
+    }
+
+    /// Parse the contents of an answers file
+    fn parse(contents: &str) -> Result<Self, ParseError> {
+        let answers = parse_lines(contents, |line| {
+            let (part, answer) = split_once(line, line, ": ")?;
+            Ok((parse_number(line, part)?, unescape(answer)))
+        })?;
+
+        Ok(RecordedAnswers { answers: answers.into_iter().collect() })
+    }
+
+    /// Compare an answer with the one recorded for that part
+    pub fn check(&self, part: usize, answer: &Answer) -> Status {
+        match self.answers.get(&part) {
+            Some(expected) if *expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail(expected.to_string()),
+            None => Status::New,
+        }
+    }
+
+    /// Replace the answer recorded for a part
+    pub fn record(&mut self, part: usize, answer: &Answer) {
+        self.answers.insert(part, answer.to_string());
+    }
+}
+
+impl Display for RecordedAnswers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (part, answer) in &self.answers {
+            writeln!(f, "{}: {}", part, escape(answer))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The answers for `res/day-N-input` are stored in `res/day-N-input.answers`, or e.g. `res/day-N-input.rpsls.answers`
+/// when the day is run with the variant of its rules named `rpsls`, see [`crate::solution::Solution::variant`]
+pub fn answers_path(input_path: &Path, variant: Option<&str>) -> PathBuf {
+    let mut path = input_path.as_os_str().to_owned();
+    if let Some(variant) = variant {
+        path.push(format!(".{}", variant));
+    }
+    path.push(".answers");
+
+    path.into()
+}
+
+/// Keep each answer on a single line of the answers file
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Reverse [`escape`]
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = answer.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::answers::{answers_path, escape, RecordedAnswers, Status, unescape};
+    use crate::solution::Answer;
+
+    #[test]
+    fn can_escape_answers() {
+        let examples = vec![
+            ("12345", "12345"),
+            ("NDP", "NDP"),
+            ("\n##..\n#..#", "\\n##..\\n#..#"),
+            ("a\\nb", "a\\\\nb"),
+        ];
+
+        for (answer, escaped) in examples {
+            assert_eq!(escape(answer), escaped);
+            assert_eq!(unescape(escaped), answer);
+        }
+    }
+
+    #[test]
+    fn can_check_answers() {
+        let recorded = RecordedAnswers::parse("1: 24000\n2: \\n##\\n..\n").unwrap();
+
+        assert_eq!(recorded.check(1, &Answer::from(24000)), Status::Pass);
+        assert_eq!(recorded.check(1, &Answer::from(24001)), Status::Fail("24000".to_string()));
+        assert_eq!(recorded.check(2, &Answer::from("\n##\n..")), Status::Pass);
+        assert_eq!(RecordedAnswers::default().check(1, &Answer::from(24000)), Status::New);
+    }
+
+    #[test]
+    fn can_record_answers() {
+        let mut recorded = RecordedAnswers::parse("2: 45000\n").unwrap();
+        recorded.record(1, &Answer::from(24000));
+        recorded.record(2, &Answer::from(45001));
+
+        assert_eq!(recorded.to_string(), "1: 24000\n2: 45001\n");
+        assert_eq!(RecordedAnswers::parse(&recorded.to_string()), Ok(recorded));
+    }
+
+    #[test]
+    fn reports_invalid_answers_files() {
+        let error = RecordedAnswers::parse("1: 24000\none: 45000").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "one"));
+    }
+
+    #[test]
+    fn answers_are_stored_next_to_the_input() {
+        assert_eq!(answers_path(Path::new("res/day-7-input"), None), Path::new("res/day-7-input.answers"));
+        assert_eq!(
+            answers_path(Path::new("res/day-2-input"), Some("rpsls")),
+            Path::new("res/day-2-input.rpsls.answers")
+        );
+    }
+}
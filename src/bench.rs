@@ -0,0 +1,307 @@
+//! Benchmarks each day, timing parsing and each part separately over many runs.
+//!
+//! The input is read once, then each day is run a few times to warm up before the timed runs. Each timed run parses
+//! the input afresh and solves the requested parts. The medians can be saved to a baseline file, and later runs
+//! compared with it to flag any stage that has slowed down by more than a threshold.
+//!
+//! A baseline file has one line per day and stage with the median in nanoseconds, e.g. `7 parse 110250`.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::cli::Part;
+use crate::solution::Puzzle;
+use crate::util::parse::{parse_lines, parse_number, ParseError};
+
+/// The steps of running a day that are timed separately
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Ord, PartialOrd)]
+pub enum Stage {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+
+impl Stage {
+    /// The name used for this stage in baseline files
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::PartOne => "part1",
+            Stage::PartTwo => "part2",
+        }
+    }
+}
+
+impl TryFrom<&str> for Stage {
+    type Error = ParseError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name {
+            "parse" => Ok(Stage::Parse),
+            "part1" => Ok(Stage::PartOne),
+            "part2" => Ok(Stage::PartTwo),
+            _ => Err(ParseError::new(name, name, "Expected a stage: parse, part1 or part2")),
+        }
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::PartOne => write!(f, "part 1"),
+            Stage::PartTwo => write!(f, "part 2"),
+        }
+    }
+}
+
+/// Summary statistics for a set of timings
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarise a non-empty set of timings
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let median =
+            if count.is_multiple_of(2) { (sorted[count / 2 - 1] + sorted[count / 2]) / 2 } else { sorted[count / 2] };
+        // Nearest rank, i.e. the smallest sample that at least 95% of the samples are no larger than
+        let p95 = sorted[(count * 95).div_ceil(100) - 1];
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: sorted.iter().sum::<Duration>() / count as u32,
+            p95,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  p95 {:>10.2?}",
+            self.min,
+            self.median,
+            self.mean,
+            self.p95,
+        )
+    }
+}
+
+/// The timings for each stage of a day
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct DayBench {
+    pub day: usize,
+    pub stages: Vec<(Stage, Stats)>,
+}
+
+/// Time parsing and solving a day's input `runs` times, after `warmup` untimed runs
+pub fn bench_day(
+    solution: &dyn Puzzle,
+    contents: &str,
+    part: Part,
+    warmup: usize,
+    runs: usize,
+) -> Result<DayBench, String> {
+    let mut samples: BTreeMap<Stage, Vec<Duration>> = BTreeMap::new();
+
+    for run in 0..(warmup + runs) {
+        let mut timings = Vec::new();
+
+        let start = Instant::now();
+        let parsed = solution.parse(contents).map_err(|err| format!("Failed to parse input: {}", err))?;
+        timings.push((Stage::Parse, start.elapsed()));
+
+        if part.includes_one() {
+            let start = Instant::now();
+            parsed.part_one();
+            timings.push((Stage::PartOne, start.elapsed()));
+        }
+
+        if part.includes_two() && solution.has_part_two() {
+            let start = Instant::now();
+            parsed.part_two();
+            timings.push((Stage::PartTwo, start.elapsed()));
+        }
+
+        if run >= warmup {
+            for (stage, timing) in timings {
+                samples.entry(stage).or_default().push(timing);
+            }
+        }
+    }
+
+    Ok(DayBench {
+        day: solution.day(),
+        stages: samples.into_iter().map(|(stage, timings)| (stage, Stats::from_samples(&timings))).collect(),
+    })
+}
+
+/// The median time for each day and stage from an earlier benchmark
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct Baseline {
+    medians: BTreeMap<(usize, Stage), Duration>,
+}
+
+impl Baseline {
+    /// Take the medians from a set of benchmarks
+    pub fn from_benches(benches: &[DayBench]) -> Baseline {
+        let medians =
+            benches.iter()
+                   .flat_map(|bench| bench.stages.iter().map(|&(stage, stats)| ((bench.day, stage), stats.median)))
+                   .collect();
+
+        Baseline { medians }
+    }
+
+    /// Read a baseline file
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Write the baseline to a file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    fn parse(contents: &str) -> Result<Baseline, ParseError> {
+        let medians = parse_lines(contents, |line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            match fields[..] {
+                [day, stage, nanos] => Ok((
+                    (parse_number(line, day)?, Stage::try_from(stage).map_err(|err| err.within(line, stage))?),
+                    Duration::from_nanos(parse_number(line, nanos)?),
+                )),
+                _ => Err(ParseError::new(line, line, "Expected a day, stage and time e.g. '7 parse 110250'")),
+            }
+        })?;
+
+        Ok(Baseline { medians: medians.into_iter().collect() })
+    }
+
+    /// How much slower, as a percentage, the median for a stage is than the baseline. Negative if it is faster.
+    pub fn change(&self, day: usize, stage: Stage, median: Duration) -> Option<f64> {
+        self.medians
+            .get(&(day, stage))
+            .filter(|baseline| !baseline.is_zero())
+            .map(|baseline| (median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for ((day, stage), median) in &self.medians {
+            writeln!(f, "{} {} {}", day, stage.name(), median.as_nanos())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The lines to print for a day's benchmark, comparing with the baseline if there is one. Also returns the number of
+/// stages that have regressed by more than `threshold` percent.
+pub fn describe(bench: &DayBench, baseline: Option<&Baseline>, threshold: u32) -> (Vec<String>, usize) {
+    let mut regressions = 0;
+    let lines = bench.stages.iter().map(|&(stage, stats)| {
+        let line = format!("{:<6}  {}", stage.to_string(), stats);
+
+        match baseline.and_then(|baseline| baseline.change(bench.day, stage, stats.median)) {
+            Some(change) if change > threshold as f64 => {
+                regressions += 1;
+                format!("{}  {:+.1}% REGRESSION", line, change)
+            }
+            Some(change) => format!("{}  {:+.1}%", line, change),
+            None => line,
+        }
+    }).collect();
+
+    (lines, regressions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::bench::{Baseline, bench_day, DayBench, describe, Stage, Stats};
+    use crate::cli::Part;
+    use crate::day_1::Day1;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&value| Duration::from_micros(value)).collect()
+    }
+
+    #[test]
+    fn can_summarise_samples() {
+        assert_eq!(
+            Stats::from_samples(&micros(&[5, 1, 4, 2, 3])),
+            Stats {
+                min: Duration::from_micros(1),
+                median: Duration::from_micros(3),
+                mean: Duration::from_micros(3),
+                p95: Duration::from_micros(5),
+            }
+        );
+
+        let hundred: Vec<u64> = (1..=100).rev().collect();
+        let stats = Stats::from_samples(&micros(&hundred));
+        assert_eq!(stats.median, Duration::from_nanos(50_500));
+        assert_eq!(stats.p95, Duration::from_micros(95));
+    }
+
+    #[test]
+    fn can_bench_a_day() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
+        let bench = bench_day(&Day1, input, Part::Both, 1, 5).unwrap();
+        let stages: Vec<Stage> = bench.stages.iter().map(|&(stage, _)| stage).collect();
+        assert_eq!(bench.day, 1);
+        assert_eq!(stages, vec![Stage::Parse, Stage::PartOne, Stage::PartTwo]);
+
+        assert_eq!(bench_day(&Day1, input, Part::Two, 0, 1).unwrap().stages.len(), 2);
+        assert!(bench_day(&Day1, "x", Part::Both, 0, 1).is_err());
+    }
+
+    fn sample_bench(parse: u64, part_one: u64) -> DayBench {
+        let stats = |value: u64| Stats::from_samples(&micros(&[value]));
+        DayBench { day: 7, stages: vec![(Stage::Parse, stats(parse)), (Stage::PartOne, stats(part_one))] }
+    }
+
+    #[test]
+    fn can_save_and_load_baselines() {
+        let baseline = Baseline::from_benches(&[sample_bench(100, 250)]);
+
+        assert_eq!(baseline.to_string(), "7 parse 100000\n7 part1 250000\n");
+        assert_eq!(Baseline::parse(&baseline.to_string()), Ok(baseline));
+
+        let error = Baseline::parse("7 parse 100000\n7 part3 250000").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "part3"));
+    }
+
+    #[test]
+    fn can_flag_regressions() {
+        let baseline = Baseline::from_benches(&[sample_bench(100, 250)]);
+
+        let (lines, regressions) = describe(&sample_bench(105, 300), Some(&baseline), 10);
+        assert_eq!(regressions, 1);
+        assert!(lines[0].ends_with("+5.0%"));
+        assert!(lines[1].ends_with("+20.0% REGRESSION"));
+
+        let (lines, regressions) = describe(&sample_bench(50, 300), None, 10);
+        assert_eq!(regressions, 0);
+        assert!(lines[1].ends_with("300.00µs"));
+    }
+}
@@ -0,0 +1,448 @@
+//! Parses the command-line arguments for the solution runner.
+//!
+//! The runner can be driven non-interactively, e.g. `advent-of-code-2022 1,5-7 --part 2`. If no arguments are given,
+//! the runner binary falls back to prompting for a day on stdin.
+
+use std::path::PathBuf;
+use crate::report::Format;
+
+/// The help text printed for `--help` or when the arguments can't be understood
+pub const USAGE: &str = "Usage: advent-of-code-2022 [OPTIONS] [DAYS]
+
+Arguments:
+  [DAYS]               Days to run: a single day `7`, a list `1,3,5`, a range `10-15`, a mix of these, or `all`
+
+Options:
+  -p, --part <PART>    Which part(s) to run: `1`, `2` or `both` [default: both]
+  -i, --input <FILE>   Read the puzzle input from FILE, or stdin if FILE is `-` (single day only)
+  -n, --name <NAME>    Use the named inputs, e.g. `day-N-input.NAME` rather than `day-N-input`
+  -c, --check          Compare the answers with those recorded next to the input, exits with 1 if any have changed
+  -r, --record         Record the answers next to the input for future checks
+  -j, --jobs <N>       Run up to N days in parallel, `0` uses one thread per CPU [default: 1]
+  -b, --bench <RUNS>   Benchmark each day over RUNS runs, timing parsing and each part separately
+      --warmup <RUNS>  Untimed runs before benchmarking [default: 3]
+      --baseline <FILE>
+                       Compare the benchmark with a baseline, flagging stages that are slower than the threshold
+      --save-baseline <FILE>
+                       Save the benchmark's medians as a baseline
+      --threshold <PERCENT>
+                       How much slower than the baseline counts as a regression, up to 1000 [default: 10]
+      --report <FORMAT>  Print a report with a record per day and part instead: `json` or `csv`
+  -o, --report-file <FILE>
+                       Write the report to FILE rather than stdout, in JSON unless `--report csv` is given
+  -h, --help           Print this help
+
+Inputs are read from `--input`, then `$AOC_INPUT_DIR/day-N-input` if that file exists, then the cache in
+`$AOC_CACHE_DIR` (default `.cache/`), then `res/day-N-input`. Missing inputs are downloaded into the cache when
+`$AOC_SESSION` or `$AOC_SESSION_FILE` provides a session token, from `$AOC_BASE_URL/day/N/input`.
+`$AOC_DAY_2_RULES` can name a file of rules for day 2 to use instead of rock, paper, scissors.";
+
+/// The largest percentage accepted by options such as `--threshold`
+const MAX_PERCENTAGE: u32 = 1000;
+
+/// Which of the two parts of each day's puzzle should be run
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    /// Should part 1 be run?
+    pub fn includes_one(&self) -> bool {
+        matches!(self, Part::One | Part::Both)
+    }
+
+    /// Should part 2 be run?
+    pub fn includes_two(&self) -> bool {
+        matches!(self, Part::Two | Part::Both)
+    }
+}
+
+impl TryFrom<&str> for Part {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            other => Err(format!("Invalid part '{}', expected 1, 2 or both", other)),
+        }
+    }
+}
+
+/// How the answers should be treated once they have been found
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Mode {
+    /// Just print the answers
+    Solve,
+    /// Compare the answers with those previously recorded
+    Check,
+    /// Save the answers for future checks
+    Record,
+}
+
+/// How to benchmark the days
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Bench {
+    /// The number of timed runs
+    pub runs: usize,
+    /// The number of untimed runs before the timed runs
+    pub warmup: usize,
+    /// A baseline to compare the results with
+    pub baseline: Option<PathBuf>,
+    /// Where to save the results as a new baseline
+    pub save_baseline: Option<PathBuf>,
+    /// The percentage slower than the baseline that is reported as a regression
+    pub threshold: u32,
+}
+
+/// What the user has asked the runner to do
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Options {
+    /// The days to run, in the order they should be run
+    pub days: Vec<usize>,
+    /// The part(s) of each day to run
+    pub part: Part,
+    /// Override the default location of the puzzle input
+    pub input: Option<PathBuf>,
+    /// Which of several inputs for each day to use
+    pub name: Option<String>,
+    /// What to do with the answers
+    pub mode: Mode,
+    /// How many days to run in parallel, `0` to pick based on the number of CPUs
+    pub jobs: usize,
+    /// Benchmark the days rather than just solving them
+    pub bench: Option<Bench>,
+    /// The format of the machine-readable report, if one was requested
+    pub report: Option<Format>,
+    /// Where to write the report, `None` for stdout
+    pub report_file: Option<PathBuf>,
+}
+
+impl Options {
+    /// The options equivalent to the interactive prompt: `0` runs all days, anything else is a single day.
+    pub fn for_prompted_day(day: usize) -> Result<Self, String> {
+        let days = if day == 0 { all_days() } else { parse_days(&day.to_string())? };
+
+        Ok(Options {
+            days,
+            part: Part::Both,
+            input: None,
+            name: None,
+            mode: Mode::Solve,
+            jobs: 1,
+            bench: None,
+            report: None,
+            report_file: None,
+        })
+    }
+}
+
+/// All the days that have solutions
+fn all_days() -> Vec<usize> {
+    (1..=25).collect()
+}
+
+/// Parse the program arguments (excluding the program name).
+///
+/// - `Ok(None)` means no arguments were given, and the caller should fall back to prompting.
+/// - `Err` holds a message explaining what was wrong, `--help` is also reported this way with the usage text.
+pub fn parse_args<I>(args: I) -> Result<Option<Options>, String>
+    where I: IntoIterator<Item = String>
+{
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_none() {
+        return Ok(None);
+    }
+
+    let mut days = None;
+    let mut part = Part::Both;
+    let mut input = None;
+    let mut name = None;
+    let mut mode = Mode::Solve;
+    let mut jobs = 1;
+    let mut bench_runs = None;
+    let mut warmup = None;
+    let mut baseline = None;
+    let mut save_baseline = None;
+    let mut threshold = None;
+    let mut report = None;
+    let mut report_file = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            "-p" | "--part" => part = Part::try_from(expect_value(&arg, args.next())?.as_str())?,
+            "-i" | "--input" => input = Some(PathBuf::from(expect_value(&arg, args.next())?)),
+            "-n" | "--name" => name = Some(expect_value(&arg, args.next())?),
+            "-c" | "--check" => mode = set_mode(mode, Mode::Check)?,
+            "-r" | "--record" => mode = set_mode(mode, Mode::Record)?,
+            "-j" | "--jobs" => jobs = parse_count(&arg, &expect_value(&arg, args.next())?)?,
+            "-b" | "--bench" => bench_runs = Some(parse_count(&arg, &expect_value(&arg, args.next())?)?),
+            "--warmup" => warmup = Some(parse_count(&arg, &expect_value(&arg, args.next())?)?),
+            "--baseline" => baseline = Some(PathBuf::from(expect_value(&arg, args.next())?)),
+            "--save-baseline" => save_baseline = Some(PathBuf::from(expect_value(&arg, args.next())?)),
+            "--threshold" => threshold = Some(parse_percentage(&arg, &expect_value(&arg, args.next())?)?),
+            "--report" => report = Some(Format::try_from(expect_value(&arg, args.next())?.as_str())?),
+            "-o" | "--report-file" => report_file = Some(PathBuf::from(expect_value(&arg, args.next())?)),
+            flag if flag.starts_with('-') => return Err(format!("Unknown option '{}'", flag)),
+            spec if days.is_none() => days = Some(parse_days(spec)?),
+            spec => return Err(format!("Unexpected argument '{}', days have already been given", spec)),
+        }
+    }
+
+    let days = days.unwrap_or_else(all_days);
+
+    if input.is_some() && days.len() != 1 {
+        return Err("--input can only be used when running a single day".to_string());
+    }
+
+    if report_file.is_some() && report.is_none() {
+        report = Some(Format::Json);
+    }
+
+    let bench = match bench_runs {
+        Some(0) => return Err("--bench needs at least one run".to_string()),
+        Some(_) if mode != Mode::Solve => return Err("--bench can't be used with --check or --record".to_string()),
+        Some(_) if report.is_some() => return Err("--bench can't be used with --report or --report-file".to_string()),
+        Some(runs) => Some(Bench {
+            runs,
+            warmup: warmup.unwrap_or(3),
+            baseline,
+            save_baseline,
+            threshold: threshold.unwrap_or(10),
+        }),
+        None if warmup.is_some() || baseline.is_some() || save_baseline.is_some() || threshold.is_some() => {
+            return Err("--warmup, --baseline, --save-baseline and --threshold need --bench".to_string());
+        }
+        None => None,
+    };
+
+    Ok(Some(Options { days, part, input, name, mode, jobs, bench, report, report_file }))
+}
+
+/// Only one of the modes can be chosen
+fn set_mode(current: Mode, new: Mode) -> Result<Mode, String> {
+    match current {
+        Mode::Solve => Ok(new),
+        _ if current == new => Ok(new),
+        _ => Err("--check and --record can't be used together".to_string()),
+    }
+}
+
+/// Options that take a value need to be followed by another argument
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("Option '{}' requires a value", flag))
+}
+
+/// Parse the value of an option that is a count, e.g. the number of threads to run days on
+fn parse_count(flag: &str, spec: &str) -> Result<usize, String> {
+    spec.parse::<usize>().map_err(|_| format!("Invalid value '{}' for '{}', expected a number", spec, flag))
+}
+
+/// Parse the value of an option that is a whole percentage, e.g. the benchmark regression threshold
+fn parse_percentage(flag: &str, spec: &str) -> Result<u32, String> {
+    match spec.parse::<u32>() {
+        Ok(percent) if percent <= MAX_PERCENTAGE => Ok(percent),
+        _ => Err(format!(
+            "Invalid value '{}' for '{}', expected a percentage from 0 to {}", spec, flag, MAX_PERCENTAGE
+        )),
+    }
+}
+
+/// Parse a day selection such as `7`, `1,3,5`, `10-15`, `1,4-6` or `all`
+fn parse_days(spec: &str) -> Result<Vec<usize>, String> {
+    if spec == "all" || spec == "0" {
+        return Ok(all_days());
+    }
+
+    let mut days = Vec::new();
+    for part in spec.split(',') {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse_day(start)?, parse_day(end)?),
+            None => {
+                let day = parse_day(part)?;
+                (day, day)
+            }
+        };
+
+        if start > end {
+            return Err(format!("Invalid day range '{}'", part));
+        }
+
+        for day in start..=end {
+            if !days.contains(&day) {
+                days.push(day)
+            }
+        }
+    }
+
+    Ok(days)
+}
+
+/// Parse and validate a single day number
+fn parse_day(spec: &str) -> Result<usize, String> {
+    match spec.trim().parse::<usize>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid Day '{}', expected a number from 1 to 25", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::cli::{Bench, Mode, Options, Part, parse_args, parse_days};
+    use crate::report::Format;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn can_parse_days() {
+        assert_eq!(parse_days("7"), Ok(vec![7]));
+        assert_eq!(parse_days("1,3,5"), Ok(vec![1, 3, 5]));
+        assert_eq!(parse_days("10-13"), Ok(vec![10, 11, 12, 13]));
+        assert_eq!(parse_days("1,4-6,5,25"), Ok(vec![1, 4, 5, 6, 25]));
+        assert_eq!(parse_days("all"), Ok((1..=25).collect()));
+        assert_eq!(parse_days("0"), Ok((1..=25).collect()));
+
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("6-4").is_err());
+        assert!(parse_days("three").is_err());
+        assert!(parse_days("1,,2").is_err());
+    }
+
+    #[test]
+    fn falls_back_to_prompt_without_args() {
+        assert_eq!(parse_args(args(&[])), Ok(None));
+    }
+
+    #[test]
+    fn can_parse_args() {
+        assert_eq!(
+            parse_args(args(&["7"])),
+            Ok(Some(Options {
+                days: vec![7],
+                part: Part::Both,
+                input: None,
+                name: None,
+                mode: Mode::Solve,
+                jobs: 1,
+                bench: None,
+                report: None,
+                report_file: None,
+            }))
+        );
+
+        assert_eq!(
+            parse_args(args(&["--part", "2", "1-3", "--name", "alice"])),
+            Ok(Some(Options {
+                days: vec![1, 2, 3],
+                part: Part::Two,
+                input: None,
+                name: Some("alice".to_string()),
+                mode: Mode::Solve,
+                jobs: 1,
+                bench: None,
+                report: None,
+                report_file: None,
+            }))
+        );
+
+        assert_eq!(
+            parse_args(args(&["12", "-p", "1", "-i", "other/input.txt"])),
+            Ok(Some(Options {
+                days: vec![12],
+                part: Part::One,
+                input: Some(PathBuf::from("other/input.txt")),
+                name: None,
+                mode: Mode::Solve,
+                jobs: 1,
+                bench: None,
+                report: None,
+                report_file: None,
+            }))
+        );
+
+        assert_eq!(
+            parse_args(args(&["-p", "both"])),
+            Ok(Some(Options {
+                days: (1..=25).collect(),
+                part: Part::Both,
+                input: None,
+                name: None,
+                mode: Mode::Solve,
+                jobs: 1,
+                bench: None,
+                report: None,
+                report_file: None,
+            }))
+        );
+
+        assert_eq!(parse_args(args(&["all", "--check"])).map(|options| options.unwrap().mode), Ok(Mode::Check));
+        assert_eq!(parse_args(args(&["-r", "3"])).map(|options| options.unwrap().mode), Ok(Mode::Record));
+        assert_eq!(parse_args(args(&["all", "-j", "4"])).map(|options| options.unwrap().jobs), Ok(4));
+
+        assert_eq!(
+            parse_args(args(&["16", "--bench", "20"])).map(|options| options.unwrap().bench),
+            Ok(Some(Bench { runs: 20, warmup: 3, baseline: None, save_baseline: None, threshold: 10 }))
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "-b", "5", "--warmup", "0", "--baseline", "old", "--save-baseline", "new", "--threshold", "25",
+            ])).map(|options| options.unwrap().bench),
+            Ok(Some(Bench {
+                runs: 5,
+                warmup: 0,
+                baseline: Some(PathBuf::from("old")),
+                save_baseline: Some(PathBuf::from("new")),
+                threshold: 25,
+            }))
+        );
+
+        let report = |list: &[&str]| parse_args(args(list)).map(|options| {
+            let options = options.unwrap();
+            (options.report, options.report_file)
+        });
+        assert_eq!(report(&["--report", "csv"]), Ok((Some(Format::Csv), None)));
+        assert_eq!(report(&["-o", "report.json"]), Ok((Some(Format::Json), Some(PathBuf::from("report.json")))));
+        assert_eq!(
+            report(&["--report", "csv", "-o", "report.csv"]),
+            Ok((Some(Format::Csv), Some(PathBuf::from("report.csv"))))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_args() {
+        assert!(parse_args(args(&["--help"])).is_err());
+        assert!(parse_args(args(&["--part", "3"])).is_err());
+        assert!(parse_args(args(&["--part"])).is_err());
+        assert!(parse_args(args(&["--unknown"])).is_err());
+        assert!(parse_args(args(&["1", "2"])).is_err());
+        assert!(parse_args(args(&["1-3", "--input", "file"])).is_err());
+        assert!(parse_args(args(&["--check", "--record"])).is_err());
+        assert!(parse_args(args(&["--name"])).is_err());
+        assert!(parse_args(args(&["--report", "xml"])).is_err());
+        assert!(parse_args(args(&["--jobs", "many"])).is_err());
+        assert!(parse_args(args(&["--bench", "0"])).is_err());
+        assert!(parse_args(args(&["--warmup", "5"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "--check"])).is_err());
+        assert!(parse_args(args(&["--record", "--bench", "5"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "--report", "csv"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "-o", "report.json"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "--threshold", "-5"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "--threshold", "1001"])).is_err());
+        assert!(parse_args(args(&["--bench", "5", "--threshold", "4294967306"])).is_err());
+    }
+
+    #[test]
+    fn can_build_prompted_options() {
+        assert_eq!(Options::for_prompted_day(0).unwrap().days, (1..=25).collect::<Vec<usize>>());
+        assert_eq!(Options::for_prompted_day(4).unwrap().days, vec![4]);
+        assert!(Options::for_prompted_day(42).is_err());
+    }
+}
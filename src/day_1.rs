@@ -2,81 +2,221 @@
 //!
 //! The task is to sum all the calories carried per elf on our expedition and find those that are carrying the most.
 
-use std::fs;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, BufRead, BufReader, Read};
+use itertools::process_results;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::ParseError;
 
-/// An elf represented by the total calories in their combined food items
-type CalorieTotal = u32;
+/// The total calories in an elf's combined food items
+pub type CalorieTotal = u64;
+
+/// An elf, by their position in the expedition, counting from 0, and the total calories they are carrying
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Elf {
+    pub index: usize,
+    pub calories: CalorieTotal,
+}
 
 /// The whole expedition's supplies: a list of Elves' calorie totals
-type Expedition = Vec<CalorieTotal>;
+pub type Expedition = Vec<Elf>;
+
+/// Why an expedition couldn't be read from a stream
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl Display for ReadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "Failed to read the expedition: {}", err),
+            ReadError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
 
+impl Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(err: ParseError) -> Self {
+        ReadError::Parse(err)
+    }
+}
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-1-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 1.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-1-input").expect("Failed to read file");
-    let elf_calorie_totals = parse_input(&contents);
-    let (first, second, third) = find_top_three_calorie_totals(&elf_calorie_totals);
-
-    println!(
-        "The most calories carried by one elf is: {}",
-        first
-    );
-
-    println!(
-        "The total calories carried by the top three elves is: {}",
-        first + second + third
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-1-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 1.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Parsed = Expedition;
+    const DAY: usize = 1;
+
+    fn parse(&self, input: &str) -> Result<Expedition, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, expedition: &Expedition) -> Answer {
+        sum_top_calorie_totals(expedition, 1).into()
+    }
+
+    fn part_two(&self, expedition: &Expedition) -> Answer {
+        sum_top_calorie_totals(expedition, 3).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The most calories carried by one elf is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The total calories carried by the top three elves is: {}", answer)
+    }
+}
+
+/// Turn the input text file into a list of elves by their total carried calories. Elves are separated by blank lines.
+pub fn parse_input(input: &str) -> Result<Expedition, ParseError> {
+    calorie_totals(input.as_bytes())
+        .collect::<Result<Expedition, ReadError>>()
+        .map_err(|err| into_parse_error(input, err))
 }
 
-/// Turn the input text file into a list of elves by their total carried calories
-fn parse_input(input: &String) -> Expedition {
-    let mut expedition = Vec::new();
-    let mut current_calorie_total = 0;
-
-    input.lines().for_each(
-        |line| match line.parse::<u32>() {
-            Ok(calories) => current_calorie_total = current_calorie_total + calories,
-            Err(_) => {
-                expedition.push(current_calorie_total);
-                current_calorie_total = 0;
+/// Reading from a string can only fail to parse, but report any other error against the whole input
+fn into_parse_error(input: &str, err: ReadError) -> ParseError {
+    match err {
+        ReadError::Parse(err) => err,
+        ReadError::Io(err) => ParseError::new(input, input, err.to_string()),
+    }
+}
+
+/// Streams the elves from a reader a line at a time, so that the input never needs to be held in memory
+pub struct CalorieTotals<R> {
+    reader: BufReader<R>,
+    /// Re-used for each line read
+    line: String,
+    line_number: usize,
+    next_index: usize,
+}
+
+/// Read the elves from a stream with one food item per line, and blank lines between elves
+pub fn calorie_totals<R: Read>(reader: R) -> CalorieTotals<R> {
+    CalorieTotals { reader: BufReader::new(reader), line: String::new(), line_number: 0, next_index: 0 }
+}
+
+impl<R: Read> Iterator for CalorieTotals<R> {
+    type Item = Result<Elf, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut calories: Option<CalorieTotal> = None;
+
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => break,
+                Ok(_) => self.line_number += 1,
+                Err(err) => return Some(Err(err.into())),
+            }
+
+            let item = self.line.trim_end_matches(['\n', '\r']);
+            if item.is_empty() {
+                // Extra blank lines between elves are skipped
+                if calories.is_some() { break } else { continue }
+            }
+
+            let error = |message: &str| Some(Err(ParseError::at(self.line_number, 1, item, message).into()));
+            let Ok(item_calories) = item.trim().parse::<CalorieTotal>() else {
+                return error("Expected a number");
+            };
+
+            match calories.unwrap_or(0).checked_add(item_calories) {
+                Some(total) => calories = Some(total),
+                None => return error("Expected the elf's total to fit in a u64"),
             }
         }
-    );
 
-    if current_calorie_total > 0 {
-        expedition.push(current_calorie_total)
+        calories.map(|calories| {
+            self.next_index += 1;
+            Ok(Elf { index: self.next_index - 1, calories })
+        })
+    }
+}
+
+/// Find the `k` elves carrying the most calories, most first. Ties go to the elf that came first. Only `k` elves are
+/// kept at a time, so this works on streams of any length.
+pub fn top_calorie_totals<I: IntoIterator<Item=Elf>>(elves: I, k: usize) -> Expedition {
+    // A min-heap of the best so far, so the worst of them can be dropped when a better elf is found
+    let mut top: BinaryHeap<Reverse<(CalorieTotal, Reverse<usize>)>> = BinaryHeap::with_capacity(k + 1);
+
+    for elf in elves {
+        top.push(Reverse((elf.calories, Reverse(elf.index))));
+        if top.len() > k {
+            top.pop();
+        }
     }
 
-    expedition
+    top.into_sorted_vec()
+       .into_iter()
+       .map(|Reverse((calories, Reverse(index)))| Elf { index, calories })
+       .collect()
 }
 
-/// Extract the calorie totals of  the three elves with the most food.
-fn find_top_three_calorie_totals(expedition: &Expedition) -> (CalorieTotal, CalorieTotal, CalorieTotal) {
-    expedition.iter().fold((0, 0, 0), bubble_calorie_total_into_top_three)
+/// The total calories carried by the `k` elves carrying the most
+pub fn sum_top_calorie_totals(expedition: &Expedition, k: usize) -> CalorieTotal {
+    top_calorie_totals(expedition.iter().copied(), k).iter().map(|elf| elf.calories).sum()
 }
 
-/// Insert the next elf into place in the current top three if its calorie total is high enough
-fn bubble_calorie_total_into_top_three(
-    top_3: (CalorieTotal, CalorieTotal, CalorieTotal),
-    &next_elf: &CalorieTotal
-) -> (CalorieTotal, CalorieTotal, CalorieTotal) {
-    match top_3 {
-        (a, b, _) if a < next_elf => (next_elf, a, b),
-        (a, b, _) if b < next_elf => (a, next_elf, b),
-        (a, b, c) if c < next_elf => (a, b, next_elf),
-        _ => top_3
-    }
+/// Stream the elves from a reader and find the `k` carrying the most calories, see [`top_calorie_totals`]
+pub fn read_top_calorie_totals<R: Read>(reader: R, k: usize) -> Result<Expedition, ReadError> {
+    process_results(calorie_totals(reader), |elves| top_calorie_totals(elves, k))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_1::{Expedition, find_top_three_calorie_totals, parse_input};
+    use std::io::{self, Read};
+    use crate::day_1::{
+        calorie_totals, CalorieTotal, Elf, Expedition, parse_input, read_top_calorie_totals, ReadError,
+        sum_top_calorie_totals, top_calorie_totals,
+    };
+
+    fn elves(calories: &[CalorieTotal]) -> Expedition {
+        calories.iter().enumerate().map(|(index, &calories)| Elf { index, calories }).collect()
+    }
 
     fn sample_expedition() -> Expedition {
-        vec![6000, 4000, 11000, 24000, 10000]
+        elves(&[6000, 4000, 11000, 24000, 10000])
+    }
+
+    /// Generates an expedition a line at a time, so that it never needs to be held in memory
+    struct GeneratedExpedition {
+        next_elf: usize,
+        elves: usize,
+        pending: Vec<u8>,
+    }
+
+    impl Read for GeneratedExpedition {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.pending.is_empty() && self.next_elf < self.elves {
+                self.pending = format!("{}\n1\n\n", self.next_elf * 7919 % 100_003).into_bytes();
+                self.next_elf += 1;
+            }
+
+            let read = buf.len().min(self.pending.len());
+            buf[..read].copy_from_slice(&self.pending[..read]);
+            self.pending.drain(..read);
+
+            Ok(read)
+        }
     }
 
     #[test]
@@ -96,11 +236,64 @@ mod tests {
 
 10000".to_string();
 
-        assert_eq!(parse_input(&input), sample_expedition());
+        assert_eq!(parse_input(&input), Ok(sample_expedition()));
+        assert_eq!(parse_input("\n1\n\n\n\n2\r\n3\n\n"), Ok(elves(&[1, 5])));
     }
 
     #[test]
-    fn can_find_top_three_calories() {
-        assert_eq!(find_top_three_calorie_totals(&sample_expedition()), (24000, 11000, 10000))
+    fn reports_invalid_calories() {
+        let error = parse_input("1000\n2000\n\n3O00").unwrap_err();
+
+        assert_eq!((error.line, error.column, error.text.as_str()), (4, 1, "3O00"));
+
+        match read_top_calorie_totals("1\n\n2\nx".as_bytes(), 3) {
+            Err(ReadError::Parse(error)) => assert_eq!((error.line, error.text.as_str()), (4, "x")),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn can_find_top_calories() {
+        let top = |k| top_calorie_totals(sample_expedition(), k);
+
+        assert_eq!(
+            top(3),
+            vec![
+                Elf { index: 3, calories: 24000 },
+                Elf { index: 2, calories: 11000 },
+                Elf { index: 4, calories: 10000 },
+            ]
+        );
+        assert_eq!(top(1), vec![Elf { index: 3, calories: 24000 }]);
+        assert_eq!(top(0), vec![]);
+        assert_eq!(top(10).len(), 5);
+
+        let tied = top_calorie_totals(elves(&[5, 7, 5, 7, 5]), 3);
+        assert_eq!(tied.iter().map(|elf| elf.index).collect::<Vec<_>>(), vec![1, 3, 0]);
+
+        assert_eq!(sum_top_calorie_totals(&sample_expedition(), 1), 24000);
+        assert_eq!(sum_top_calorie_totals(&sample_expedition(), 3), 45000);
+    }
+
+    #[test]
+    fn handles_large_totals() {
+        let top = read_top_calorie_totals("4000000000\n1000000000\n\n1".as_bytes(), 1).unwrap();
+        assert_eq!(top, vec![Elf { index: 0, calories: 5_000_000_000 }]);
+
+        let error = parse_input("1\n\n18446744073709551615\n1").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (4, "1"));
+        assert_eq!(error.message, "Expected the elf's total to fit in a u64");
+    }
+
+    #[test]
+    fn can_stream_large_expeditions() {
+        let generate = || GeneratedExpedition { next_elf: 0, elves: 200_000, pending: Vec::new() };
+
+        let top = read_top_calorie_totals(generate(), 3).unwrap();
+
+        let mut all: Expedition = calorie_totals(generate()).map(Result::unwrap).collect();
+        all.sort_by_key(|elf| std::cmp::Reverse(elf.calories));
+        assert_eq!(all.len(), 200_000);
+        assert_eq!(top, all[..3]);
     }
 }
@@ -2,55 +2,65 @@
 //!
 //! Interpret a set of instructions into pixels on a display
 
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError};
 use itertools::Itertools;
 use crate::day_10::Instruction::{ADDX, NOOP};
 
 /// Represent the two possible instruction types that the puzzle input can contain
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     ADDX(isize),
     NOOP,
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-10-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 10.
-pub fn run() {
-    let contents =
-        fs::read_to_string("res/day-10-input").expect("Failed to read file");
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-10-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 10.
+pub struct Day10;
 
-    let instructions = parse_input(&contents);
+impl Solution for Day10 {
+    type Parsed = Vec<Instruction>;
+    const DAY: usize = 10;
 
-    println!(
-        "The sum of sampled signal strengths is: {}",
-        sample_and_sum_signal_strength(&instructions)
-    );
+    fn parse(&self, input: &str) -> Result<Vec<Instruction>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, instructions: &Vec<Instruction>) -> Answer {
+        sample_and_sum_signal_strength(instructions).into()
+    }
+
+    fn part_two(&self, instructions: &Vec<Instruction>) -> Answer {
+        draw_pixels(instructions).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sum of sampled signal strengths is: {}", answer)
+    }
 
-    println!(
-        "The screen shows: \n{}",
-        draw_pixels(&instructions)
-    );
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The screen shows: \n{}", answer)
+    }
 }
 
 /// Parse the puzzle input string
-fn parse_input(input: &String) -> Vec<Instruction> {
-    input.lines().map(parse_instruction).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse_lines(input, parse_instruction)
 }
 
 /// Parse a line of the input to a signal
-fn parse_instruction(line: &str) -> Instruction {
-    if line.starts_with("addx") {
-        let (_, value) = line.split_once(" ").unwrap();
-        ADDX(value.parse::<isize>().unwrap())
-    } else {
-        NOOP
+pub fn parse_instruction(line: &str) -> Result<Instruction, ParseError> {
+    match line.strip_prefix("addx ") {
+        Some(value) => Ok(ADDX(parse_number(line, value)?)),
+        None if line == "noop" => Ok(NOOP),
+        None => Err(ParseError::new(line, line, "Expected an instruction: 'addx V' or 'noop'"))
     }
 }
 
 /// Interpret the instruction list into the signals sent to the display
-fn to_signals(instructions: &Vec<Instruction>) -> Vec<isize> {
+pub fn to_signals(instructions: &Vec<Instruction>) -> Vec<isize> {
     let mut register = 1;
     let mut signals = Vec::new();
     for &instruction in instructions {
@@ -68,7 +78,7 @@ fn to_signals(instructions: &Vec<Instruction>) -> Vec<isize> {
 }
 
 /// Take specific signals and sum them
-fn sample_and_sum_signal_strength(instructions: &Vec<Instruction>) -> isize {
+pub fn sample_and_sum_signal_strength(instructions: &Vec<Instruction>) -> isize {
     to_signals(instructions)
         .iter()
         .enumerate()
@@ -81,7 +91,7 @@ fn sample_and_sum_signal_strength(instructions: &Vec<Instruction>) -> isize {
 
 /// Interpret the signals as controlling a "sprite" that will cause a pixel to be lit if the sprite overlaps whilst the
 /// pixel is drawn.
-fn draw_pixels(instructions: &Vec<Instruction>) -> String {
+pub fn draw_pixels(instructions: &Vec<Instruction>) -> String {
     let mut lines = String::new();
 
     for (i, &signal) in to_signals(instructions).iter().enumerate() {
@@ -111,7 +121,7 @@ mod tests {
 addx 3
 addx -5".to_string();
 
-        assert_eq!(parse_input(&input), vec![NOOP, ADDX(3), ADDX(-5)])
+        assert_eq!(parse_input(&input), Ok(vec![NOOP, ADDX(3), ADDX(-5)]))
     }
 
     #[test]
@@ -290,6 +300,15 @@ noop
 noop
 noop".to_string();
 
-        parse_input(&input)
+        parse_input(&input).unwrap()
+    }
+
+    #[test]
+    fn reports_invalid_instructions() {
+        let bad_value = parse_input("noop\naddx 3\naddx five").unwrap_err();
+        assert_eq!((bad_value.line, bad_value.column, bad_value.text.as_str()), (3, 6, "five"));
+
+        let bad_instruction = parse_input("noop\nmulx 3").unwrap_err();
+        assert_eq!((bad_instruction.line, bad_instruction.column, bad_instruction.text.as_str()), (2, 1, "mulx 3"));
     }
 }
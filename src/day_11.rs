@@ -2,23 +2,27 @@
 //!
 //!
 
-use std::fs;
+use std::str::Lines;
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 use crate::day_11::Operand::Value;
 use crate::day_11::Operation::{Add, Mul};
+use crate::util::parse::{parse_number, ParseError};
 
 /// Represent an operand that can either be the old worry value or a fixed number
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Operand {
+pub enum Operand {
     Value(isize),
     Old,
 }
 
-impl From<&str> for Operand {
-    fn from(spec: &str) -> Self {
+impl TryFrom<&str> for Operand {
+    type Error = ParseError;
+
+    fn try_from(spec: &str) -> Result<Self, Self::Error> {
         match spec {
-            "old" => Operand::Old,
-            i => Value(i.parse().unwrap())
+            "old" => Ok(Operand::Old),
+            i => Ok(Value(parse_number(spec, i)?))
         }
     }
 }
@@ -35,22 +39,26 @@ impl Operand {
 
 /// Represent an update operation for an item's worry level
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Operation {
+pub enum Operation {
     Mul(Operand, Operand),
     Add(Operand, Operand),
 }
 
-impl From<&str> for Operation {
-    fn from(spec: &str) -> Self {
-        let mut parts = spec.split_whitespace();
-        let a = parts.next().unwrap().into();
-        let op = parts.next().unwrap();
-        let b = parts.next().unwrap().into();
+impl TryFrom<&str> for Operation {
+    type Error = ParseError;
+
+    fn try_from(spec: &str) -> Result<Self, Self::Error> {
+        let parts: Vec<&str> = spec.split_whitespace().collect();
+        let &[a, op, b] = &parts[..] else {
+            return Err(ParseError::new(spec, spec, "Expected an operation e.g. 'old * 19'"));
+        };
+
+        let operand = |part: &str| Operand::try_from(part).map_err(|err| err.within(spec, part));
 
         match op {
-            "+" => Add(a, b),
-            "*" => Mul(a, b),
-            _ => unreachable!()
+            "+" => Ok(Add(operand(a)?, operand(b)?)),
+            "*" => Ok(Mul(operand(a)?, operand(b)?)),
+            _ => Err(ParseError::new(spec, op, "Expected an operator: + or *"))
         }
     }
 }
@@ -66,10 +74,10 @@ impl Operation {
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-struct Test {
-    divisor: isize,
-    if_true: usize,
-    if_false: usize,
+pub struct Test {
+    pub divisor: isize,
+    pub if_true: usize,
+    pub if_false: usize,
 }
 
 impl Test {
@@ -85,86 +93,114 @@ impl Test {
 
 // Represent a predictable monkey throwing items
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct Monkey {
-    items: Vec<isize>,
-    operation: Operation,
-    test: Test,
-    handling_count: usize,
+pub struct Monkey {
+    pub items: Vec<isize>,
+    pub operation: Operation,
+    pub test: Test,
+    pub handling_count: usize,
 }
 
-impl From<&str> for Monkey {
-    fn from(spec: &str) -> Self {
+impl TryFrom<&str> for Monkey {
+    type Error = ParseError;
+
+    fn try_from(spec: &str) -> Result<Self, Self::Error> {
         let mut lines = spec.lines();
 
         // Ignore Monkey: <id>
-        lines.next();
+        expect_line(spec, &mut lines, "Monkey ")?;
 
         //   Starting items: 79, 60, 97
-        let (_, item_spec) = lines.next().unwrap().split_once(": ").unwrap();
+        let item_spec = expect_line(spec, &mut lines, "Starting items: ")?;
         let items: Vec<isize> =
             item_spec.split(", ")
-                     .map(|item| item.parse::<isize>().unwrap())
-                     .collect();
+                     .map(|item| parse_number(spec, item))
+                     .collect::<Result<_, _>>()?;
 
         // Operation: new = old * 19
-        let (_, op_spec) = lines.next().unwrap().split_once("new = ").unwrap();
-        let operation = op_spec.into();
+        let op_spec = expect_line(spec, &mut lines, "Operation: new = ")?;
+        let operation = Operation::try_from(op_spec).map_err(|err| err.within(spec, op_spec))?;
 
         //Test: divisible by 19
-        let divisor =
-            lines.next().unwrap()
-                 .split_whitespace()
-                 .dropping(3).next().unwrap()
-                 .parse::<isize>().unwrap();
+        let divisor_spec = expect_line(spec, &mut lines, "Test: divisible by ")?;
+        let divisor = match parse_number(spec, divisor_spec)? {
+            0 => return Err(ParseError::new(spec, divisor_spec, "Expected a non-zero divisor")),
+            divisor => divisor
+        };
 
         // If true: throw to monkey 2
-        let if_true = parse_branch(lines.next().unwrap());
+        let if_true = parse_number(spec, expect_line(spec, &mut lines, "If true: throw to monkey ")?)?;
         // If false: throw to monkey 3
-        let if_false = parse_branch(lines.next().unwrap());
+        let if_false = parse_number(spec, expect_line(spec, &mut lines, "If false: throw to monkey ")?)?;
 
-        Monkey {
+        Ok(Monkey {
             items,
             operation,
             test: Test { divisor, if_true, if_false },
             handling_count: 0,
-        }
+        })
     }
 }
 
-/// Parse a if/else branch of a monkey's decision tree in the format `If <true|false>: throw to monkey <index>`
-fn parse_branch(spec: &str) -> usize {
-    spec.split_whitespace()
-        .dropping(5).next().unwrap()
-        .parse::<usize>().unwrap()
+/// Take the next line of a monkey's spec, check it has the expected label, and return the rest of the line
+pub fn expect_line<'a>(spec: &'a str, lines: &mut Lines<'a>, label: &str) -> Result<&'a str, ParseError> {
+    let line = lines.next().unwrap_or(&spec[spec.len()..]);
+
+    line.trim_start()
+        .strip_prefix(label)
+        .ok_or_else(|| ParseError::new(spec, line, format!("Expected '{}'", label)))
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-11-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 11.
-pub fn run() {
-    let contents =
-        fs::read_to_string("res/day-11-input").expect("Failed to read file");
-    let mut monkeys = parse_input(&contents);
-
-    println!(
-        "After twenty rounds the top two monkeys have a monkey business score of: {}",
-        get_monkey_business_level(&mut monkeys.clone(), 20, 3),
-    );
-
-    println!(
-        "After 10,000 rounds without worry reduction, the top two monkeys have a score of: {}",
-        get_monkey_business_level(&mut monkeys, 10000, 1),
-    )
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-11-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 11.
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Vec<Monkey>;
+    const DAY: usize = 11;
+
+    fn parse(&self, input: &str) -> Result<Vec<Monkey>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, monkeys: &Vec<Monkey>) -> Answer {
+        get_monkey_business_level(&mut monkeys.clone(), 20, 3).into()
+    }
+
+    fn part_two(&self, monkeys: &Vec<Monkey>) -> Answer {
+        get_monkey_business_level(&mut monkeys.clone(), 10000, 1).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("After twenty rounds the top two monkeys have a monkey business score of: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("After 10,000 rounds without worry reduction, the top two monkeys have a score of: {}", answer)
+    }
 }
 
 /// Parse the puzzle input into `Monkey`s
-fn parse_input(input: &String) -> Vec<Monkey> {
-    input.split("\n\n").map_into().collect()
+pub fn parse_input(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let specs: Vec<&str> = input.split("\n\n").collect();
+    let monkeys: Vec<Monkey> =
+        specs.iter()
+             .map(|&spec| Monkey::try_from(spec).map_err(|err| err.within(input, spec)))
+             .collect::<Result<_, _>>()?;
+
+    for (monkey, &spec) in monkeys.iter().zip(specs.iter()) {
+        if monkey.test.if_true.max(monkey.test.if_false) >= monkeys.len() {
+            let header = spec.lines().next().unwrap_or(spec);
+            return Err(ParseError::new(input, header, "Expected monkeys to only throw to other monkeys in the list"));
+        }
+    }
+
+    Ok(monkeys)
 }
 
 /// Simulate each monkey processing its items in turn, updating the list in-place
-fn simulate_round(monkeys: &mut Vec<Monkey>, worry_divisor: isize, common_denominator: isize) {
+pub fn simulate_round(monkeys: &mut [Monkey], worry_divisor: isize, common_denominator: isize) {
     for i in 0..monkeys.len() {
         let mut monkey = monkeys.get_mut(i).unwrap();
         let current_items = monkey.items.clone();
@@ -183,7 +219,7 @@ fn simulate_round(monkeys: &mut Vec<Monkey>, worry_divisor: isize, common_denomi
 
 /// Simulate the monkeys for a number of rounds, then multiply the handling counts of the two mist active monkeys to
 /// get their "monkey business score!
-fn get_monkey_business_level(
+pub fn get_monkey_business_level(
     mut monkeys: &mut Vec<Monkey>,
     rounds: usize,
     worry_divisor: isize,
@@ -270,7 +306,7 @@ Monkey 3:
     If true: throw to monkey 0
     If false: throw to monkey 1".to_string();
 
-        assert_eq!(parse_input(&sample_input), sample_monkeys())
+        assert_eq!(parse_input(&sample_input), Ok(sample_monkeys()))
     }
 
     fn get_sample_common_denominator() -> isize {
@@ -309,4 +345,27 @@ Monkey 3:
             2713310158,
         )
     }
+
+    #[test]
+    fn reports_invalid_monkeys() {
+        let monkey = |operation: &str, divisor: &str, if_false: &str| format!(
+            "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = {}\n  Test: divisible by {}\n    \
+            If true: throw to monkey 1\n    If false: throw to monkey {}",
+            operation,
+            divisor,
+            if_false,
+        );
+        let position = |input: String| parse_input(&input).map(|_| ()).map_err(|err| (err.line, err.column, err.text));
+
+        assert_eq!(position(monkey("old * 19", "23", "0") + "\n\n" + &monkey("old + 6", "19", "1")), Ok(()));
+        assert_eq!(
+            position(monkey("old * 19", "23", "0") + "\n\n" + &monkey("old - 6", "19", "1")),
+            Err((10, 24, "-".to_string()))
+        );
+        assert_eq!(position(monkey("old * nineteen", "23", "0")), Err((3, 26, "nineteen".to_string())));
+        assert_eq!(position(monkey("old * 19", "0", "0")), Err((4, 22, "0".to_string())));
+        assert_eq!(position(monkey("old * 19", "23", "2")), Err((1, 1, "Monkey 0:".to_string())));
+        assert_eq!(position("Monkey 0:\n  Starting items: 79, 98".to_string()), Err((2, 25, "".to_string())));
+        assert_eq!(position("Monkey 0:\n  Starting items: 79, x".to_string()), Err((2, 23, "x".to_string())));
+    }
 }
@@ -2,181 +2,170 @@
 //!
 //!
 
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-use std::fs;
+use crate::solution::{Answer, Solution};
 use crate::util::grid::Grid;
+use crate::util::grid_parser::GridParser;
+use crate::util::parse::ParseError;
+use crate::util::point::Point2;
+use crate::util::search::a_star;
 
-type Position = (usize, usize);
+pub type Position = (usize, usize);
 
-/// This is juts copied from  the example [`BinaryHeap`] with position swapped for coords.
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct Cell {
-    dist: usize,
-    cost: usize,
-    coords: Position,
-}
+/// The entry point for running the solutions with the 'real' puzzle input.
+///
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-12-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 12.
+pub struct Day12;
 
-// The priority queue depends on `Ord`.
-// Explicitly implement the trait so the queue becomes a min-heap
-// instead of a max-heap.
-impl Ord for Cell {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Notice that the we flip the ordering on costs.
-        // In case of a tie we compare positions - this step is necessary
-        // to make implementations of `PartialEq` and `Ord` consistent.
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.coords.cmp(&other.coords))
-    }
-}
+impl Solution for Day12 {
+    type Parsed = (Grid<u8>, Position, Position);
+    const DAY: usize = 12;
 
-// `PartialOrd` needs to be implemented as well.
-impl PartialOrd for Cell {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn parse(&self, input: &str) -> Result<(Grid<u8>, Position, Position), ParseError> {
+        parse_input(input)
     }
-}
 
-/// The entry point for running the solutions with the 'real' puzzle input.
-///
-/// - The puzzle input is expected to be at `<project_root>/res/day-12-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 12.
-pub fn run() {
-    let contents =
-        fs::read_to_string("res/day-12-input").expect("Failed to read file");
-    let (grid, start, goal) = parse_input(&contents);
-
-    println!(
-        "The shortest path to the goal is: {}",
-        find_shortest_path_from_start(&grid, start, goal).unwrap()
-    );
-
-    println!(
-        "The shortest trail to the goal is: {}",
-        find_shortest_trail(&grid, goal).unwrap()
-    )
-}
+    fn part_one(&self, (grid, start, goal): &(Grid<u8>, Position, Position)) -> Answer {
+        steps(&find_shortest_path_from_start(grid, *start, *goal).unwrap()).into()
+    }
 
-fn parse_input(input: &String) -> (Grid, Position, Position) {
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, chr) in line.chars().enumerate() {
-            if chr == 'S' {
-                start = (y, x)
-            } else if chr == 'E' {
-                end = (y, x)
-            }
-        }
+    fn part_two(&self, (grid, _, goal): &(Grid<u8>, Position, Position)) -> Answer {
+        steps(&find_shortest_trail(grid, *goal).unwrap()).into()
     }
 
-    let grid = Grid::from_string_with_mapping(
-        input,
-        |chr| match chr {
-            'S' => 1,
-            'E' => 26,
-            c => u8::try_from(c).unwrap() & 0b11111
-        },
-    );
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The shortest path to the goal is: {}", answer)
+    }
 
-    (grid, start, end)
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The shortest trail to the goal is: {}", answer)
+    }
 }
 
-fn manhatten_distance((y_a, x_a): Position, (y_b, x_b): Position) -> usize {
-    y_a.abs_diff(y_b) + x_a.abs_diff(x_b)
+pub fn parse_input(input: &str) -> Result<(Grid<u8>, Position, Position), ParseError> {
+    let parsed = GridParser::new()
+        .marker('S', "start")
+        .marker('E', "goal")
+        .expecting("a height a-z, or a single S and E")
+        .parse(
+            input,
+            |chr| match chr {
+                'S' => Some(1),
+                'E' => Some(26),
+                'a'..='z' => Some(chr as u8 & 0b11111),
+                _ => None,
+            },
+        )?;
+
+    let start = parsed.position('S').unwrap();
+    let goal = parsed.position('E').unwrap();
+
+    Ok((parsed.grid, start, goal))
 }
 
-fn find_shortest_path<F, GP, HP>(
-    grid: &Grid,
+/// Search for the shortest path from `start` to a position that meets `goal_met_predicate`, only stepping between
+/// neighbouring positions whose heights meet `height_difference_predicate`. The `heuristic` guides the search towards
+/// the goal, see [`a_star`]. Returns the positions along the path, including `start` and the goal.
+pub fn find_shortest_path<F, GP, HP>(
+    grid: &Grid<u8>,
     start: Position,
     height_difference_predicate: HP,
     goal_met_predicate: GP,
-    cost_function: F,
-) -> Option<usize>
+    heuristic: F,
+) -> Option<Vec<Position>>
     where
         HP: Fn(u8, u8) -> bool,
         GP: Fn(Position, u8) -> bool,
-        F: Fn(Position, usize, u8) -> usize,
+        F: Fn(Position, u8) -> usize,
 {
-    let mut heap: BinaryHeap<Cell> = BinaryHeap::new();
-    let mut dists: Vec<usize> = (0..grid.numbers.len()).map(|_| usize::MAX).collect();
-
-    dists[grid.pos_of(start).unwrap()] = 0;
-    heap.push(Cell {
-        dist: 0,
-        cost: cost_function(start, 0, 0),
-        coords: start,
-    });
-
-    while let Some(Cell { dist, coords, .. }) = heap.pop() {
-        let current_height = grid.get(coords.0, coords.1).unwrap();
-
-        if goal_met_predicate(coords, current_height) {
-            return Some(dist);
-        }
-
-        if dist > dists[grid.pos_of(coords).unwrap()] {
-            continue;
-        }
-
-
-        for (next_coords, next_height) in grid.get_orthogonal_surrounds(coords) {
-            let next_pos = grid.pos_of(next_coords).unwrap();
-
-            if height_difference_predicate(current_height, next_height) && (dist + 1 < dists[next_pos]) {
-                heap.push(Cell {
-                    dist: dist + 1,
-                    cost: cost_function(next_coords, dist + 1, next_height),
-                    coords: next_coords,
-                });
-                dists[next_pos] = dist + 1
-            }
-        }
-    }
+    let height = |pos: Position| *grid.get(pos.0, pos.1).unwrap();
 
-    None
+    a_star(
+        start,
+        |&pos| {
+            grid.get_orthogonal_surrounds(pos)
+                .into_iter()
+                .filter(|&(_, &next_height)| height_difference_predicate(height(pos), next_height))
+                .map(|(next_pos, _)| (next_pos, 1))
+                .collect::<Vec<_>>()
+        },
+        |&pos| goal_met_predicate(pos, height(pos)),
+        |&pos| heuristic(pos, height(pos)),
+    ).map(|found| found.path())
 }
 
-fn find_shortest_trail(
-    grid: &Grid,
+/// The shortest trail from any lowest point to the goal. This searches backwards from the goal, but the trail is
+/// returned in the order it would be walked.
+pub fn find_shortest_trail(
+    grid: &Grid<u8>,
     goal: Position,
-) -> Option<usize> {
-    find_shortest_path(
+) -> Option<Vec<Position>> {
+    let mut trail = find_shortest_path(
         grid,
         goal,
         |curr_h, next_h| curr_h - 1 <= next_h,
         |_, h| h == 1,
-        |_, d, h| d + usize::from(h),
-    )
+        |_, h| usize::from(h - 1),
+    )?;
+
+    trail.reverse();
+    Some(trail)
 }
 
-fn find_shortest_path_from_start(
-    grid: &Grid,
+pub fn find_shortest_path_from_start(
+    grid: &Grid<u8>,
     start: Position,
     goal: Position,
-) -> Option<usize> {
+) -> Option<Vec<Position>> {
     find_shortest_path(
         grid,
         start,
         |curr_h, next_h| curr_h + 1 >= next_h,
         |pos, _| pos == goal,
-        |pos, d, h| manhatten_distance(pos, goal) + d + usize::from(h),
+        |pos, _| Point2::from_grid_position(pos).manhattan_distance(&Point2::from_grid_position(goal)),
     )
 }
 
+/// The number of steps taken to walk a route
+pub fn steps(route: &[Position]) -> usize {
+    route.len().saturating_sub(1)
+}
+
+/// Draw the route over the height map in the style of the puzzle text, each position on the route is marked with an
+/// arrow showing which way it was left, the end is marked `E`, and everywhere else is `.`
+pub fn render_route(grid: &Grid<u8>, route: &[Position]) -> String {
+    let mut overlay = Grid::new(grid.width, grid.height(), |_, _| '.');
+
+    for step in route.windows(2) {
+        let ((y, x), next) = (step[0], step[1]);
+        let arrow = match next {
+            _ if next == (y.wrapping_sub(1), x) => '^',
+            _ if next == (y + 1, x) => 'v',
+            _ if next == (y, x.wrapping_sub(1)) => '<',
+            _ if next == (y, x + 1) => '>',
+            _ => '?',
+        };
+
+        overlay.set(y, x, arrow);
+    }
+
+    if let Some(&(y, x)) = route.last() {
+        overlay.set(y, x, 'E');
+    }
+
+    overlay.print_with(|c| c.to_string())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day_12::{find_shortest_path_from_start, find_shortest_trail, parse_input, Position};
+    use crate::day_12::{find_shortest_path_from_start, find_shortest_trail, parse_input, Position, render_route, steps};
     use crate::util::grid::Grid;
 
-    fn sample_data() -> (Grid, Position, Position) {
+    fn sample_data() -> (Grid<u8>, Position, Position) {
         (
             Grid {
                 width: 8,
-                numbers: vec![
+                cells: vec![
                     1, 1, 2, 17, 16, 15, 14, 13,
                     1, 2, 3, 18, 25, 24, 24, 12,
                     1, 3, 3, 19, 26, 26, 24, 11,
@@ -199,24 +188,53 @@ mod tests {
              acctuvwj\n\
              abdefghi".to_string();
 
-        assert_eq!(parse_input(&input), sample_data())
+        assert_eq!(parse_input(&input), Ok(sample_data()))
     }
 
     #[test]
     fn can_find_shortest_path() {
         let (grid, start, goal) = sample_data();
+        let path = find_shortest_path_from_start(&grid, start, goal).unwrap();
+        assert_eq!(steps(&path), 31);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+
         assert_eq!(
-            find_shortest_path_from_start(&grid, start, goal),
-            Some(31)
-        )
+            render_route(&grid, &path),
+            ">>vv<<<<\n\
+             ..vvv<<^\n\
+             ..vv>E^^\n\
+             ..v>>>^^\n\
+             ..>>>>>^"
+        );
     }
 
     #[test]
     fn can_find_shortest_trail() {
         let (grid, _, goal) = sample_data();
+        let trail = find_shortest_trail(&grid, goal).unwrap();
+        assert_eq!(steps(&trail), 29);
+        assert_eq!(trail.first().and_then(|&(y, x)| grid.get(y, x)), Some(&1));
+        assert_eq!(trail.last(), Some(&goal));
+
         assert_eq!(
-            find_shortest_trail(&grid, goal),
-            Some(29)
-        )
+            render_route(&grid, &trail),
+            "...v<<<<\n\
+             ...vv<<^\n\
+             ...v>E^^\n\
+             .>v>>>^^\n\
+             >^>>>>>^"
+        );
+    }
+
+    #[test]
+    fn reports_invalid_maps() {
+        let position = |input: &str| parse_input(input).map(|_| ()).map_err(|err| (err.line, err.column, err.text));
+
+        assert_eq!(position("Sab\ncdE"), Ok(()));
+        assert_eq!(position("Sab\ncDE"), Err((2, 2, "D".to_string())));
+        assert_eq!(position("Sab\ncdE\nSaa"), Err((3, 1, "S".to_string())));
+        assert_eq!(position("Sab\ncd"), Err((2, 1, "cd".to_string())));
+        assert_eq!(position("Sab\ncde").unwrap_err().2, "Sab\ncde");
     }
 }
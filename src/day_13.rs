@@ -4,12 +4,13 @@
 
 use std::cmp::Ordering;
 use std::cmp::Ordering::Equal;
-use std::fs;
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 use crate::day_13::NestedList::{List, Value};
+use crate::util::parse::{ParseError, split_once};
 
 #[derive(Eq, PartialEq, Debug, Clone)]
-enum NestedList {
+pub enum NestedList {
     Value(u32),
     List(Vec<NestedList>),
 }
@@ -53,36 +54,56 @@ impl PartialOrd for NestedList {
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-13-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 13.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-13-input").expect("Failed to read file");
-    let pairs = parse_input(&contents);
-
-    println!(
-        "The sum of in order indices is: {}",
-        find_in_order_index_sum(&pairs)
-    );
-
-    println!(
-        "The decoder key is: {}",
-        find_decoder_key(&pairs)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-13-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 13.
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<(NestedList, NestedList)>;
+    const DAY: usize = 13;
+
+    fn parse(&self, input: &str) -> Result<Vec<(NestedList, NestedList)>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, pairs: &Vec<(NestedList, NestedList)>) -> Answer {
+        find_in_order_index_sum(pairs).into()
+    }
+
+    fn part_two(&self, pairs: &Vec<(NestedList, NestedList)>) -> Answer {
+        find_decoder_key(pairs).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sum of in order indices is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The decoder key is: {}", answer)
+    }
 }
 
-fn parse_input(input: &String) -> Vec<(NestedList, NestedList)> {
-    input.split("\n\n").map(parse_pair).collect()
+pub fn parse_input(input: &str) -> Result<Vec<(NestedList, NestedList)>, ParseError> {
+    input.split("\n\n")
+         .map(|spec| parse_pair(spec).map_err(|err| err.within(input, spec)))
+         .collect()
 }
 
-fn parse_pair(spec: &str) -> (NestedList, NestedList) {
-    let (left, right) = spec.split_once("\n").unwrap();
-    (parse_list(left), parse_list(right))
+pub fn parse_pair(spec: &str) -> Result<(NestedList, NestedList), ParseError> {
+    let (left, right) = split_once(spec, spec, "\n")?;
+    Ok((
+        parse_list(left).map_err(|err| err.within(spec, left))?,
+        parse_list(right).map_err(|err| err.within(spec, right))?
+    ))
 }
 
-fn parse_list(spec: &str) -> NestedList {
+pub fn parse_list(spec: &str) -> Result<NestedList, ParseError> {
+    let trimmed = spec.trim();
+    let unbalanced = |pos: usize| ParseError::new(spec, &trimmed[pos..pos + 1], "Unexpected ']', there is no list to close");
+
     let mut stack = Vec::new();
     let mut current = List(Vec::new());
-    for c in spec.trim().chars() {
+    for (pos, c) in trimmed.char_indices() {
         match c {
             '[' => {
                 stack.push(current);
@@ -91,15 +112,15 @@ fn parse_list(spec: &str) -> NestedList {
             ']' => {
                 match current {
                     List(l) => {
-                        current = stack.pop().unwrap();
+                        current = stack.pop().ok_or_else(|| unbalanced(pos))?;
                         current.push(List(l));
                     }
                     Value(v) => {
-                        current = stack.pop().unwrap();
+                        current = stack.pop().ok_or_else(|| unbalanced(pos))?;
                         current.push(Value(v));
 
                         let temp = current.clone();
-                        current = stack.pop().unwrap();
+                        current = stack.pop().ok_or_else(|| unbalanced(pos))?;
                         current.push(temp.clone());
                     }
                 }
@@ -121,21 +142,21 @@ fn parse_list(spec: &str) -> NestedList {
                     }
                 }
             }
-            _ => unreachable!()
+            c => return Err(ParseError::new(spec, &trimmed[pos..pos + c.len_utf8()], "Expected '[', ']', ',' or a digit"))
         }
     }
 
     match current {
-        List(l) => l.first().unwrap().clone(),
-        _ => unreachable!()
+        List(l) if stack.is_empty() && l.len() == 1 => Ok(l[0].clone()),
+        _ => Err(ParseError::new(spec, trimmed, "Expected a single list with balanced brackets"))
     }
 }
 
-fn in_order(a: &NestedList, b: &NestedList) -> bool {
+pub fn in_order(a: &NestedList, b: &NestedList) -> bool {
     a < b
 }
 
-fn find_in_order_index_sum(pairs: &Vec<(NestedList, NestedList)>) -> usize {
+pub fn find_in_order_index_sum(pairs: &[(NestedList, NestedList)]) -> usize {
     pairs
         .iter()
         .enumerate()
@@ -144,7 +165,7 @@ fn find_in_order_index_sum(pairs: &Vec<(NestedList, NestedList)>) -> usize {
         .sum()
 }
 
-fn find_decoder_key(pairs: &Vec<(NestedList, NestedList)>) -> usize {
+pub fn find_decoder_key(pairs: &[(NestedList, NestedList)]) -> usize {
     let divider_packets = vec![
         List(vec![List(vec![Value(2)])]),
         List(vec![List(vec![Value(6)])]),
@@ -193,7 +214,7 @@ mod tests {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]".to_string();
 
-        let actual = parse_input(&input);
+        let actual = parse_input(&input).unwrap();
         for (a, e) in actual.iter().zip(sample_pairs()) {
             assert_eq!(*a, e);
         }
@@ -254,4 +275,15 @@ mod tests {
     fn can_find_decoder_key() {
         assert_eq!(find_decoder_key(&sample_pairs()), 140)
     }
+
+    #[test]
+    fn reports_invalid_packets() {
+        let position = |input: &str| parse_input(input).map(|_| ()).map_err(|err| (err.line, err.column, err.text));
+
+        assert_eq!(position("[1,[2]]\n[3]\n\n[]\n[[]]"), Ok(()));
+        assert_eq!(position("[1,[2]]\n[3]\n\n[]\n[[a]]"), Err((5, 3, "a".to_string())));
+        assert_eq!(position("[1,[2]]\n[3]]"), Err((2, 4, "]".to_string())));
+        assert_eq!(position("[1,[2]]\n[3,[4]"), Err((2, 1, "[3,[4]".to_string())));
+        assert_eq!(position("[1,[2]]\n[3]\n\n[1]"), Err((4, 1, "[1]".to_string())));
+    }
 }
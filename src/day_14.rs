@@ -3,37 +3,50 @@
 //!
 
 use std::collections::HashSet;
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 
-type Coordinates = (isize, isize);
+pub type Coordinates = (isize, isize);
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-14-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 14.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-14-input").expect("Failed to read file");
-    let (mut map, deepest_point) = parse_input(&contents);
-
-    let (part_1, part_2) = count_added_sand(&mut map, deepest_point);
-    println!(
-        "Before reaching the floor, {} units of sand were added",
-        part_1
-    );
-
-    println!(
-        "Before blocking the inlet, {} units of sand were added",
-        part_2
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-14-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 14.
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Parsed = (HashSet<Coordinates>, isize);
+    const DAY: usize = 14;
+
+    fn parse(&self, input: &str) -> Result<(HashSet<Coordinates>, isize), ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, (map, deepest_point): &(HashSet<Coordinates>, isize)) -> Answer {
+        let (to_floor, _) = count_added_sand(&mut map.clone(), *deepest_point);
+        to_floor.into()
+    }
+
+    fn part_two(&self, (map, deepest_point): &(HashSet<Coordinates>, isize)) -> Answer {
+        let (_, to_inlet) = count_added_sand(&mut map.clone(), *deepest_point);
+        to_inlet.into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("Before reaching the floor, {} units of sand were added", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("Before blocking the inlet, {} units of sand were added", answer)
+    }
 }
 
-fn parse_input(input: &String) -> (HashSet<Coordinates>, isize) {
+pub fn parse_input(input: &str) -> Result<(HashSet<Coordinates>, isize), ParseError> {
     let mut points = HashSet::new();
     let mut deepest = 0;
 
-    let points_list = input
-        .lines()
-        .map(parse_line)
+    let points_list = parse_lines(input, parse_line)?
+        .into_iter()
         .flat_map(line_to_points);
 
     for (x, y) in points_list {
@@ -43,31 +56,41 @@ fn parse_input(input: &String) -> (HashSet<Coordinates>, isize) {
         }
     }
 
-    (points, deepest)
+    Ok((points, deepest))
 }
 
-fn parse_line(line: &str) -> Vec<Coordinates> {
-    line.split(" -> ")
-        .map(parse_coordinate)
-        .collect()
+pub fn parse_line(line: &str) -> Result<Vec<Coordinates>, ParseError> {
+    let specs: Vec<&str> = line.split(" -> ").collect();
+    let coordinates: Vec<Coordinates> =
+        specs.iter()
+             .map(|spec| parse_coordinate(line, spec))
+             .collect::<Result<_, _>>()?;
+
+    for (i, segment) in coordinates.windows(2).enumerate() {
+        if segment[0].0 != segment[1].0 && segment[0].1 != segment[1].1 {
+            return Err(ParseError::new(line, specs[i + 1], "Expected a horizontal or vertical line"));
+        }
+    }
+
+    Ok(coordinates)
 }
 
-fn parse_coordinate(coordinate: &str) -> Coordinates {
-    let (x, y) = coordinate.split_once(',').unwrap();
+pub fn parse_coordinate(line: &str, coordinate: &str) -> Result<Coordinates, ParseError> {
+    let (x, y) = split_once(line, coordinate, ",")?;
 
-    (
-        x.parse::<isize>().unwrap(),
-        y.parse::<isize>().unwrap()
-    )
+    match (parse_number(line, x)?, parse_number(line, y)?) {
+        (_, y) if y < 0 => Err(ParseError::new(line, coordinate, "Expected rock to be below the sand source")),
+        coordinates => Ok(coordinates)
+    }
 }
 
-fn line_to_points(line: Vec<Coordinates>) -> Vec<Coordinates> {
+pub fn line_to_points(line: Vec<Coordinates>) -> Vec<Coordinates> {
     line.windows(2)
         .flat_map(|seg| segment_to_points(seg))
         .collect()
 }
 
-fn segment_to_points(segment: &[Coordinates]) -> Vec<Coordinates> {
+pub fn segment_to_points(segment: &[Coordinates]) -> Vec<Coordinates> {
     let (start_x, start_y) = segment[0];
     let (end_x, end_y) = segment[1];
 
@@ -84,7 +107,7 @@ fn segment_to_points(segment: &[Coordinates]) -> Vec<Coordinates> {
     }
 }
 
-fn count_added_sand(map: &mut HashSet<Coordinates>, deepest_point: isize) -> (usize, usize) {
+pub fn count_added_sand(map: &mut HashSet<Coordinates>, deepest_point: isize) -> (usize, usize) {
     let mut added_sand_to_floor = None;
     let mut added_sand = 0;
 
@@ -137,7 +160,7 @@ mod tests {
 
         assert_eq!(
             parse_input(&input),
-            (sample_map(), 9)
+            Ok((sample_map(), 9))
         )
 
     }
@@ -146,4 +169,16 @@ mod tests {
     fn can_count_added_sand() {
         assert_eq!(count_added_sand(&mut sample_map(), 9), (24, 93))
     }
+
+    #[test]
+    fn reports_invalid_rock_paths() {
+        let diagonal = parse_input("498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 501,9").unwrap_err();
+        assert_eq!((diagonal.line, diagonal.column, diagonal.text.as_str()), (2, 19, "501,9"));
+
+        let bad_number = parse_input("498,4 -> 498,six").unwrap_err();
+        assert_eq!((bad_number.line, bad_number.column, bad_number.text.as_str()), (1, 14, "six"));
+
+        let above_source = parse_input("498,-4 -> 498,6").unwrap_err();
+        assert_eq!((above_source.line, above_source.column, above_source.text.as_str()), (1, 1, "498,-4"));
+    }
 }
@@ -2,155 +2,145 @@
 //!
 //!
 
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::interval::{Interval, IntervalSet};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
+use crate::util::point::Point2;
 use itertools::Itertools;
 
-type Range = (isize, isize);
-type Position = (isize, isize);
-
 #[derive(Eq, PartialEq, Debug)]
-struct Sensor {
-    sensor: Position,
-    beacon: Position,
-    zone_size: isize,
+pub struct Sensor {
+    pub sensor: Point2,
+    pub beacon: Point2,
+    pub zone_size: isize,
 }
 
 impl Sensor {
-    fn x_coverage_for(&self, y: isize) -> Option<Range> {
-        match self.zone_size - isize::try_from(y.abs_diff(self.sensor.1)).unwrap() {
+    fn x_coverage_for(&self, y: isize) -> Option<Interval> {
+        match self.zone_size - isize::try_from(y.abs_diff(self.sensor.y)).unwrap() {
             diff if diff < 0 => None,
-            diff => Some(
-                (self.sensor.0 - diff, self.sensor.0 + diff)
-            )
+            diff => Some(Interval::new(self.sensor.x - diff, self.sensor.x + diff))
         }
     }
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-15-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 15.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-15-input").expect("Failed to read file");
-    let sensors = parse_input(&contents);
-
-    println!(
-        "On row 2,000,000 there are {} spaces known to be free of sensors.",
-        coverage_for(&sensors, 2_000_000)
-    );
-
-    println!(
-        "The tuning frequency is: {}",
-        tuning_frequency(&sensors, (0, 4_000_000))
-    );
-}
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-15-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 15.
+pub struct Day15;
 
-fn parse_input(input: &String) -> Vec<Sensor> {
-    input.lines().map(parse_line).collect()
-}
+impl Solution for Day15 {
+    type Parsed = Vec<Sensor>;
+    const DAY: usize = 15;
 
-fn parse_line(line: &str) -> Sensor {
-    let mut parts = line.split_whitespace().dropping(2);
-    let sx = parts.next().map(parse_digits).unwrap();
-    let sy = parts.next().map(parse_digits).unwrap();
+    fn parse(&self, input: &str) -> Result<Vec<Sensor>, ParseError> {
+        parse_input(input)
+    }
 
-    parts = parts.dropping(4);
-    let bx = parts.next().map(parse_digits).unwrap();
-    let by = parts.next().map(parse_digits).unwrap();
+    fn part_one(&self, sensors: &Vec<Sensor>) -> Answer {
+        coverage_for(sensors, 2_000_000).into()
+    }
+
+    fn part_two(&self, sensors: &Vec<Sensor>) -> Answer {
+        tuning_frequency(sensors, Interval::new(0, 4_000_000)).into()
+    }
 
-    Sensor {
-        sensor: (sx, sy),
-        beacon: (bx, by),
-        zone_size: manhatten_distance((sx, sy), (bx, by)),
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("On row 2,000,000 there are {} spaces known to be free of sensors.", answer)
     }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The tuning frequency is: {}", answer)
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Sensor>, ParseError> {
+    parse_lines(input, parse_line)
 }
 
-fn parse_digits(part: &str) -> isize {
-    let number_str: String = part.chars().filter(|&c| c.is_digit(10) || c == '-').collect();
-    number_str.parse::<isize>().unwrap()
+pub fn parse_line(line: &str) -> Result<Sensor, ParseError> {
+    let format_error =
+        || ParseError::new(line, line, "Expected 'Sensor at x=<x>, y=<y>: closest beacon is at x=<x>, y=<y>'");
+
+    let (sensor_spec, beacon_spec) =
+        line.strip_prefix("Sensor at ")
+            .and_then(|rest| rest.split_once(": closest beacon is at "))
+            .ok_or_else(format_error)?;
+
+    let sensor = parse_position(line, sensor_spec)?;
+    let beacon = parse_position(line, beacon_spec)?;
+
+    Ok(Sensor {
+        sensor,
+        beacon,
+        zone_size: sensor.manhattan_distance(&beacon).try_into().unwrap(),
+    })
 }
 
-fn manhatten_distance((y_a, x_a): (isize, isize), (y_b, x_b): (isize, isize)) -> isize {
-    (y_a.abs_diff(y_b) + x_a.abs_diff(x_b)).try_into().unwrap()
+/// Parse a position in the format `x=<x>, y=<y>`
+pub fn parse_position(line: &str, spec: &str) -> Result<Point2, ParseError> {
+    let (x_spec, y_spec) = split_once(line, spec, ", ")?;
+    let x = x_spec.strip_prefix("x=").ok_or_else(|| ParseError::new(line, x_spec, "Expected 'x=<x>'"))?;
+    let y = y_spec.strip_prefix("y=").ok_or_else(|| ParseError::new(line, y_spec, "Expected 'y=<y>'"))?;
+
+    Ok(Point2::new(parse_number(line, x)?, parse_number(line, y)?))
 }
 
-fn coverage_for(sensors: &Vec<Sensor>, y: isize) -> usize {
-    let coverage: usize =
-        ranges_for(sensors, y)
-            .into_iter()
-            .map(|(start, end)| start.abs_diff(end) + 1)
-            .sum();
+pub fn coverage_for(sensors: &[Sensor], y: isize) -> usize {
+    let coverage = ranges_for(sensors, y).total_length();
 
     let beacon_count =
-        sensors.into_iter()
-               .filter(|s| s.beacon.1 == y)
-               .map(|s| s.beacon.0)
+        sensors.iter()
+               .filter(|s| s.beacon.y == y)
+               .map(|s| s.beacon.x)
                .unique()
                .count();
 
     coverage - beacon_count
 }
 
-fn ranges_for(sensors: &Vec<Sensor>, y: isize) -> Vec<Range> {
-    sensors.into_iter()
+/// The x positions on row `y` that are covered by at least one sensor
+pub fn ranges_for(sensors: &[Sensor], y: isize) -> IntervalSet {
+    sensors.iter()
            .flat_map(|s| s.x_coverage_for(y))
-           .fold(
-               Vec::new(),
-               |acc: Vec<Range>, next_range: Range| {
-                   let mut new: Vec<Range> = Vec::new();
-                   let mut current = next_range;
-                   for existing_range in acc.into_iter() {
-                       match try_merge(&current, &existing_range) {
-                           Some(merged) => { current = merged; }
-                           None => { new.push(existing_range); }
-                       }
-                   }
-                   new.push(current);
-                   new
-               },
-           )
+           .collect()
 }
 
-fn try_merge(&(s_a, e_a): &Range, &(s_b, e_b): &Range) -> Option<Range> {
-    if s_a <= e_b && e_a >= s_b {
-        Some((s_a.min(s_b), e_a.max(e_b)))
-    } else {
-        None
-    }
-}
-
-fn tuning_frequency(sensors: &Vec<Sensor>, bounds: Range) -> isize {
-    let (y, line_with_gap) = (bounds.0..=bounds.1)
-        .map(|y| (y, ranges_for(&sensors, y)))
-        .filter(|(_, ranges)| ranges.len() > 1)
-        .next()
+/// Find the only position within `bounds` on both axes that no sensor covers
+pub fn tuning_frequency(sensors: &[Sensor], bounds: Interval) -> isize {
+    let (y, gaps) = (bounds.start()..=bounds.end())
+        .map(|y| (y, ranges_for(sensors, y).gaps(bounds)))
+        .find(|(_, gaps)| !gaps.is_empty())
         .unwrap();
 
-    let x = line_with_gap[0].1 + 1;
+    let x = gaps.intervals()[0].start();
 
     4_000_000 * x + y
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_15::{coverage_for, parse_input, Sensor, tuning_frequency};
+    use crate::day_15::{coverage_for, parse_input, ranges_for, Sensor, tuning_frequency};
+    use crate::util::interval::Interval;
+    use crate::util::point::Point2;
 
     fn sample_sensors() -> Vec<Sensor> {
         vec![
-            Sensor { sensor: (2, 18), beacon: (-2, 15), zone_size: 7 },
-            Sensor { sensor: (9, 16), beacon: (10, 16), zone_size: 1 },
-            Sensor { sensor: (13, 2), beacon: (15, 3), zone_size: 3 },
-            Sensor { sensor: (12, 14), beacon: (10, 16), zone_size: 4 },
-            Sensor { sensor: (10, 20), beacon: (10, 16), zone_size: 4 },
-            Sensor { sensor: (14, 17), beacon: (10, 16), zone_size: 5 },
-            Sensor { sensor: (8, 7), beacon: (2, 10), zone_size: 9 },
-            Sensor { sensor: (2, 0), beacon: (2, 10), zone_size: 10 },
-            Sensor { sensor: (0, 11), beacon: (2, 10), zone_size: 3 },
-            Sensor { sensor: (20, 14), beacon: (25, 17), zone_size: 8 },
-            Sensor { sensor: (17, 20), beacon: (21, 22), zone_size: 6 },
-            Sensor { sensor: (16, 7), beacon: (15, 3), zone_size: 5 },
-            Sensor { sensor: (14, 3), beacon: (15, 3), zone_size: 1 },
-            Sensor { sensor: (20, 1), beacon: (15, 3), zone_size: 7 },
+            Sensor { sensor: Point2::new(2, 18), beacon: Point2::new(-2, 15), zone_size: 7 },
+            Sensor { sensor: Point2::new(9, 16), beacon: Point2::new(10, 16), zone_size: 1 },
+            Sensor { sensor: Point2::new(13, 2), beacon: Point2::new(15, 3), zone_size: 3 },
+            Sensor { sensor: Point2::new(12, 14), beacon: Point2::new(10, 16), zone_size: 4 },
+            Sensor { sensor: Point2::new(10, 20), beacon: Point2::new(10, 16), zone_size: 4 },
+            Sensor { sensor: Point2::new(14, 17), beacon: Point2::new(10, 16), zone_size: 5 },
+            Sensor { sensor: Point2::new(8, 7), beacon: Point2::new(2, 10), zone_size: 9 },
+            Sensor { sensor: Point2::new(2, 0), beacon: Point2::new(2, 10), zone_size: 10 },
+            Sensor { sensor: Point2::new(0, 11), beacon: Point2::new(2, 10), zone_size: 3 },
+            Sensor { sensor: Point2::new(20, 14), beacon: Point2::new(25, 17), zone_size: 8 },
+            Sensor { sensor: Point2::new(17, 20), beacon: Point2::new(21, 22), zone_size: 6 },
+            Sensor { sensor: Point2::new(16, 7), beacon: Point2::new(15, 3), zone_size: 5 },
+            Sensor { sensor: Point2::new(14, 3), beacon: Point2::new(15, 3), zone_size: 1 },
+            Sensor { sensor: Point2::new(20, 1), beacon: Point2::new(15, 3), zone_size: 7 },
         ]
     }
 
@@ -173,7 +163,7 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3".to_string();
 
         assert_eq!(
             parse_input(&sample_input),
-            sample_sensors()
+            Ok(sample_sensors())
         )
     }
 
@@ -182,13 +172,35 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3".to_string();
         assert_eq!(coverage_for(&sample_sensors(), 9), 25);
         assert_eq!(coverage_for(&sample_sensors(), 10), 26);
         assert_eq!(coverage_for(&sample_sensors(), 11), 28);
+
+        assert_eq!(ranges_for(&sample_sensors(), 10).to_string(), "-2-24");
+        assert_eq!(ranges_for(&sample_sensors(), 11).gaps(Interval::new(0, 20)).to_string(), "14-14");
     }
 
     #[test]
     fn can_find_tuning_frequecy() {
         assert_eq!(
-            tuning_frequency(&sample_sensors(), (0, 20)),
+            tuning_frequency(&sample_sensors(), Interval::new(0, 20)),
             56_000_011
         )
     }
+
+    #[test]
+    fn reports_invalid_sensors() {
+        let position = |input: &str| parse_input(input).map(|_| ()).map_err(|err| (err.line, err.column, err.text));
+
+        assert_eq!(
+            position("Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9, y=1b: closest beacon is at x=10, y=16"),
+            Err((2, 18, "1b".to_string()))
+        );
+        assert_eq!(
+            position("Sensor at x=2, y=18: closest beacon is at x=-2; y=15"),
+            Err((1, 43, "x=-2; y=15".to_string()))
+        );
+        assert_eq!(
+            position("Sensor at x=2, z=18: closest beacon is at x=-2, y=15"),
+            Err((1, 16, "z=18".to_string()))
+        );
+        assert_eq!(position("Beacon at x=2, y=18").unwrap_err().1, 1);
+    }
 }
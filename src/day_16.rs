@@ -2,61 +2,105 @@
 //!
 //!
 
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::fs;
-use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError};
+use crate::util::search::reachable;
 
 #[derive(Eq, PartialEq, Debug)]
-struct Valve {
-    flow: usize,
-    links: Vec<usize>,
+pub struct Valve {
+    pub flow: usize,
+    pub links: Vec<usize>,
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-16-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 16.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-16-input").expect("Failed to read file");
-    let valves = parse_input(&contents);
-
-    println!(
-        "The maximal flow rate alone is: {}",
-        find_best_flow(&valves, 27, 30),
-    );
-
-    println!(
-        "The maximal flow rate with an elephant is: {}",
-        find_best_flow_with_elephant(&valves, 27, 26),
-    );
-}
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-16-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 16.
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = HashMap<usize, Valve>;
+    const DAY: usize = 16;
+
+    fn parse(&self, input: &str) -> Result<HashMap<usize, Valve>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, valves: &HashMap<usize, Valve>) -> Answer {
+        find_best_flow(valves, 27, 30).into()
+    }
 
-fn parse_input(input: &String) -> HashMap<usize, Valve> {
-    input.lines().map(parse_valve).collect()
+    fn part_two(&self, valves: &HashMap<usize, Valve>) -> Answer {
+        find_best_flow_with_elephant(valves, 27, 26).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The maximal flow rate alone is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The maximal flow rate with an elephant is: {}", answer)
+    }
 }
 
-fn parse_valve(line: &str) -> (usize, Valve) {
-    //Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-    let mut parts = line.split_whitespace().dropping(1);
-    let id = id_to_usize(parts.next().unwrap());
+pub fn parse_input(input: &str) -> Result<HashMap<usize, Valve>, ParseError> {
+    let valves: HashMap<usize, Valve> = parse_lines(input, parse_valve)?.into_iter().collect();
 
-    parts = parts.dropping(2);
-    let flow =
-        parts.next().unwrap()
-             .split_once("=")
-             .and_then(|(_, flow)| flow.trim_matches(';').parse::<usize>().ok())
-             .unwrap();
+    if !valves.contains_key(&id_to_usize("AA")) {
+        return Err(ParseError::new(input, input, "Expected a starting valve: AA"));
+    }
+
+    for line in input.lines() {
+        let (_, Valve { links, .. }) = parse_valve(line)?;
+        if let Some(&unknown) = links.iter().find(|link| !valves.contains_key(link)) {
+            let message = format!("Expected tunnels to lead to known valves, not {}", usize_to_id(unknown));
+            return Err(ParseError::new(input, line, message));
+        }
+    }
+
+    Ok(valves)
+}
 
-    let links: Vec<usize> = parts.dropping(4).map(|id| id.trim_matches(',')).map(id_to_usize).collect();
+pub fn parse_valve(line: &str) -> Result<(usize, Valve), ParseError> {
+    //Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+    let (id_spec, flow_spec, links_spec) =
+        line.strip_prefix("Valve ")
+            .and_then(|rest| rest.split_once(" has flow rate="))
+            .and_then(|(id, rest)| rest.split_once("; ").map(|(flow, links)| (id, flow, links)))
+            .ok_or_else(|| ParseError::new(line, line, "Expected 'Valve <id> has flow rate=<rate>; <tunnels>'"))?;
+
+    let links_spec =
+        links_spec.strip_prefix("tunnels lead to valves ")
+                  .or_else(|| links_spec.strip_prefix("tunnel leads to valve "))
+                  .ok_or_else(|| ParseError::new(line, links_spec, "Expected 'tunnels lead to valves <ids>'"))?;
+
+    let id = parse_id(line, id_spec)?;
+    let flow = parse_number(line, flow_spec)?;
+    let links: Vec<usize> = links_spec.split(", ").map(|link| parse_id(line, link)).collect::<Result<_, _>>()?;
+
+    Ok((id, Valve { flow, links }))
+}
 
-    (id, Valve { flow, links })
+/// Valve ids are two uppercase letters
+pub fn parse_id(line: &str, id: &str) -> Result<usize, ParseError> {
+    if id.len() == 2 && id.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(id_to_usize(id))
+    } else {
+        Err(ParseError::new(line, id, "Expected a valve id e.g. 'AA'"))
+    }
 }
 
-fn id_to_usize(id: &str) -> usize {
+pub fn id_to_usize(id: &str) -> usize {
     id.chars().fold(0, |acc, c| 26 * acc + usize::from(u8::try_from(c).unwrap() & 0b11111))
 }
 
-fn search_best_path(
+/// The inverse of [`id_to_usize`] for reporting errors. Each letter is a digit from 1 to 26, so `AZ` is `1 * 26 + 26`.
+pub fn usize_to_id(id: usize) -> String {
+    [(id - 1) / 26, (id - 1) % 26 + 1].iter().map(|&c| char::from(b'@' + c as u8)).collect()
+}
+
+pub fn search_best_path(
     current_id: usize,
     shortest_paths: &HashMap<(usize, usize), usize>,
     valves: &HashMap<usize, Valve>,
@@ -83,13 +127,13 @@ fn search_best_path(
     flow_rates.into_iter().max().unwrap_or(flow)
 }
 
-fn find_best_flow(valves: &HashMap<usize, Valve>, start: usize, time: usize) -> usize {
+pub fn find_best_flow(valves: &HashMap<usize, Valve>, start: usize, time: usize) -> usize {
     let shortest_paths = build_shortest_paths(start, &valves);
 
     search_best_path(start, &shortest_paths, &valves, time, 0, HashSet::new())
 }
 
-fn search_best_path_with_elephant(
+pub fn search_best_path_with_elephant(
     current_ids: (usize, usize),
     shortest_paths: &HashMap<(usize, usize), usize>,
     valves: &HashMap<usize, Valve>,
@@ -125,7 +169,7 @@ fn search_best_path_with_elephant(
     flow_rates.into_iter().max().unwrap()
 }
 
-fn find_best_flow_with_elephant(valves: &HashMap<usize, Valve>, start: usize, time: usize) -> usize {
+pub fn find_best_flow_with_elephant(valves: &HashMap<usize, Valve>, start: usize, time: usize) -> usize {
     let shortest_paths = build_shortest_paths(start, &valves);
 
     search_best_path_with_elephant(
@@ -138,7 +182,7 @@ fn find_best_flow_with_elephant(valves: &HashMap<usize, Valve>, start: usize, ti
     )
 }
 
-fn build_shortest_paths(start: usize, valves: &HashMap<usize, Valve>) -> HashMap<(usize, usize), usize> {
+pub fn build_shortest_paths(start: usize, valves: &HashMap<usize, Valve>) -> HashMap<(usize, usize), usize> {
     let mut openable_valves: HashSet<usize> =
         valves.into_iter()
               .flat_map(|(&id, v)| if v.flow == 0 { None } else { Some(id) })
@@ -169,35 +213,22 @@ fn build_shortest_paths(start: usize, valves: &HashMap<usize, Valve>) -> HashMap
     permutations
 }
 
-fn build_shortest_paths_from_valve(start: usize, target_nodes: &HashSet<usize>, valves: &HashMap<usize, Valve>)
+pub fn build_shortest_paths_from_valve(start: usize, target_nodes: &HashSet<usize>, valves: &HashMap<usize, Valve>)
                                    -> Vec<((usize, usize), usize)> {
-    let mut nodes: HashMap<usize, usize> = HashMap::new();
-    let mut to_visit: VecDeque<(usize, usize)> = VecDeque::new();
-    to_visit.push_back((start, 0));
-    let mut visited = HashSet::new();
-    visited.insert(start);
-
-    while let Some((id, depth)) = to_visit.pop_front() {
-        let valve = valves.get(&id).unwrap();
-
-        for &link in &valve.links {
-            if !visited.contains(&link) {
-                if target_nodes.contains(&link) {
-                    nodes.insert(link, depth + 1);
-                }
-                to_visit.push_back((link, depth + 1));
-                visited.insert(link);
-            }
-        }
-    }
-
-    nodes.into_iter().map(|(end, path_len)| ((start, end), path_len)).collect()
+    reachable(start, |id| valves.get(id).unwrap().links.clone())
+        .into_iter()
+        .filter(|(end, _)| *end != start && target_nodes.contains(end))
+        .map(|(end, path_len)| ((start, end), path_len))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
-    use crate::day_16::{build_shortest_paths, find_best_flow, find_best_flow_with_elephant, parse_input, Valve};
+    use crate::day_16::{
+        build_shortest_paths, find_best_flow, find_best_flow_with_elephant, id_to_usize, parse_input, usize_to_id,
+        Valve,
+    };
 
     fn sample_valves() -> HashMap<usize, Valve> {
         let list: Vec<(usize, Valve)> = vec![
@@ -229,7 +260,7 @@ Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II".to_string();
 
-        let map = parse_input(&sample_input);
+        let map = parse_input(&sample_input).unwrap();
 
         for (id, valve) in sample_valves() {
             assert_eq!(
@@ -314,4 +345,40 @@ Valve JJ has flow rate=21; tunnel leads to valve II".to_string();
             1707
         );
     }
+
+    #[test]
+    fn reports_invalid_valves() {
+        let position = |input: &str| parse_input(input).map(|_| ()).map_err(|err| (err.line, err.column, err.text));
+
+        assert_eq!(
+            position("Valve AA has flow rate=0; tunnels lead to valves BB, AA\nValve BB has flow rate=x; tunnel leads to valve AA"),
+            Err((2, 24, "x".to_string()))
+        );
+        assert_eq!(
+            position("Valve AA has flow rate=0; tunnels lead to valves BB, A1"),
+            Err((1, 54, "A1".to_string()))
+        );
+        assert_eq!(
+            position("Valve AA has flow rate=0; tunnels lead to valves BB, CC\nValve BB has flow rate=1; tunnel leads to valve AA"),
+            Err((1, 1, "Valve AA has flow rate=0; tunnels lead to valves BB, CC".to_string()))
+        );
+        assert_eq!(
+            position("Valve BB has flow rate=0; tunnel leads to valve BB"),
+            Err((1, 1, "Valve BB has flow rate=0; tunnel leads to valve BB".to_string()))
+        );
+        assert_eq!(
+            position("Valve AA has flow rate=0; tunnels go to valves AA"),
+            Err((1, 27, "tunnels go to valves AA".to_string()))
+        );
+
+        let unknown = parse_input("Valve AA has flow rate=0; tunnels lead to valves ZZ, AA").unwrap_err();
+        assert_eq!(unknown.message, "Expected tunnels to lead to known valves, not ZZ");
+    }
+
+    #[test]
+    fn can_round_trip_ids() {
+        for id in ["AA", "AZ", "BA", "ZA", "ZZ"] {
+            assert_eq!(usize_to_id(id_to_usize(id)), id);
+        }
+    }
 }
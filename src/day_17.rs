@@ -4,12 +4,13 @@
 
 use std::collections::HashMap;
 use std::fmt::{Debug};
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::ParseError;
 use itertools::Itertools;
 use crate::day_17::GasJet::{LEFT, RIGHT};
 
 #[derive(Eq, PartialEq, Debug)]
-enum GasJet {
+pub enum GasJet {
     LEFT,
     RIGHT,
 }
@@ -27,19 +28,19 @@ impl TryFrom<char> for GasJet {
 }
 
 #[derive(Eq, PartialEq, Debug, Clone, Hash)]
-struct Shape {
-    width: u8,
-    bitmap: Vec<u8>,
+pub struct Shape {
+    pub width: u8,
+    pub bitmap: Vec<u8>,
 }
 
 #[derive(Eq, PartialEq)]
-struct Column {
-    rows: Vec<u8>,
-    current_shape: Option<Shape>,
-    shape_x: u8,
-    shape_y: usize,
-    deepest_fall: usize,
-    skipped: usize,
+pub struct Column {
+    pub rows: Vec<u8>,
+    pub current_shape: Option<Shape>,
+    pub shape_x: u8,
+    pub shape_y: usize,
+    pub deepest_fall: usize,
+    pub skipped: usize,
 }
 
 impl Column {
@@ -144,28 +145,50 @@ impl Column {
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-17-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 17.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-17-input").expect("Failed to read file");
-    let jets = parse_input(&contents);
-
-    println!(
-        "After 2022 rocks the pile is {} units high",
-        simulate_rocks(&jets, 2022).height()
-    );
-
-    println!(
-        "After 1,000,000,000,000 rocks the pile is {} units high",
-        simulate_rocks(&jets, 1_000_000_000_000).height()
-    )
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-17-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 17.
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Parsed = Vec<GasJet>;
+    const DAY: usize = 17;
+
+    fn parse(&self, input: &str) -> Result<Vec<GasJet>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, jets: &Vec<GasJet>) -> Answer {
+        simulate_rocks(jets, 2022).height().into()
+    }
+
+    fn part_two(&self, jets: &Vec<GasJet>) -> Answer {
+        simulate_rocks(jets, 1_000_000_000_000).height().into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("After 2022 rocks the pile is {} units high", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("After 1,000,000,000,000 rocks the pile is {} units high", answer)
+    }
 }
 
-fn parse_input(input: &String) -> Vec<GasJet> {
-    input.chars().flat_map(GasJet::try_from).collect()
+pub fn parse_input(input: &str) -> Result<Vec<GasJet>, ParseError> {
+    let pattern = input.trim_end();
+    if pattern.is_empty() {
+        return Err(ParseError::new(input, input, "Expected a pattern of jets"));
+    }
+
+    pattern.char_indices()
+           .map(|(pos, c)| {
+               GasJet::try_from(c)
+                   .map_err(|_| ParseError::new(input, &pattern[pos..pos + c.len_utf8()], "Expected a jet: < or >"))
+           })
+           .collect()
 }
 
-fn simulate_rocks(jets: &Vec<GasJet>, rock_count: usize) -> Column {
+pub fn simulate_rocks(jets: &Vec<GasJet>, rock_count: usize) -> Column {
     let dash: Shape = Shape { width: 4, bitmap: vec![0b1111] };
     let plus: Shape = Shape { width: 3, bitmap: vec![0b010, 0b111, 0b010] };
     let angle: Shape = Shape { width: 3, bitmap: vec![0b001, 0b001, 0b111] };
@@ -232,7 +255,7 @@ mod tests {
     fn can_parse() {
         let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>".to_string();
 
-        assert_eq!(parse_input(&input), sample_jets());
+        assert_eq!(parse_input(&input), Ok(sample_jets()));
     }
 
     #[test]
@@ -273,4 +296,12 @@ mod tests {
         assert_eq!(simulate_rocks(&sample_jets(), 2022).height(), 3068);
         assert_eq!(simulate_rocks(&sample_jets(), 1_000_000_000_000).height(), 1_514_285_714_288)
     }
+
+    #[test]
+    fn reports_invalid_jets() {
+        let bad_jet = parse_input(">>><<>^<").unwrap_err();
+        assert_eq!((bad_jet.line, bad_jet.column, bad_jet.text.as_str()), (1, 7, "^"));
+
+        assert!(parse_input("\n").is_err());
+    }
 }
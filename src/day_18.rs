@@ -2,117 +2,109 @@
 //!
 //!
 
-use std::collections::{HashSet, VecDeque};
-use std::fs;
+use std::collections::HashSet;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError};
+use crate::util::point::Point3;
+use crate::util::search::reachable;
 use itertools::Itertools;
 
-type Coordinate = (isize, isize, isize);
-
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-18-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 18.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-18-input").expect("Failed to read file");
-    let coords = parse_input(&contents);
-
-    println!(
-        "The surface area is {}.",
-        get_surface_area(&coords)
-    );
-
-    println!(
-        "The external surface area is {}.",
-        get_external_surface_area(&coords)
-    );
-}
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-18-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 18.
+pub struct Day18;
 
-fn parse_input(input: &String) -> Vec<Coordinate> {
-    input.trim().lines().map(parse_coordinate).collect()
-}
+impl Solution for Day18 {
+    type Parsed = Vec<Point3>;
+    const DAY: usize = 18;
 
-fn parse_coordinate(line: &str) -> Coordinate {
-    let mut values = line.split(',').flat_map(|s| s.parse::<isize>());
+    fn parse(&self, input: &str) -> Result<Vec<Point3>, ParseError> {
+        parse_input(input)
+    }
 
-    (values.next().unwrap(), values.next().unwrap(), values.next().unwrap())
-}
+    fn part_one(&self, coords: &Vec<Point3>) -> Answer {
+        get_surface_area(coords).into()
+    }
 
-fn get_surface_area(coords: &Vec<Coordinate>) -> usize {
-    let mut surface_area = 0;
-    let mut coords_seen: HashSet<Coordinate> = HashSet::new();
-    let deltas = vec![
-        (1, 0, 0),
-        (0, 1, 0),
-        (0, 0, 1),
-    ];
-
-    for &(x, y, z) in coords {
-        surface_area += 6;
-        coords_seen.insert((x, y, z));
-        for &(dx, dy, dz) in deltas.iter() {
-            if coords_seen.contains(&(x + dx, y + dy, z + dz)) {
-                surface_area -= 2;
-            }
+    fn part_two(&self, coords: &Vec<Point3>) -> Answer {
+        get_external_surface_area(coords).into()
+    }
 
-            if coords_seen.contains(&(x - dx, y - dy, z - dz)) {
-                surface_area -= 2;
-            }
-        }
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The surface area is {}.", answer)
     }
 
-    surface_area
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The external surface area is {}.", answer)
+    }
 }
 
-fn get_external_surface_area(coords: &Vec<Coordinate>) -> usize {
-    let (x_min, x_max) = coords.iter().map(|&c| c.0).minmax().into_option().unwrap();
-    let (y_min, y_max) = coords.iter().map(|&c| c.1).minmax().into_option().unwrap();
-    let (z_min, z_max) = coords.iter().map(|&c| c.2).minmax().into_option().unwrap();
-
-    let coord_set: HashSet<Coordinate> = coords.into_iter().map(|&c| c).collect();
-    let mut visited: HashSet<Coordinate> = HashSet::new();
-    let mut surface_area = 0;
-
-    let mut to_process: VecDeque<Coordinate> = VecDeque::new();
-    to_process.push_back((x_min - 1, y_min - 1, z_min - 1));
-
-    let deltas = vec![
-        (1, 0, 0),
-        (0, 1, 0),
-        (0, 0, 1),
-    ];
+pub fn parse_input(input: &str) -> Result<Vec<Point3>, ParseError> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err(ParseError::new(input, input, "Expected at least one cube"));
+    }
 
-    while let Some((x, y, z)) = to_process.pop_front() {
-        if visited.contains(&(x, y, z)) {
-            continue;
-        }
+    parse_lines(trimmed, parse_coordinate).map_err(|err| err.within(input, trimmed))
+}
 
-        if coord_set.contains(&(x, y, z)) {
-            surface_area += 1;
-            continue;
-        }
+pub fn parse_coordinate(line: &str) -> Result<Point3, ParseError> {
+    let values: Vec<&str> = line.split(',').collect();
 
-        if x < x_min - 1 || x > x_max + 1
-            || y < y_min - 1 || y > y_max + 1
-            || z < z_min - 1|| z > z_max + 1 {
-            continue;
-        }
+    match values[..] {
+        [x, y, z] => Ok(Point3::new(parse_number(line, x)?, parse_number(line, y)?, parse_number(line, z)?)),
+        _ => Err(ParseError::new(line, line, "Expected a coordinate: x,y,z"))
+    }
+}
 
-        visited.insert((x, y, z));
+pub fn get_surface_area(coords: &[Point3]) -> usize {
+    let mut surface_area = 0;
+    let mut coords_seen: HashSet<Point3> = HashSet::new();
 
-        for &(dx, dy, dz) in deltas.iter() {
-            to_process.push_back((x + dx, y + dy, z + dz));
-            to_process.push_back((x - dx, y - dy, z - dz));
+    for &coord in coords {
+        surface_area += 6;
+        coords_seen.insert(coord);
+        for neighbour in coord.neighbours() {
+            if coords_seen.contains(&neighbour) {
+                surface_area -= 2;
+            }
         }
     }
 
     surface_area
 }
 
+/// Flood fill the air around the droplet, within a box one larger than the droplet on every side, counting the faces
+/// of the droplet that the air touches
+pub fn get_external_surface_area(coords: &[Point3]) -> usize {
+    let (x_min, x_max) = coords.iter().map(|c| c.x).minmax().into_option().unwrap();
+    let (y_min, y_max) = coords.iter().map(|c| c.y).minmax().into_option().unwrap();
+    let (z_min, z_max) = coords.iter().map(|c| c.z).minmax().into_option().unwrap();
+
+    let coord_set: HashSet<Point3> = coords.iter().copied().collect();
+    let in_bounds = |&Point3 { x, y, z }: &Point3| {
+        (x_min - 1..=x_max + 1).contains(&x)
+            && (y_min - 1..=y_max + 1).contains(&y)
+            && (z_min - 1..=z_max + 1).contains(&z)
+    };
+
+    let air = reachable(Point3::new(x_min - 1, y_min - 1, z_min - 1), |coord| {
+        coord.neighbours().into_iter().filter(|next| in_bounds(next) && !coord_set.contains(next)).collect::<Vec<_>>()
+    });
+
+    air.keys()
+       .flat_map(|coord| coord.neighbours())
+       .filter(|next| coord_set.contains(next))
+       .count()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day_18::{Coordinate, get_external_surface_area, get_surface_area, parse_input};
+    use crate::day_18::{get_external_surface_area, get_surface_area, parse_input};
+    use crate::util::point::Point3;
 
-    fn sample_coords() -> Vec<Coordinate> {
+    fn sample_coords() -> Vec<Point3> {
         vec![
             (2, 2, 2),
             (1, 2, 2),
@@ -127,7 +119,7 @@ mod tests {
             (3, 2, 5),
             (2, 1, 5),
             (2, 3, 5),
-        ]
+        ].into_iter().map(Point3::from).collect()
     }
 
     #[test]
@@ -146,7 +138,7 @@ mod tests {
 2,1,5
 2,3,5".to_string();
 
-        assert_eq!(parse_input(&input), sample_coords());
+        assert_eq!(parse_input(&input), Ok(sample_coords()));
     }
 
     #[test]
@@ -158,4 +150,15 @@ mod tests {
     fn can_get_external_surface_area() {
         assert_eq!(get_external_surface_area(&sample_coords()), 58)
     }
+
+    #[test]
+    fn reports_invalid_cubes() {
+        let bad_number = parse_input("\n2,2,2\n1,z,2").unwrap_err();
+        assert_eq!((bad_number.line, bad_number.column, bad_number.text.as_str()), (3, 3, "z"));
+
+        let missing_axis = parse_input("2,2,2\n1,2").unwrap_err();
+        assert_eq!((missing_axis.line, missing_axis.column, missing_axis.text.as_str()), (2, 1, "1,2"));
+
+        assert!(parse_input(" \n").is_err());
+    }
 }
@@ -3,40 +3,48 @@
 //!
 
 use std::collections::{HashSet, VecDeque};
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 use crate::day_19::Resource::{CLAY, GEODE, OBSIDIAN, ORE};
 
 #[derive(Eq, PartialEq, Debug, Ord, PartialOrd, Copy, Clone)]
-enum Resource {
+pub enum Resource {
     ORE,
     CLAY,
     OBSIDIAN,
     GEODE,
 }
 
-#[derive(Eq, PartialEq, Debug)]
-struct Blueprint {
-    ore: usize,
-    clay: usize,
-    obsidian: (usize, usize),
-    geode: (usize, usize),
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Blueprint {
+    pub ore: usize,
+    pub clay: usize,
+    pub obsidian: (usize, usize),
+    pub geode: (usize, usize),
 }
 
-impl From<&str> for Blueprint {
+impl TryFrom<&str> for Blueprint {
+    type Error = ParseError;
+
     // Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore.
     // Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
-    fn from(line: &str) -> Self {
-        let mut numbers =
-            line.split_whitespace()
-                // Note also filter `:<id>` as it is not all digits
-                .filter(|&word| word.chars().all(|c| c.is_digit(10)))
-                .map(|word| word.parse::<usize>().unwrap());
-
-        Blueprint {
-            ore: numbers.next().unwrap(),
-            clay: numbers.next().unwrap(),
-            obsidian: (numbers.next().unwrap(), numbers.next().unwrap()),
-            geode: (numbers.next().unwrap(), numbers.next().unwrap()),
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let (_, costs) = split_once(line, line, ":")?;
+        let words: Vec<&str> = costs.split_whitespace().collect();
+
+        match words[..] {
+            [
+                "Each", "ore", "robot", "costs", ore, "ore.",
+                "Each", "clay", "robot", "costs", clay, "ore.",
+                "Each", "obsidian", "robot", "costs", obsidian_ore, "ore", "and", obsidian_clay, "clay.",
+                "Each", "geode", "robot", "costs", geode_ore, "ore", "and", geode_obsidian, "obsidian."
+            ] => Ok(Blueprint {
+                ore: parse_number(line, ore)?,
+                clay: parse_number(line, clay)?,
+                obsidian: (parse_number(line, obsidian_ore)?, parse_number(line, obsidian_clay)?),
+                geode: (parse_number(line, geode_ore)?, parse_number(line, geode_obsidian)?),
+            }),
+            _ => Err(ParseError::new(line, costs, "Expected the cost of the ore, clay, obsidian and geode robots"))
         }
     }
 }
@@ -53,11 +61,11 @@ impl Blueprint {
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
-struct Counts {
-    ore: usize,
-    clay: usize,
-    obsidian: usize,
-    geode: usize,
+pub struct Counts {
+    pub ore: usize,
+    pub clay: usize,
+    pub obsidian: usize,
+    pub geode: usize,
 }
 
 impl Counts {
@@ -106,10 +114,10 @@ impl Counts {
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
-struct State {
-    resources: Counts,
-    robots: Counts,
-    time: usize,
+pub struct State {
+    pub resources: Counts,
+    pub robots: Counts,
+    pub time: usize,
 }
 
 impl State {
@@ -143,28 +151,40 @@ impl State {
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-19-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 19.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-19-input").expect("Failed to read file");
-    let blueprints = parse_input(&contents);
-
-    println!(
-        "The sum of the blueprint's quality level is {}",
-        get_quality_level_sum(&blueprints, 24)
-    );
-
-    println!(
-        "The sum of the blueprint's quality level is {}",
-        get_max_geode_product(&blueprints.into_iter().take(3).collect(), 32)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-19-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 19.
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Parsed = Vec<Blueprint>;
+    const DAY: usize = 19;
+
+    fn parse(&self, input: &str) -> Result<Vec<Blueprint>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, blueprints: &Vec<Blueprint>) -> Answer {
+        get_quality_level_sum(blueprints, 24).into()
+    }
+
+    fn part_two(&self, blueprints: &Vec<Blueprint>) -> Answer {
+        get_max_geode_product(&blueprints.iter().take(3).cloned().collect(), 32).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sum of the blueprint's quality level is {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The sum of the blueprint's quality level is {}", answer)
+    }
 }
 
-fn parse_input(input: &String) -> Vec<Blueprint> {
-    input.lines().map(Blueprint::from).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Blueprint>, ParseError> {
+    parse_lines(input, Blueprint::try_from)
 }
 
-fn get_max_geodes(blueprint: &Blueprint, time_limit: usize) -> usize {
+pub fn get_max_geodes(blueprint: &Blueprint, time_limit: usize) -> usize {
     let mut unseen = VecDeque::new();
     let starting_state = State::new(time_limit);
     let mut best_geodes = 0;
@@ -213,13 +233,13 @@ fn get_max_geodes(blueprint: &Blueprint, time_limit: usize) -> usize {
     best_geodes
 }
 
-fn get_quality_level_sum(blueprints: &Vec<Blueprint>, time_limit: usize) -> usize {
+pub fn get_quality_level_sum(blueprints: &Vec<Blueprint>, time_limit: usize) -> usize {
     blueprints.into_iter().enumerate()
               .map(|(i, bp)| (i + 1) * get_max_geodes(bp, time_limit))
               .sum()
 }
 
-fn get_max_geode_product(blueprints: &Vec<Blueprint>, time_limit: usize) -> usize {
+pub fn get_max_geode_product(blueprints: &Vec<Blueprint>, time_limit: usize) -> usize {
     blueprints.into_iter()
               .map(|bp| get_max_geodes(bp, time_limit))
               .fold(
@@ -253,7 +273,7 @@ Blueprint 2: \
   Each obsidian robot costs 3 ore and 8 clay. \
   Each geode robot costs 3 ore and 12 obsidian.".to_string();
 
-        assert_eq!(parse_input(&input), sample_blueprints());
+        assert_eq!(parse_input(&input), Ok(sample_blueprints()));
     }
 
     #[test]
@@ -275,4 +295,18 @@ Blueprint 2: \
     fn can_get_geode_product() {
         assert_eq!(get_max_geode_product(&sample_blueprints(), 32), 62 * 56)
     }
+
+    #[test]
+    fn reports_invalid_blueprints() {
+        let bad_cost = parse_input(
+            "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs two ore. \
+Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian."
+        ).unwrap_err();
+        assert_eq!((bad_cost.line, bad_cost.column, bad_cost.text.as_str()), (1, 64, "two"));
+
+        let missing_robot = parse_input("Blueprint 1: Each ore robot costs 4 ore.").unwrap_err();
+        assert_eq!((missing_robot.line, missing_robot.column), (1, 13));
+
+        assert!(parse_input("Each ore robot costs 4 ore.").is_err());
+    }
 }
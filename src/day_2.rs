@@ -3,215 +3,777 @@
 //! The task was to interpret a strategy guide for a rock, paper, scissors tournament in two different ways,
 //! calculating a final score if the guide is followed.
 
+use std::collections::HashMap;
 use std::fs;
-use crate::day_2::Move::{Paper, Rock, Scissors};
+use std::path::Path;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 use crate::day_2::Outcome::{Draw, Loss, Win};
 
-/// Encodes the possible moves a player can make
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
-}
+/// The environment variable the runner reads for a rules file to use instead of [`STANDARD_RULES`]
+pub const RULES_VAR: &str = "AOC_DAY_2_RULES";
+
+/// The rules from the puzzle, in the format read by [`Game::from_config`]
+pub const STANDARD_RULES: &str = "\
+# move <name> <score> <opponent's letter> <my letter>
+move Rock 1 A X
+move Paper 2 B Y
+move Scissors 3 C Z
+# beats <move> <moves it beats...>
+beats Rock Scissors
+beats Paper Rock
+beats Scissors Paper
+# outcome <loss|draw|win> <score> <letter>
+outcome loss 0 X
+outcome draw 3 Y
+outcome win 6 Z";
+
+/// A move a player can make, by its position in the game's list of moves
+pub type Move = usize;
 
 /// Encodes the possible outcome of a round
-#[derive(Eq, PartialEq, Debug)]
-enum Outcome {
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Outcome {
     Win,
     Loss,
     Draw,
 }
+
+impl Outcome {
+    /// The position of the outcome in [`Game`]'s outcome table
+    fn index(self) -> usize {
+        match self {
+            Loss => 0,
+            Draw => 1,
+            Win => 2,
+        }
+    }
+}
+
+/// How a move is written in the strategy guide, and how much playing it scores
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct MoveRule {
+    pub name: String,
+    pub score: u32,
+    /// The letter used for the move in the opponent's column
+    pub their_letter: String,
+    /// The letter used for the move in my column, when it is read as a move
+    pub my_letter: String,
+}
+
+/// The rules of a rock, paper, scissors style game: the moves, which beat which, and how rounds are scored
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Game {
+    pub moves: Vec<MoveRule>,
+    /// `beats[a][b]` is true if move `a` beats move `b`
+    beats: Vec<Vec<bool>>,
+    /// The score and letter for a loss, draw and win, in that order
+    outcomes: [(u32, String); 3],
+}
+
 /// A round representing `(opponent's move, my move)`
-type Round = (Move, Move);
+pub type Round = (Move, Move);
 
 /// My view of a tournament: all the rounds I participate in
-type Tournament = Vec<Round>;
+pub type Tournament = Vec<Round>;
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 //
-/// - The puzzle input is expected to be at `<project_root>/res/day-2-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 2.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-2-input").expect("Failed to read file");
-    let part_1_tournament = parse_strategy(&contents, parse_moves_line);
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-2-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 2.
+/// - The rules are [`STANDARD_RULES`], the runner uses [`Day2::with_rules`] when [`RULES_VAR`] names a rules file.
+pub struct Day2 {
+    game: Game,
+    /// The name of the rules, when they aren't [`STANDARD_RULES`]
+    rules: Option<String>,
+}
+
+impl Default for Day2 {
+    fn default() -> Self {
+        Day2 { game: Game::standard(), rules: None }
+    }
+}
+
+impl Day2 {
+    /// Play the guide with other rules, e.g. from [`Game::read`]. Answers are recorded separately for each `name`.
+    pub fn with_rules(name: &str, game: Game) -> Day2 {
+        Day2 { game, rules: Some(name.to_string()) }
+    }
+}
+
+impl Solution for Day2 {
+    /// The guide read as moves and as outcomes. With some rules only one of these readings is valid, the part that
+    /// needs the other is then unanswered.
+    type Parsed = (Option<Tournament>, Option<Tournament>);
+    const DAY: usize = 2;
+
+    fn variant(&self) -> Option<&str> {
+        self.rules.as_deref()
+    }
+
+    fn parse(&self, input: &str) -> Result<(Option<Tournament>, Option<Tournament>), ParseError> {
+        let moves = parse_strategy(input, &self.game, parse_moves_line);
+        let outcomes = parse_strategy(input, &self.game, parse_outcome_line);
+
+        match (moves, outcomes) {
+            (Err(err), Err(_)) => Err(err),
+            (moves, outcomes) => Ok((moves.ok(), outcomes.ok())),
+        }
+    }
+
+    fn part_one(&self, (moves_tournament, _): &(Option<Tournament>, Option<Tournament>)) -> Answer {
+        moves_tournament.as_ref().map_or(Answer::Empty, |tournament| score_tournament(&self.game, tournament).into())
+    }
+
+    fn part_two(&self, (_, outcomes_tournament): &(Option<Tournament>, Option<Tournament>)) -> Answer {
+        outcomes_tournament.as_ref().map_or(Answer::Empty, |tournament| score_tournament(&self.game, tournament).into())
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        match answer {
+            Answer::Empty => "The guide can't be read as moves with these rules".to_string(),
+            _ => format!("Following the guide assuming moves, my score would be: {}", answer),
+        }
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        match answer {
+            Answer::Empty => "The guide can't be read as outcomes with these rules".to_string(),
+            _ => format!("Following the guide assuming outcomes, my score would be: {}", answer),
+        }
+    }
+}
+
+impl Game {
+    /// The rules from the puzzle
+    pub fn standard() -> Game {
+        Game::from_config(STANDARD_RULES).expect("The standard rules are valid")
+    }
+
+    /// Read the rules from a file, errors are reported with the position in that file
+    pub fn read(path: &Path) -> Result<Game, ParseError> {
+        let path_text = path.display().to_string();
+        let config = fs::read_to_string(path)
+            .map_err(|err| ParseError::at(1, 1, &path_text, format!("Expected a readable rules file: {}", err)))?;
+
+        Game::from_config(&config)
+            .map_err(|err| ParseError { message: format!("{} in rules file {}", err.message, path_text), ..err })
+    }
+
+    /// Parse the rules, see [`STANDARD_RULES`] for the format. Blank lines, and lines starting with `#`, are ignored.
+    ///
+    /// There must be an odd number of moves, at least three, and each pair of different moves must have exactly one
+    /// that beats the other, with every move beating half of the others. Each column's letters must be unique.
+    pub fn from_config(config: &str) -> Result<Game, ParseError> {
+        let lines: Vec<(&str, Vec<&str>)> =
+            config.lines()
+                  .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+                  .map(|line| (line, line.split_whitespace().collect()))
+                  .collect();
+
+        let mut moves: Vec<MoveRule> = Vec::new();
+        // Where each move is named in the config, to point errors about that move at
+        let mut names: Vec<&str> = Vec::new();
+        let end = &config[config.len()..];
+        for (line, words) in lines.iter().filter(|(_, words)| words[0] == "move") {
+            let [_, name, score, their_letter, my_letter] = words[..] else {
+                return Err(ParseError::new(config, line, "Expected 'move <name> <score> <letter> <letter>'"));
+            };
 
-    println!(
-        "Following the guide assuming moves, my score would be: {}",
-        score_tournament(&part_1_tournament)
-    );
+            if moves.iter().any(|rule| rule.name == name) {
+                return Err(ParseError::new(config, name, "Expected each move to have a different name"));
+            }
+            unique_letter(config, their_letter, moves.iter().map(|rule| rule.their_letter.as_str()))?;
+            unique_letter(config, my_letter, moves.iter().map(|rule| rule.my_letter.as_str()))?;
 
-    let part_2_tournament = parse_strategy(&contents, parse_outcome_line);
+            names.push(name);
+            moves.push(MoveRule {
+                name: name.to_string(),
+                score: parse_number(config, score)?,
+                their_letter: their_letter.to_string(),
+                my_letter: my_letter.to_string(),
+            })
+        }
 
-    println!(
-        "Following the guide assuming outcomes, my score would be: {}",
-        score_tournament(&part_2_tournament)
-    );
+        if moves.len().is_multiple_of(2) {
+            return Err(ParseError::new(config, names.last().unwrap_or(&end), "Expected an odd number of moves"));
+        }
+
+        // With fewer, some outcomes can't happen, e.g. a game with one move can't be won
+        if moves.len() < 3 {
+            return Err(ParseError::new(config, names[0], "Expected at least three moves"));
+        }
+
+        let move_named = |name: &str| {
+            moves.iter()
+                 .position(|rule| rule.name == name)
+                 .ok_or_else(|| ParseError::new(config, name, "Expected the name of a move"))
+        };
+
+        let mut beats = vec![vec![false; moves.len()]; moves.len()];
+        let mut outcomes: [Option<(u32, String)>; 3] = [None, None, None];
+
+        for (line, words) in &lines {
+            match words[..] {
+                ["move", ..] => {}
+                ["beats", winner, ref losers @ ..] if !losers.is_empty() => {
+                    let winner = move_named(winner)?;
+                    for &loser in losers {
+                        if move_named(loser)? == winner {
+                            return Err(ParseError::new(config, loser, "Expected a move not to beat itself"));
+                        }
+                        beats[winner][move_named(loser)?] = true;
+                    }
+                }
+                ["outcome", outcome, score, letter] => {
+                    let outcome = match outcome {
+                        "loss" => Loss,
+                        "draw" => Draw,
+                        "win" => Win,
+                        _ => return Err(ParseError::new(config, outcome, "Expected an outcome: loss, draw or win")),
+                    };
+                    unique_letter(config, letter, outcomes.iter().flatten().map(|(_, letter)| letter.as_str()))?;
+                    outcomes[outcome.index()] = Some((parse_number(config, score)?, letter.to_string()));
+                }
+                _ => return Err(ParseError::new(
+                    config,
+                    line,
+                    "Expected 'move <name> <score> <letter> <letter>', 'beats <move> <moves...>' or \
+                     'outcome <loss|draw|win> <score> <letter>'",
+                )),
+            }
+        }
+
+        let half = moves.len() / 2;
+        for (a, rule) in moves.iter().enumerate() {
+            if let Some(b) = (0..moves.len()).find(|&b| b != a && beats[a][b] == beats[b][a]) {
+                return Err(ParseError::new(
+                    config,
+                    names[a],
+                    format!("Expected exactly one of {} and {} to beat the other", rule.name, moves[b].name),
+                ));
+            }
+
+            if beats[a].iter().filter(|&&beaten| beaten).count() != half {
+                return Err(ParseError::new(config, names[a], format!("Expected {} to beat {} moves", rule.name, half)));
+            }
+        }
+
+        let [Some(loss), Some(draw), Some(win)] = outcomes else {
+            return Err(ParseError::new(config, end, "Expected a score and letter for each of loss, draw and win"));
+        };
+
+        Ok(Game { moves, beats, outcomes: [loss, draw, win] })
+    }
+
+    pub fn beats(&self, a: Move, b: Move) -> bool {
+        self.beats[a][b]
+    }
+
+    /// The outcome of a round from my point of view
+    pub fn outcome(&self, &(their_move, my_move): &Round) -> Outcome {
+        if my_move == their_move {
+            Draw
+        } else if self.beats(my_move, their_move) {
+            Win
+        } else {
+            Loss
+        }
+    }
+
+    pub fn move_score(&self, mv: Move) -> u32 {
+        self.moves[mv].score
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcomes[outcome.index()].0
+    }
+
+    /// The move written as `letter` in the opponent's column
+    pub fn their_move(&self, letter: &str) -> Option<Move> {
+        self.moves.iter().position(|rule| rule.their_letter == letter)
+    }
+
+    /// The move written as `letter` in my column
+    pub fn my_move(&self, letter: &str) -> Option<Move> {
+        self.moves.iter().position(|rule| rule.my_letter == letter)
+    }
+
+    /// The outcome written as `letter` in my column
+    pub fn outcome_for(&self, letter: &str) -> Option<Outcome> {
+        [Loss, Draw, Win].into_iter().find(|outcome| self.outcomes[outcome.index()].1 == letter)
+    }
+}
+
+/// Check a letter hasn't already been used in the same column
+fn unique_letter<'a, I>(config: &str, letter: &str, mut used: I) -> Result<(), ParseError>
+    where I: Iterator<Item=&'a str>
+{
+    match used.any(|used| used == letter) {
+        true => Err(ParseError::new(config, letter, "Expected each letter in a column to be different")),
+        false => Ok(()),
+    }
+}
+
+/// List letters for an error message, e.g. "A, B or C"
+fn describe_letters<'a, I: Iterator<Item=&'a str>>(letters: I) -> String {
+    let letters: Vec<&str> = letters.collect();
+    match letters.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
 }
 
 /// Parse a strategy guide, taking the syntax that maps a line in the guide to a `Round` played so this can be reused
 /// in both parts.
-fn parse_strategy(strategy: &String, syntax: fn(&str) -> Round) -> Tournament {
-    strategy.lines()
-            .map(syntax)
-            .collect()
+pub fn parse_strategy(
+    strategy: &str,
+    game: &Game,
+    syntax: fn(&Game, &str) -> Result<Round, ParseError>,
+) -> Result<Tournament, ParseError> {
+    parse_lines(strategy, |line| syntax(game, line))
+}
+
+/// Parse the opponent's move from the first column of a line
+fn parse_their_move(game: &Game, line: &str, letter: &str) -> Result<Move, ParseError> {
+    game.their_move(letter).ok_or_else(|| {
+        let letters = describe_letters(game.moves.iter().map(|rule| rule.their_letter.as_str()));
+        ParseError::new(line, letter, format!("Expected a move: {}", letters))
+    })
 }
 
 /// The line syntax for part 1
-fn parse_moves_line(line: &str) -> Round {
-    let (part_1, part_2) = line.split_once(' ').unwrap();
-    (
-        parse_move(part_1).unwrap(),
-        parse_move(part_2).unwrap()
-    )
+pub fn parse_moves_line(game: &Game, line: &str) -> Result<Round, ParseError> {
+    let (part_1, part_2) = split_once(line, line, " ")?;
+    Ok((
+        parse_their_move(game, line, part_1)?,
+        game.my_move(part_2).ok_or_else(|| {
+            let letters = describe_letters(game.moves.iter().map(|rule| rule.my_letter.as_str()));
+            ParseError::new(line, part_2, format!("Expected a move: {}", letters))
+        })?,
+    ))
 }
 
 /// The kine syntax for part 2
-fn parse_outcome_line(line: &str) -> Round {
-    let (part_1, part_2) = line.split_once(' ').unwrap();
-    resolve_outcome(
-        parse_move(part_1).unwrap(),
-        parse_outcome(part_2).unwrap(),
-    )
+pub fn parse_outcome_line(game: &Game, line: &str) -> Result<Round, ParseError> {
+    let (part_1, part_2) = split_once(line, line, " ")?;
+    Ok(resolve_outcome(
+        game,
+        parse_their_move(game, line, part_1)?,
+        game.outcome_for(part_2).ok_or_else(|| {
+            let letters = describe_letters(game.outcomes.iter().map(|(_, letter)| letter.as_str()));
+            ParseError::new(line, part_2, format!("Expected an outcome: {}", letters))
+        })?,
+    ))
 }
 
-/// Parse a part of an input line as a move
-fn parse_move(chr: &str) -> Option<Move> {
-    match chr {
-        "A" | "X" => Some(Rock),
-        "B" | "Y" => Some(Paper),
-        "C" | "Z" => Some(Scissors),
-        _ => None
-    }
-}
+/// Calculate the move to make given my opponent's expected move and a desired outcome. If more than one move gives
+/// that outcome, the highest scoring is picked.
+pub fn resolve_outcome(game: &Game, their_move: Move, outcome: Outcome) -> Round {
+    let my_move = (0..game.moves.len())
+        .filter(|&my_move| game.outcome(&(their_move, my_move)) == outcome)
+        .rev()
+        .max_by_key(|&my_move| game.move_score(my_move))
+        .expect("Every outcome is possible against every move");
 
-/// Parse part of an input line as and expected outcome for a round
-fn parse_outcome(chr: &str) -> Option<Outcome> {
-    match chr {
-        "X" => Some(Loss),
-        "Y" => Some(Draw),
-        "Z" => Some(Win),
-        _ => None
-    }
+    (their_move, my_move)
 }
 
-/// Calculate the move to make given my opponent's expected move and a desired outcome
-fn resolve_outcome(their_move: Move, outcome: Outcome) -> Round {
-    let my_move = match outcome {
-        Loss => loss_for(their_move),
-        Draw => draw_for(their_move),
-        Win => win_for(their_move)
-    };
+/// Calculate the score for a single round, from the move I threw and the round's outcome
+pub fn score_round(game: &Game, round: &Round) -> u32 {
+    game.outcome_score(game.outcome(round)) + game.move_score(round.1)
+}
 
-    (their_move, my_move)
+/// Calculate the sum of the scores for all rounds I layed in
+pub fn score_tournament(game: &Game, tournament: &Tournament) -> u32 {
+    tournament.iter().map(|round| score_round(game, round)).sum()
 }
 
-/// Given an expected move, what move should be thrown to win a round
-fn win_for(mv: Move) -> Move {
-    match mv {
-        Rock => Paper,
-        Paper => Scissors,
-        Scissors => Rock,
-    }
+/// Limits on the responses [`best_strategy`] can choose, built up from [`Constraints::new`] which allows anything
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct Constraints {
+    wins: Option<usize>,
+    max_repeats: Option<usize>,
+    max_score: Option<u32>,
 }
 
-/// Given an expected move, what move should be thrown to draw a round
-fn draw_for(mv: Move) -> Move {
-    mv
+/// What the solver needs to know about the rounds chosen so far to apply the constraints. Anything that isn't
+/// constrained is left at its default, so that choices that only differ in that way are merged.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Default)]
+struct SolverKey {
+    wins: usize,
+    last_move: Option<Move>,
+    repeats: usize,
+    score: u32,
 }
 
-/// Given an expected move, what move should be thrown to win a round
-fn loss_for(mv: Move) -> Move {
-    match mv {
-        Rock => Scissors,
-        Paper => Rock,
-        Scissors => Paper,
+impl Constraints {
+    pub fn new() -> Constraints {
+        Constraints::default()
+    }
+
+    /// Win exactly `wins` rounds
+    pub fn exactly_wins(self, wins: usize) -> Constraints {
+        Constraints { wins: Some(wins), ..self }
+    }
+
+    /// Never play the same move more than `max_repeats` times in a row, e.g. 2 for never three times in a row
+    pub fn max_repeats(self, max_repeats: usize) -> Constraints {
+        Constraints { max_repeats: Some(max_repeats), ..self }
+    }
+
+    /// Score at most `max_score` in total
+    pub fn max_score(self, max_score: u32) -> Constraints {
+        Constraints { max_score: Some(max_score), ..self }
+    }
+
+    /// The state after playing `round` with a total score of `score`, or `None` if that breaks a constraint, or makes
+    /// it impossible to meet one in the `remaining` rounds
+    fn advance(&self, game: &Game, key: &SolverKey, round: &Round, score: u32, remaining: usize) -> Option<SolverKey> {
+        let mut next = SolverKey::default();
+
+        if let Some(target) = self.wins {
+            next.wins = key.wins + usize::from(game.outcome(round) == Win);
+            if next.wins > target || next.wins + remaining < target {
+                return None;
+            }
+        }
+
+        if let Some(max_repeats) = self.max_repeats {
+            next.last_move = Some(round.1);
+            next.repeats = if key.last_move == Some(round.1) { key.repeats + 1 } else { 1 };
+            if next.repeats > max_repeats {
+                return None;
+            }
+        }
+
+        if let Some(max_score) = self.max_score {
+            next.score = score;
+            if score > max_score {
+                return None;
+            }
+        }
+
+        Some(next)
     }
 }
 
-/// Calculate the score for a single round
-fn score_round(round: &Round) -> u32 {
-    score_result(round) + score_move(round)
+/// Parse the opponent's moves from the first column of a strategy guide, ignoring the rest of each line
+pub fn parse_opponent_moves(strategy: &str, game: &Game) -> Result<Vec<Move>, ParseError> {
+    parse_lines(strategy, |line| parse_their_move(game, line, line.split(' ').next().unwrap_or(line)))
 }
 
-/// Calculate the part of a round score based on the move I threw
-fn score_move((_, my_move): &Round) -> u32 {
-    match my_move {
-        Rock => 1,
-        Paper => 2,
-        Scissors => 3
+/// Choose my move for each of `their_moves` to get the highest score allowed by the constraints, returning the score
+/// and the rounds played, or `None` if the constraints can't be met. Ties are broken in favour of moves earlier in the
+/// game's config.
+///
+/// This works through the rounds keeping the best total for each distinct state the constraints care about, so the
+/// time and memory used grow with the number of rounds times the number of wins and total scores being tracked.
+pub fn best_strategy(game: &Game, their_moves: &[Move], constraints: &Constraints) -> Option<(u32, Tournament)> {
+    // For each round, how each choice was reached: the index of the choice in the previous round, and my move
+    let mut history: Vec<Vec<(usize, Move)>> = Vec::with_capacity(their_moves.len());
+    let mut current: Vec<(SolverKey, u32)> = vec![(SolverKey::default(), 0)];
+
+    for (round, &their_move) in their_moves.iter().enumerate() {
+        let remaining = their_moves.len() - round - 1;
+        let mut next: Vec<(SolverKey, u32)> = Vec::new();
+        let mut steps: Vec<(usize, Move)> = Vec::new();
+        let mut seen: HashMap<SolverKey, usize> = HashMap::new();
+
+        for (parent, (key, score)) in current.iter().enumerate() {
+            for my_move in 0..game.moves.len() {
+                let score = score + score_round(game, &(their_move, my_move));
+                let Some(next_key) = constraints.advance(game, key, &(their_move, my_move), score, remaining) else {
+                    continue;
+                };
+
+                match seen.get(&next_key) {
+                    Some(&index) if next[index].1 >= score => {}
+                    Some(&index) => {
+                        next[index].1 = score;
+                        steps[index] = (parent, my_move);
+                    }
+                    None => {
+                        seen.insert(next_key, next.len());
+                        next.push((next_key, score));
+                        steps.push((parent, my_move));
+                    }
+                }
+            }
+        }
+
+        history.push(steps);
+        current = next;
     }
-}
 
-/// Calculate the part of a round score based on the round's outcome
-fn score_result(round: &Round) -> u32 {
-    match round {
-        &(their_move, my_move) if win_for(their_move) == my_move => 6,
-        &(their_move, my_move) if draw_for(their_move) == my_move => 3,
-        (_, _) => 0
+    let (mut index, &(_, score)) = current
+        .iter()
+        .enumerate()
+        .filter(|(_, (key, _))| constraints.wins.is_none_or(|wins| key.wins == wins))
+        .rev()
+        .max_by_key(|(_, (_, score))| *score)?;
+
+    let mut my_moves = Vec::with_capacity(their_moves.len());
+    for steps in history.iter().rev() {
+        let (parent, my_move) = steps[index];
+        my_moves.push(my_move);
+        index = parent;
     }
-}
 
-/// Calculate the sum of the scores for all rounds I layed in
-fn score_tournament(tournament: &Tournament) -> u32 {
-    tournament.into_iter().map(score_round).sum()
+    Some((score, their_moves.iter().copied().zip(my_moves.into_iter().rev()).collect()))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_2::{parse_strategy, parse_moves_line, parse_outcome_line, score_round, score_tournament, Tournament};
-    use crate::day_2::Move::{Paper, Rock, Scissors};
+    use crate::day_2::{
+        best_strategy, Constraints, Day2, Game, Move, parse_opponent_moves, parse_strategy, parse_moves_line,
+        parse_outcome_line, resolve_outcome, score_round, score_tournament, Tournament,
+    };
+    use crate::day_2::Outcome::{Draw, Loss, Win};
+    use crate::solution::{Answer, Solution};
+
+    const ROCK: Move = 0;
+    const PAPER: Move = 1;
+    const SCISSORS: Move = 2;
+    const LIZARD: Move = 3;
+    const SPOCK: Move = 4;
+
+    const RPSLS_RULES: &str = "\
+move Rock 1 A V
+move Paper 2 B W
+move Scissors 3 C X
+move Lizard 4 D Y
+move Spock 5 E Z
+
+beats Rock Scissors Lizard
+beats Paper Rock Spock
+beats Scissors Paper Lizard
+beats Lizard Paper Spock
+beats Spock Rock Scissors
+
+outcome loss 0 L
+outcome draw 3 D
+outcome win 6 W";
 
     #[test]
     fn can_parse() {
         let example_guide = "A Y
 B X
 C Z".to_string();
+        let game = Game::standard();
 
         assert_eq!(
-            parse_strategy(&example_guide, parse_moves_line),
-            sample_moves_tournament()
+            parse_strategy(&example_guide, &game, parse_moves_line),
+            Ok(sample_moves_tournament())
         );
 
         assert_eq!(
-            parse_strategy(&example_guide, parse_outcome_line),
-            sample_outcome_tournament()
+            parse_strategy(&example_guide, &game, parse_outcome_line),
+            Ok(sample_outcome_tournament())
         )
     }
 
+    #[test]
+    fn reports_invalid_lines() {
+        let game = Game::standard();
+
+        let bad_move = parse_strategy("A Y\nD X", &game, parse_moves_line).unwrap_err();
+        assert_eq!((bad_move.line, bad_move.column, bad_move.text.as_str()), (2, 1, "D"));
+        assert_eq!(bad_move.message, "Expected a move: A, B or C");
+
+        let bad_outcome = parse_strategy("A Y\nB X\nC W", &game, parse_outcome_line).unwrap_err();
+        assert_eq!((bad_outcome.line, bad_outcome.column, bad_outcome.text.as_str()), (3, 3, "W"));
+        assert_eq!(bad_outcome.message, "Expected an outcome: X, Y or Z");
+
+        let missing_space = parse_strategy("AY", &game, parse_moves_line).unwrap_err();
+        assert_eq!(missing_space.message, "Expected ' '");
+    }
+
+    #[test]
+    fn can_parse_other_games() {
+        let game = Game::from_config(RPSLS_RULES).unwrap();
+
+        assert_eq!(game.moves.len(), 5);
+        assert!(game.beats(SPOCK, SCISSORS));
+        assert!(!game.beats(SCISSORS, SPOCK));
+
+        assert_eq!(
+            parse_strategy("A Z\nD W\nE V", &game, parse_moves_line),
+            Ok(vec![(ROCK, SPOCK), (LIZARD, PAPER), (SPOCK, ROCK)])
+        );
+        assert_eq!(
+            parse_strategy("A W\nE L\nC D", &game, parse_outcome_line),
+            Ok(vec![(ROCK, SPOCK), (SPOCK, SCISSORS), (SCISSORS, SCISSORS)])
+        );
+
+        let bad_move = parse_strategy("A X\nF V", &game, parse_moves_line).unwrap_err();
+        assert_eq!(bad_move.message, "Expected a move: A, B, C, D or E");
+    }
+
+    #[test]
+    fn can_solve_with_other_rules() {
+        let standard = Day2::default();
+        let parsed = standard.parse("A Y\nB X\nC Z").unwrap();
+        assert_eq!(standard.part_one(&parsed), Answer::from(15u32));
+        assert_eq!(standard.part_two(&parsed), Answer::from(12u32));
+
+        // Z is a move but not an outcome with these rules, so only part 1 can be answered
+        let rpsls = Day2::with_rules("rpsls", Game::from_config(RPSLS_RULES).unwrap());
+        assert_eq!((standard.variant(), rpsls.variant()), (None, Some("rpsls")));
+        let parsed = rpsls.parse("A Z\nD W").unwrap();
+        assert_eq!(rpsls.part_one(&parsed), Answer::from(11u32 + 2));
+        assert_eq!(rpsls.part_two(&parsed), Answer::Empty);
+        assert_eq!(rpsls.describe_part_two(&Answer::Empty), "The guide can't be read as outcomes with these rules");
+
+        let parsed = rpsls.parse("A W\nB L").unwrap();
+        assert_eq!(rpsls.part_two(&parsed), Answer::from(11u32 + 5));
+
+        assert_eq!(rpsls.parse("A Z\nD Q").unwrap_err().text, "Q");
+    }
+
+    #[test]
+    fn reports_invalid_games() {
+        let unbalanced = RPSLS_RULES.replace("beats Rock Scissors Lizard", "beats Rock Scissors Lizard Spock");
+        let error = Game::from_config(&unbalanced).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 6, "Rock"));
+        assert_eq!(error.message, "Expected exactly one of Rock and Spock to beat the other");
+
+        let even = "move Rock 1 A X\nmove Paper 2 B Y\nbeats Paper Rock";
+        assert_eq!(Game::from_config(even).unwrap_err().message, "Expected an odd number of moves");
+
+        let single = "move Rock 1 A X\noutcome loss 0 X\noutcome draw 3 Y\noutcome win 6 Z";
+        let single = Game::from_config(single).unwrap_err();
+        assert_eq!((single.line, single.column, single.text.as_str()), (1, 6, "Rock"));
+        assert_eq!(single.message, "Expected at least three moves");
+
+        let duplicate_letter = RPSLS_RULES.replace("move Lizard 4 D Y", "move Lizard 4 D X");
+        let error = Game::from_config(&duplicate_letter).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (4, 17, "X"));
+        assert_eq!(error.message, "Expected each letter in a column to be different");
+
+        let unknown_move = RPSLS_RULES.replace("beats Spock Rock Scissors", "beats Spock Rock Sissors");
+        let error = Game::from_config(&unknown_move).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (11, 18, "Sissors"));
+        assert_eq!(error.message, "Expected the name of a move");
+
+        let missing_outcome = RPSLS_RULES.replace("outcome draw 3 D", "");
+        let error = Game::from_config(&missing_outcome).unwrap_err();
+        assert_eq!(error.message, "Expected a score and letter for each of loss, draw and win");
+
+        let bad_line = RPSLS_RULES.replace("outcome win 6 W", "outcome win W");
+        let error = Game::from_config(&bad_line).unwrap_err();
+        assert_eq!((error.line, error.column), (15, 1));
+    }
+
     fn sample_moves_tournament() -> Tournament {
         vec![
-            (Rock, Paper),
-            (Paper, Rock),
-            (Scissors, Scissors),
+            (ROCK, PAPER),
+            (PAPER, ROCK),
+            (SCISSORS, SCISSORS),
         ]
     }
 
     fn sample_outcome_tournament() -> Tournament {
         vec![
-            (Rock, Rock),
-            (Paper, Rock),
-            (Scissors, Rock),
+            (ROCK, ROCK),
+            (PAPER, ROCK),
+            (SCISSORS, ROCK),
         ]
     }
 
+    #[test]
+    fn can_resolve_outcomes() {
+        let game = Game::standard();
+        assert_eq!(resolve_outcome(&game, ROCK, Win), (ROCK, PAPER));
+        assert_eq!(resolve_outcome(&game, ROCK, Loss), (ROCK, SCISSORS));
+
+        let game = Game::from_config(RPSLS_RULES).unwrap();
+        assert_eq!(resolve_outcome(&game, ROCK, Win), (ROCK, SPOCK));
+        assert_eq!(resolve_outcome(&game, ROCK, Loss), (ROCK, LIZARD));
+        assert_eq!(resolve_outcome(&game, LIZARD, Draw), (LIZARD, LIZARD));
+    }
+
     #[test]
     fn can_score_round() {
-        assert_eq!(score_round(&(Rock, Paper)), 8);
-        assert_eq!(score_round(&(Paper, Rock)), 1);
-        assert_eq!(score_round(&(Scissors, Scissors)), 6);
+        let game = Game::standard();
+
+        assert_eq!(score_round(&game, &(ROCK, PAPER)), 8);
+        assert_eq!(score_round(&game, &(PAPER, ROCK)), 1);
+        assert_eq!(score_round(&game, &(SCISSORS, SCISSORS)), 6);
+
+        let game = Game::from_config(RPSLS_RULES).unwrap();
+        assert_eq!(score_round(&game, &(SCISSORS, SPOCK)), 11);
+        assert_eq!(score_round(&game, &(SPOCK, SCISSORS)), 3);
     }
 
     #[test]
     fn can_score_tournament() {
+        let game = Game::standard();
+
         assert_eq!(
-            score_tournament(&sample_moves_tournament()),
+            score_tournament(&game, &sample_moves_tournament()),
             15
         );
 
         assert_eq!(
-            score_tournament(&sample_outcome_tournament()),
+            score_tournament(&game, &sample_outcome_tournament()),
             12
         );
     }
+
+    #[test]
+    fn can_find_best_strategy() {
+        let game = Game::standard();
+        let their_moves = parse_opponent_moves("A Y\nB X\nC Z", &game).unwrap();
+        assert_eq!(their_moves, vec![ROCK, PAPER, SCISSORS]);
+
+        assert_eq!(
+            best_strategy(&game, &their_moves, &Constraints::new()),
+            Some((24, vec![(ROCK, PAPER), (PAPER, SCISSORS), (SCISSORS, ROCK)]))
+        );
+
+        assert_eq!(
+            best_strategy(&game, &their_moves, &Constraints::new().exactly_wins(0)),
+            Some((15, vec![(ROCK, ROCK), (PAPER, PAPER), (SCISSORS, SCISSORS)]))
+        );
+
+        let (score, tournament) = best_strategy(&game, &their_moves, &Constraints::new().max_score(20)).unwrap();
+        assert_eq!(score, 20);
+        assert_eq!(score_tournament(&game, &tournament), 20);
+
+        assert_eq!(best_strategy(&game, &their_moves, &Constraints::new().exactly_wins(4)), None);
+        assert_eq!(best_strategy(&game, &their_moves, &Constraints::new().max_score(2)), None);
+    }
+
+    #[test]
+    fn can_limit_repeated_moves() {
+        let game = Game::standard();
+        let their_moves = vec![ROCK; 5];
+
+        assert_eq!(best_strategy(&game, &their_moves, &Constraints::new()).map(|(score, _)| score), Some(40));
+
+        let constraints = Constraints::new().max_repeats(2);
+        let (score, tournament) = best_strategy(&game, &their_moves, &constraints).unwrap();
+        assert_eq!(score, 8 + 8 + 4 + 8 + 8);
+        assert_eq!(score_tournament(&game, &tournament), score);
+        assert!(tournament.windows(3).all(|rounds| rounds[0].1 != rounds[1].1 || rounds[1].1 != rounds[2].1));
+
+        let constraints = Constraints::new().max_repeats(2).exactly_wins(2).max_score(26);
+        let (score, tournament) = best_strategy(&game, &their_moves, &constraints).unwrap();
+        assert_eq!(score, 8 + 8 + 4 + 3 + 3);
+        assert_eq!(tournament.iter().filter(|&&(_, my_move)| my_move == PAPER).count(), 2);
+        assert!(tournament.windows(3).all(|rounds| rounds[0].1 != rounds[1].1 || rounds[1].1 != rounds[2].1));
+    }
+
+    #[test]
+    fn can_find_best_strategy_for_other_games() {
+        let game = Game::from_config(RPSLS_RULES).unwrap();
+        let their_moves = vec![ROCK, ROCK, LIZARD];
+
+        assert_eq!(
+            best_strategy(&game, &their_moves, &Constraints::new().max_repeats(1)),
+            Some((11 + 8 + 9, vec![(ROCK, SPOCK), (ROCK, PAPER), (LIZARD, SCISSORS)]))
+        );
+    }
 }
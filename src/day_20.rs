@@ -3,38 +3,57 @@
 //!
 
 use std::cell::RefCell;
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError};
 use std::rc::Rc;
 
-struct IndexedNumber {
-    value: i64,
-    mix_index: RefCell<usize>,
+pub struct IndexedNumber {
+    pub value: i64,
+    pub mix_index: RefCell<usize>,
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-20-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 20.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-20-input").expect("Failed to read file");
-    let numbers = parse_input(&contents);
-
-    println!(
-        "The sm of the grove coordinates is {}",
-        grove_coords_sum(&numbers, 1, 1)
-    );
-
-    println!(
-        "The sm of the grove coordinates is {}",
-        grove_coords_sum(&numbers, 10, 811589153)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-20-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 20.
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Parsed = Vec<i64>;
+    const DAY: usize = 20;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, numbers: &Vec<i64>) -> Answer {
+        grove_coords_sum(numbers, 1, 1).into()
+    }
+
+    fn part_two(&self, numbers: &Vec<i64>) -> Answer {
+        grove_coords_sum(numbers, 10, 811589153).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sm of the grove coordinates is {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The sm of the grove coordinates is {}", answer)
+    }
 }
 
-fn parse_input(input: &String) -> Vec<i64> {
-    input.lines().map(|l| l.parse::<i64>().unwrap()).collect()
+/// One number per line, the grove coordinates are relative to the 0, so there must be one
+pub fn parse_input(input: &str) -> Result<Vec<i64>, ParseError> {
+    let numbers: Vec<i64> = parse_lines(input, |line| parse_number(line, line))?;
+
+    match numbers.iter().filter(|&&v| v == 0).count() {
+        1 => Ok(numbers),
+        _ => Err(ParseError::new(input, input, "Expected exactly one 0 in the file")),
+    }
 }
 
-fn mix(numbers: &Vec<i64>, cycles: usize) -> Vec<i64> {
+pub fn mix(numbers: &Vec<i64>, cycles: usize) -> Vec<i64> {
     let mut to_mix = Vec::new();
     let mut process_order = Vec::new();
 
@@ -56,7 +75,7 @@ fn mix(numbers: &Vec<i64>, cycles: usize) -> Vec<i64> {
     to_mix.iter().map(|item| item.value).collect()
 }
 
-fn perform_cycle(process_order: &Vec<Rc<IndexedNumber>>, to_mix: &mut Vec<Rc<IndexedNumber>>) {
+pub fn perform_cycle(process_order: &Vec<Rc<IndexedNumber>>, to_mix: &mut Vec<Rc<IndexedNumber>>) {
     let wrap_when_moving = process_order.len() - 1;
 
     for item in process_order {
@@ -80,7 +99,7 @@ fn perform_cycle(process_order: &Vec<Rc<IndexedNumber>>, to_mix: &mut Vec<Rc<Ind
     }
 }
 
-fn mod_add(start: usize, delta: i64, len: usize) -> usize {
+pub fn mod_add(start: usize, delta: i64, len: usize) -> usize {
     if delta == 0 {
         return start
     }
@@ -96,7 +115,7 @@ fn mod_add(start: usize, delta: i64, len: usize) -> usize {
     usize::try_from(new_pos).unwrap()
 }
 
-fn grove_coords_sum(encrypted: &Vec<i64>, cycles: usize, decryption_key: i64) -> i64 {
+pub fn grove_coords_sum(encrypted: &[i64], cycles: usize, decryption_key: i64) -> i64 {
     let with_key = encrypted.iter().map(|&v| v * decryption_key).collect();
     let mixed = mix(&with_key, cycles);
     let zero = mixed.iter().position(|&v| v == 0).unwrap();
@@ -124,7 +143,7 @@ mod tests {
 0
 4".to_string();
 
-        assert_eq!(parse_input(&input), sample_numbers());
+        assert_eq!(parse_input(&input), Ok(sample_numbers()));
     }
 
     #[test]
@@ -158,4 +177,13 @@ mod tests {
         assert_eq!(grove_coords_sum(&sample_numbers(), 1, 1), 3);
         assert_eq!(grove_coords_sum(&sample_numbers(), 10, 811589153), 1623178306);
     }
+
+    #[test]
+    fn reports_invalid_numbers() {
+        let bad_number = parse_input("1\n2\n-x3\n0").unwrap_err();
+        assert_eq!((bad_number.line, bad_number.column, bad_number.text.as_str()), (3, 1, "-x3"));
+
+        assert!(parse_input("1\n2\n3").is_err());
+        assert!(parse_input("1\n0\n0").is_err());
+    }
 }
@@ -3,34 +3,37 @@
 //!
 
 use std::collections::HashMap;
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 use crate::day_21::Monkey::{Op, Value};
 use crate::day_21::Operation::{Left, Right};
 use crate::day_21::OperationChain::{Chain, Operand};
 use crate::day_21::Operator::{Add, Div, Mul, Sub};
 
 #[derive(Eq, PartialEq, Debug, Clone)]
-enum Monkey {
+pub enum Monkey {
     Value(isize),
     Op(String, Operator, String),
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Operator {
+pub enum Operator {
     Add,
     Sub,
     Mul,
     Div,
 }
 
-impl From<&str> for Operator {
-    fn from(op: &str) -> Self {
+impl TryFrom<&str> for Operator {
+    type Error = ParseError;
+
+    fn try_from(op: &str) -> Result<Self, Self::Error> {
         match op {
-            "+" => Add,
-            "-" => Sub,
-            "*" => Mul,
-            "/" => Div,
-            _ => unreachable!()
+            "+" => Ok(Add),
+            "-" => Ok(Sub),
+            "*" => Ok(Mul),
+            "/" => Ok(Div),
+            _ => Err(ParseError::new(op, op, "Expected an operator: +, -, * or /"))
         }
     }
 }
@@ -56,66 +59,93 @@ impl Operator {
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Operation {
+pub enum Operation {
     Left(Operator, isize),
     Right(Operator, isize),
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
-enum OperationChain {
+pub enum OperationChain {
     Operand(isize),
     Chain(Vec<Operation>)
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-21-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 21.
-pub fn run() {
-    let contents =
-        fs::read_to_string("res/day-21-input").expect("Failed to read file");
-    let monkeys = parse_input(&contents);
-
-    println!(
-        "The root monkey yells: {}",
-        resolve(&monkeys, &"root".to_string())
-    );
-
-    println!(
-        "I need to yell: {}",
-        determine_value_to_shout(&monkeys)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-21-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 21.
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Parsed = HashMap<String, Monkey>;
+    const DAY: usize = 21;
+
+    fn parse(&self, input: &str) -> Result<HashMap<String, Monkey>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, monkeys: &HashMap<String, Monkey>) -> Answer {
+        resolve(monkeys, &"root".to_string()).into()
+    }
+
+    fn part_two(&self, monkeys: &HashMap<String, Monkey>) -> Answer {
+        determine_value_to_shout(monkeys).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The root monkey yells: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("I need to yell: {}", answer)
+    }
 }
 
-fn parse_input(input: &String) -> HashMap<String, Monkey> {
-    let mut monkeys = HashMap::new();
+/// Parse each monkey's job, checking that `root`, `humn`, and any monkeys waited on are in the list
+pub fn parse_input(input: &str) -> Result<HashMap<String, Monkey>, ParseError> {
+    let lines = parse_lines(input, |line| {
+        let (id, spec) = split_once(line, line, ": ")?;
+        Ok((id, parse_monkey(spec).map_err(|err| err.within(line, spec))?))
+    })?;
 
-    for line in input.lines() {
-        let (id, spec) = line.split_once(": ").unwrap();
-        monkeys.insert(id.to_string(), parse_monkey(spec));
+    let monkeys: HashMap<String, Monkey> =
+        lines.iter().map(|(id, monkey)| (id.to_string(), monkey.clone())).collect();
+
+    for (id, monkey) in lines {
+        if let Op(a, _, b) = monkey {
+            if let Some(unknown) = [a, b].into_iter().find(|other| !monkeys.contains_key(other)) {
+                return Err(ParseError::new(input, id, format!("Expected monkey '{}' to be in the list", unknown)));
+            }
+        }
+    }
+
+    for required in ["root", "humn"] {
+        if !monkeys.contains_key(required) {
+            return Err(ParseError::new(input, input, format!("Expected monkey '{}' to be in the list", required)));
+        }
     }
 
-    monkeys
+    Ok(monkeys)
 }
 
-fn parse_monkey(spec: &str) -> Monkey {
+pub fn parse_monkey(spec: &str) -> Result<Monkey, ParseError> {
     let parts: Vec<&str> = spec.split_whitespace().collect();
 
-    if parts.len() == 1 {
-        return Value(parts[0].parse::<isize>().unwrap());
+    match parts[..] {
+        [value] => Ok(Value(parse_number(spec, value)?)),
+        [a, op, b] => Ok(Op(a.into(), Operator::try_from(op).map_err(|err| err.within(spec, op))?, b.into())),
+        _ => Err(ParseError::new(spec, spec, "Expected a number or an operation e.g. 'abcd + efgh'"))
     }
-
-    Op(parts[0].into(), parts[1].into(), parts[2].into())
 }
 
-fn resolve(monkeys: &HashMap<String, Monkey>, monkey_id: &String) -> isize {
+pub fn resolve(monkeys: &HashMap<String, Monkey>, monkey_id: &String) -> isize {
     match monkeys.get(monkey_id).unwrap() {
         Value(v) => *v,
         Op(a, op, b) => op.apply(resolve(monkeys, a), resolve(monkeys, b)),
     }
 }
 
-fn unwrap_equation(monkeys: &HashMap<String, Monkey>, monkey_id: &String) -> OperationChain {
+pub fn unwrap_equation(monkeys: &HashMap<String, Monkey>, monkey_id: &String) -> OperationChain {
     if monkey_id == &"humn".to_string() {
         return Chain(Vec::new());
     }
@@ -131,7 +161,7 @@ fn unwrap_equation(monkeys: &HashMap<String, Monkey>, monkey_id: &String) -> Ope
     }
 }
 
-fn determine_value_to_shout(monkeys: &HashMap<String, Monkey>) -> isize {
+pub fn determine_value_to_shout(monkeys: &HashMap<String, Monkey>) -> isize {
     if let Op(left, _, right) = monkeys.get(&"root".to_string()).unwrap() {
         match (unwrap_equation(monkeys, left), unwrap_equation(monkeys, right)) {
             (Chain(chain), Operand(operand)) |
@@ -203,7 +233,7 @@ drzm: hmdt - zczc
 hmdt: 32
 ".to_string();
 
-        assert_eq!(parse_input(&input), sample_monkeys())
+        assert_eq!(parse_input(&input), Ok(sample_monkeys()))
     }
 
     #[test]
@@ -239,4 +269,18 @@ hmdt: 32
             301
         )
     }
+
+    #[test]
+    fn reports_invalid_monkeys() {
+        let bad_operator = parse_input("root: humn % abcd\nhumn: 5\nabcd: 2").unwrap_err();
+        assert_eq!((bad_operator.line, bad_operator.column, bad_operator.text.as_str()), (1, 12, "%"));
+
+        let bad_value = parse_input("root: humn + abcd\nhumn: 5\nabcd: two").unwrap_err();
+        assert_eq!((bad_value.line, bad_value.column, bad_value.text.as_str()), (3, 7, "two"));
+
+        let unknown_monkey = parse_input("root: humn + abcd\nhumn: 5").unwrap_err();
+        assert_eq!(unknown_monkey.message, "Expected monkey 'abcd' to be in the list");
+
+        assert!(parse_input("root: 5").is_err());
+    }
 }
@@ -3,21 +3,30 @@
 //!
 
 use std::collections::HashMap;
-use std::fs;
-use itertools::Itertools;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{ParseError, split_once};
 use crate::day_22::Facing::{DOWN, LEFT, RIGHT, UP};
 use crate::day_22::Instruction::{Left, Move, Right};
 use crate::util::grid::Grid;
+use crate::util::grid_parser::{GridParser, RaggedRows};
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Instruction {
+pub enum Instruction {
     Move(usize),
     Left,
     Right,
 }
 
+/// A cell of the map, the void is the padding around the net of the cube
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum Tile {
+    Void,
+    Open,
+    Wall,
+}
+
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
-enum Facing {
+pub enum Facing {
     UP,
     RIGHT,
     DOWN,
@@ -54,58 +63,77 @@ impl Facing {
     }
 }
 
+/// Maps leaving a cube face in a direction to the face and facing the path continues from
+pub type FaceMap = HashMap<((usize, usize), Facing), ((usize, usize), Facing)>;
+
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-22-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 22.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-22-input").expect("Failed to read file");
-    let (map, instructions) = parse_input(&contents);
-
-    println!(
-        "The final position reference when flat is: {}",
-        walk_map(&map, &instructions)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-22-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 22.
+pub struct Day22;
 
-    println!(
-        "The final position reference when a cube is: {}",
-        walk_cube(&map, &instructions, 50, &actual_faces_map())
-    );
+impl Solution for Day22 {
+    type Parsed = (Grid<Tile>, Vec<Instruction>);
+    const DAY: usize = 22;
+
+    fn parse(&self, input: &str) -> Result<(Grid<Tile>, Vec<Instruction>), ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, (map, instructions): &(Grid<Tile>, Vec<Instruction>)) -> Answer {
+        walk_map(map, instructions).into()
+    }
+
+    fn part_two(&self, (map, instructions): &(Grid<Tile>, Vec<Instruction>)) -> Answer {
+        walk_cube(map, instructions, 50, &actual_faces_map()).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The final position reference when flat is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The final position reference when a cube is: {}", answer)
+    }
 }
 
-fn parse_input(input: &String) -> (Grid, Vec<Instruction>) {
-    let (map_input, instruction_input) = input.split_once("\n\n").unwrap();
+pub fn parse_input(input: &str) -> Result<(Grid<Tile>, Vec<Instruction>), ParseError> {
+    let (map_input, instruction_input) = split_once(input, input, "\n\n")?;
 
-    (parse_map(map_input), parse_instructions(instruction_input))
+    Ok((
+        parse_map(map_input).map_err(|err| err.within(input, map_input))?,
+        parse_instructions(instruction_input).map_err(|err| err.within(input, instruction_input))?,
+    ))
 }
 
-fn parse_map(input: &str) -> Grid {
-    let max_length = input.lines().map(|l| l.len()).max().unwrap();
-    let padded_input =
-        input.lines()
-             .map(|l| format!(
-                 "{}{}",
-                 l,
-                 " ".repeat(max_length - l.len())
-             ))
-             .join("\n");
-
-    Grid::from_string_with_mapping(
-        &padded_input,
-        |c| match c {
-            ' ' => 0,
-            '.' => 1,
-            '#' => 2,
-            _ => unreachable!("unexpected grid char: '{}'", c)
-        },
-    )
+pub fn parse_map(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let parsed = GridParser::new()
+        .ragged_rows(RaggedRows::Pad(' '))
+        .track('.')
+        .expecting("a map tile: ' ', '.' or '#'")
+        .parse(
+            input,
+            |c| match c {
+                ' ' => Some(Tile::Void),
+                '.' => Some(Tile::Open),
+                '#' => Some(Tile::Wall),
+                _ => None,
+            },
+        )?;
+
+    if parsed.position('.').is_none() {
+        return Err(ParseError::new(input, input, "Expected the map to have an open tile"));
+    }
+
+    Ok(parsed.grid)
 }
 
-fn parse_instructions(input: &str) -> Vec<Instruction> {
+pub fn parse_instructions(input: &str) -> Result<Vec<Instruction>, ParseError> {
     let mut current_number = 0;
     let mut instructions = Vec::new();
+    let trimmed = input.trim();
 
-    for c in input.trim().chars() {
+    for (pos, c) in trimmed.char_indices() {
         match c {
             d if d.is_digit(10) =>
                 current_number =
@@ -118,7 +146,7 @@ fn parse_instructions(input: &str) -> Vec<Instruction> {
                 current_number = 0;
                 if c == 'L' { instructions.push(Left) } else { instructions.push(Right) };
             }
-            _ => unreachable!("unexpected instruction {}", c)
+            _ => return Err(ParseError::new(input, &trimmed[pos..pos + c.len_utf8()], "Expected a number, 'L' or 'R'"))
         }
     }
 
@@ -126,15 +154,15 @@ fn parse_instructions(input: &str) -> Vec<Instruction> {
         instructions.push(Move(current_number))
     }
 
-    instructions
+    Ok(instructions)
 }
 
-fn walk_map(map: &Grid, route: &Vec<Instruction>) -> usize {
+pub fn walk_map(map: &Grid<Tile>, route: &Vec<Instruction>) -> usize {
     let mut position_x = 0;
     let mut position_y = 0;
     let mut facing = RIGHT;
 
-    while map.get(position_y, position_x).unwrap() != 1 {
+    while map.get(position_y, position_x) != Some(&Tile::Open) {
         position_x += 1
     }
 
@@ -159,15 +187,14 @@ fn walk_map(map: &Grid, route: &Vec<Instruction>) -> usize {
                     );
 
                     match map.get(y, x) {
-                        Some(0) => {}
-                        Some(1) => {
+                        Some(Tile::Void) => {}
+                        Some(Tile::Open) => {
                             moved += 1;
                             (next_x, next_y) = (x, y);
                         }
-                        Some(2) => break,
-                        d => unreachable!(
-                            "unexpected grid cell {:?} at ({y}, {x}) vs ({}, {})",
-                            d,
+                        Some(Tile::Wall) => break,
+                        None => unreachable!(
+                            "({y}, {x}) is outside the map ({}, {})",
                             map.height(),
                             map.width
                         )
@@ -184,7 +211,7 @@ fn walk_map(map: &Grid, route: &Vec<Instruction>) -> usize {
     1000 * (position_y + 1) + 4 * (position_x + 1) + facing.as_usize()
 }
 
-fn apply_delta(
+pub fn apply_delta(
     (start_y, start_x): (usize, usize),
     (delta_y, delta_x): (isize, isize),
     distance: usize,
@@ -196,7 +223,7 @@ fn apply_delta(
     )
 }
 
-fn wrapping_add(start: usize, delta: isize, multiple: usize, wrap_at: usize) -> usize {
+pub fn wrapping_add(start: usize, delta: isize, multiple: usize, wrap_at: usize) -> usize {
     if delta == 0 {
         return start;
     }
@@ -204,7 +231,7 @@ fn wrapping_add(start: usize, delta: isize, multiple: usize, wrap_at: usize) ->
     ((start as isize + delta * multiple as isize + wrap_at as isize) % wrap_at as isize) as usize
 }
 
-fn actual_faces_map() -> HashMap<((usize, usize), Facing), ((usize, usize), Facing)> {
+pub fn actual_faces_map() -> FaceMap {
     vec![
         (((0, 1), LEFT), ((2, 0), RIGHT)),
         (((0, 1), UP), ((3, 0), RIGHT)),
@@ -228,12 +255,12 @@ fn actual_faces_map() -> HashMap<((usize, usize), Facing), ((usize, usize), Faci
     ].into_iter().collect()
 }
 
-fn apply_cube_delta(
+pub fn apply_cube_delta(
     (start_y, start_x): (usize, usize),
     start_facing: Facing,
     (wrap_y, wrap_x): (usize, usize),
     face_size: usize,
-    face_map: &HashMap<((usize, usize), Facing), ((usize, usize), Facing)>,
+    face_map: &FaceMap,
 ) -> (usize, usize, Facing) {
     let (end_y, end_x) = apply_delta(
         (start_y, start_x),
@@ -277,17 +304,17 @@ fn apply_cube_delta(
     (pos_y1 + next_face_y * face_size, pos_x1 + next_face_x * face_size, next_facing)
 }
 
-fn walk_cube(
-    map: &Grid,
+pub fn walk_cube(
+    map: &Grid<Tile>,
     route: &Vec<Instruction>,
     face_size: usize,
-    face_map: &HashMap<((usize, usize), Facing), ((usize, usize), Facing)>,
+    face_map: &FaceMap,
 ) -> usize {
     let mut position_x = 0;
     let mut position_y = 0;
     let mut facing = RIGHT;
 
-    while map.get(position_y, position_x).unwrap() != 1 {
+    while map.get(position_y, position_x) != Some(&Tile::Open) {
         position_x += 1
     }
 
@@ -305,7 +332,7 @@ fn walk_cube(
                         face_map
                     );
 
-                    if map.get(next_y, next_x).unwrap() == 2 {
+                    if map.get(next_y, next_x) == Some(&Tile::Wall) {
                         break;
                     }
 
@@ -325,9 +352,8 @@ fn walk_cube(
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
     use crate::day_22::Instruction::{Left, Move, Right};
-    use crate::day_22::{actual_faces_map, apply_cube_delta, Facing, parse_input, walk_cube, walk_map};
+    use crate::day_22::{actual_faces_map, apply_cube_delta, FaceMap, Facing, parse_input, Tile, walk_cube, walk_map};
     use crate::day_22::Facing::{DOWN, LEFT, RIGHT, UP};
 
     fn sample_input() -> String {
@@ -349,7 +375,7 @@ mod tests {
         ].join("\n")
     }
 
-    fn sample_faces_map() -> HashMap<((usize, usize), Facing), ((usize, usize), Facing)> {
+    fn sample_faces_map() -> FaceMap {
         vec![
             (((0, 2), LEFT), ((1, 1), DOWN)),
             (((0, 2), UP), ((1, 0), DOWN)),
@@ -375,14 +401,13 @@ mod tests {
 
     #[test]
     fn can_parse() {
-        let (grid, instructions) = parse_input(&sample_input());
+        let (grid, instructions) = parse_input(&sample_input()).unwrap();
 
         assert_eq!(
-            grid.print_with(|c| match c {
-                0 => " ".to_string(),
-                1 => ".".to_string(),
-                2 => "#".to_string(),
-                _ => unreachable!()
+            grid.print_with(|tile| match tile {
+                Tile::Void => " ".to_string(),
+                Tile::Open => ".".to_string(),
+                Tile::Wall => "#".to_string(),
             }),
             vec![
                 "        ...#    ",
@@ -422,7 +447,7 @@ mod tests {
 
     #[test]
     fn can_walk_map() {
-        let (grid, instructions) = parse_input(&sample_input());
+        let (grid, instructions) = parse_input(&sample_input()).unwrap();
 
         assert_eq!(
             walk_map(&grid, &instructions),
@@ -535,11 +560,22 @@ mod tests {
 
     #[test]
     fn can_walk_cube() {
-        let (grid, instructions) = parse_input(&sample_input());
+        let (grid, instructions) = parse_input(&sample_input()).unwrap();
 
         assert_eq!(
             walk_cube(&grid, &instructions, 4, &sample_faces_map()),
             5031
         )
     }
+
+    #[test]
+    fn reports_invalid_notes() {
+        let bad_tile = parse_input("  ..#\n  .x.\n\n10R5").unwrap_err();
+        assert_eq!((bad_tile.line, bad_tile.column, bad_tile.text.as_str()), (2, 4, "x"));
+
+        let bad_instruction = parse_input("  ..#\n  ...\n\n10R5U3").unwrap_err();
+        assert_eq!((bad_instruction.line, bad_instruction.column, bad_instruction.text.as_str()), (4, 5, "U"));
+
+        assert!(parse_input("  ..#\n  ...\n").is_err());
+    }
 }
@@ -2,126 +2,171 @@
 //!
 //!
 
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use itertools::Itertools;
+use std::collections::HashSet;
+use crate::solution::{Answer, Solution};
+use crate::util::bit_grid::BitGrid;
+use crate::util::parse::ParseError;
+
+pub type Coordiantes = (isize, isize);
+
+/// How many empty cells to add around the edge of the map when the elves reach it
+const GROWTH: usize = 8;
+
+/// The elves' positions as a bitboard, that is grown as the elves spread out
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Grove {
+    pub elves: BitGrid,
+    /// The co-ordinates of the bitboard's top left cell
+    pub origin: Coordiantes,
+}
 
-type Coordiantes = (isize, isize);
+impl Grove {
+    pub fn from_positions(positions: &HashSet<Coordiantes>) -> Grove {
+        let min_x = positions.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let max_x = positions.iter().map(|&(x, _)| x).max().unwrap_or(-1);
+        let min_y = positions.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let max_y = positions.iter().map(|&(_, y)| y).max().unwrap_or(-1);
 
-/// The entry point for running the solutions with the 'real' puzzle input.
-///
-/// - The puzzle input is expected to be at `<project_root>/res/day-23-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 23.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-23-input").expect("Failed to read file");
-    let map = parse_input(&contents);
-
-    println!(
-        "After 10 rounds there are {} empty spaces",
-        get_space_after_rounds(&map, 10)
-    );
+        let mut elves = BitGrid::new((max_x + 1 - min_x) as usize, (max_y + 1 - min_y) as usize);
+        for &(x, y) in positions {
+            elves.set((y - min_y) as usize, (x - min_x) as usize, true);
+        }
 
-    println!(
-        "It takes {} rounds until the elves stabilise",
-        rounds_until_stable(&map)
-    );
-}
+        Grove { elves, origin: (min_x, min_y) }
+    }
 
-fn parse_input(input: &String) -> HashSet<Coordiantes> {
-    let mut elves = HashSet::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, chr) in line.chars().enumerate() {
-            if chr == '#' {
-                elves.insert((x as isize, y as isize));
+    pub fn positions(&self) -> HashSet<Coordiantes> {
+        let (origin_x, origin_y) = self.origin;
+        self.elves.iter().map(|(y, x)| (origin_x + x as isize, origin_y + y as isize)).collect()
+    }
+
+    /// Grow the map if any elves are on its edge, so that there is always room for them to move into
+    fn with_margin(&self) -> Grove {
+        match self.elves.bounds() {
+            Some(bounds) if bounds.min_y == 0
+                || bounds.min_x == 0
+                || bounds.max_y as usize == self.elves.height() - 1
+                || bounds.max_x as usize == self.elves.width() - 1 => {
+                let (origin_x, origin_y) = self.origin;
+                Grove {
+                    elves: self.elves.expanded(GROWTH),
+                    origin: (origin_x - GROWTH as isize, origin_y - GROWTH as isize),
+                }
             }
+            _ => self.clone(),
         }
     }
-
-    elves
 }
 
-fn bounds(map: &HashSet<Coordiantes>) -> (Coordiantes, Coordiantes) {
-    let (min_x, max_x) = map.iter().map(|&(x, _)| x).minmax().into_option().unwrap();
-    let (min_y, max_y) = map.iter().map(|&(_, y)| y).minmax().into_option().unwrap();
+/// The entry point for running the solutions with the 'real' puzzle input.
+///
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-23-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 23.
+pub struct Day23;
 
-    ((min_x, min_y), (max_x, max_y))
-}
+impl Solution for Day23 {
+    type Parsed = Grove;
+    const DAY: usize = 23;
 
-#[allow(dead_code)]
-fn print(map: &HashSet<Coordiantes>) -> String {
-    let mut lines: Vec<String> = Vec::new();
-
-    let ((min_x, min_y), (max_x, max_y)) = bounds(map);
-    for y in min_y..=max_y {
-        lines.push(
-            (min_x..=max_x)
-                .map(|x| if map.contains(&(x, y)) { '#' } else { '.' })
-                .join("")
-        )
+    fn parse(&self, input: &str) -> Result<Grove, ParseError> {
+        parse_input(input)
     }
 
-    lines.join("\n")
-}
+    fn part_one(&self, map: &Grove) -> Answer {
+        get_space_after_rounds(map, 10).into()
+    }
 
-fn iterate(map: &HashSet<Coordiantes>, round: usize) -> HashSet<Coordiantes> {
-    let mut moves: HashMap<Coordiantes, Coordiantes> = HashMap::new();
-    let mut next_map: HashSet<Coordiantes> = HashSet::new();
+    fn part_two(&self, map: &Grove) -> Answer {
+        rounds_until_stable(map).into()
+    }
 
-    let order: Vec<usize> = [0, 4, 6, 2].into_iter().cycle().dropping(round % 4).take(4).collect();
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("After 10 rounds there are {} empty spaces", answer)
+    }
 
-    for &elf in map {
-        let surrounds = get_surrounds(&elf, &map);
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("It takes {} rounds until the elves stabilise", answer)
+    }
+}
 
-        if surrounds.iter().all(|&(_, has_elf)| has_elf == false) {
-            next_map.insert(elf);
-            continue;
+pub fn parse_input(input: &str) -> Result<Grove, ParseError> {
+    let mut elves = HashSet::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, (pos, chr)) in line.char_indices().enumerate() {
+            match chr {
+                '#' => { elves.insert((x as isize, y as isize)); }
+                '.' => {}
+                _ => return Err(ParseError::new(input, &line[pos..pos + chr.len_utf8()], "Expected '#' or '.'"))
+            }
         }
+    }
 
-        let mut maybe_new_pos = None;
+    if elves.is_empty() {
+        return Err(ParseError::new(input, input, "Expected at least one elf"));
+    }
 
-        for start_index in &order {
-            if surrounds.iter().cycle().dropping(*start_index).take(3).all(|&(_, has_elf)| has_elf == false) {
-                maybe_new_pos = surrounds.get(*start_index + 1);
-                break;
-            }
-        }
+    Ok(Grove::from_positions(&elves))
+}
 
-        if let Some(&(new_pos, _)) = maybe_new_pos {
-            match moves.get(&new_pos) {
-                Some(&old_pos) => {
-                    // Undo conflicting move
-                    next_map.remove(&new_pos);
-                    next_map.insert(old_pos);
+#[allow(dead_code)]
+pub fn print(map: &Grove) -> String {
+    match map.elves.bounds() {
+        Some(bounds) => (bounds.min_y..=bounds.max_y)
+            .map(|y| {
+                (bounds.min_x..=bounds.max_x)
+                    .map(|x| if map.elves.get(y as usize, x as usize) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n"),
+        None => String::new(),
+    }
+}
 
-                    next_map.insert(elf);
-                }
-                None => {
-                    next_map.insert(new_pos);
-                    moves.insert(new_pos, elf);
-                }
-            }
-        }
-        else {
-            next_map.insert(elf);
-        }
+/// Run a round, each elf with a neighbour proposes moving in the first direction that has no elves on that side,
+/// trying north, south, west and east in turn, starting with a different direction each round. Elves that would
+/// collide stay where they are.
+pub fn iterate(map: &Grove, round: usize) -> Grove {
+    let grove = map.with_margin();
+    let elves = &grove.elves;
+
+    // Mark each cell that has an elf in that direction, by moving the elves the opposite way
+    let n = elves.shifted(1, 0);
+    let s = elves.shifted(-1, 0);
+    let w = elves.shifted(0, 1);
+    let e = elves.shifted(0, -1);
+    let (ne, nw, se, sw) = (n.shifted(0, -1), n.shifted(0, 1), s.shifted(0, -1), s.shifted(0, 1));
+
+    let any = |sides: [&BitGrid; 3]| &(sides[0] | sides[1]) | sides[2];
+    // In the order north, south, west, east, the cells with an elf on that side, and the move in that direction
+    let blocked = [any([&n, &ne, &nw]), any([&s, &se, &sw]), any([&w, &nw, &sw]), any([&e, &ne, &se])];
+    let deltas = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+    let crowded = &(&blocked[0] | &blocked[1]) | &(&blocked[2] | &blocked[3]);
+    let mut next = elves & &!&crowded;
+    let mut undecided = elves & &crowded;
+
+    let mut proposals: [BitGrid; 4] = std::array::from_fn(|_| BitGrid::new(elves.width(), elves.height()));
+    for dir in (0..4).cycle().skip(round % 4).take(4) {
+        proposals[dir] = &undecided & &!&blocked[dir];
+        undecided &= &blocked[dir];
     }
+    next |= &undecided;
 
-    next_map
-}
+    // Only elves moving in opposite directions can propose the same cell, i.e. north with south, and west with east
+    let targets: Vec<BitGrid> = (0..4).map(|dir| proposals[dir].shifted(deltas[dir].0, deltas[dir].1)).collect();
+    for dir in 0..4 {
+        let opposite = dir ^ 1;
+        let (dy, dx) = deltas[dir];
 
-fn get_surrounds((x, y): &Coordiantes, map: &HashSet<Coordiantes>) -> Vec<(Coordiantes, bool)> {
-    let deltas = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+        next |= &(&targets[dir] & &!&targets[opposite]);
+        next |= &(&proposals[dir] & &targets[opposite].shifted(-dy, -dx));
+    }
 
-    deltas
-        .into_iter()
-        .map(|(dx, dy)| (
-            (x + dx, y + dy),
-            map.contains(&(x + dx, y + dy))
-        ))
-        .collect()
+    Grove { elves: next, origin: grove.origin }
 }
 
-fn get_space_after_rounds(map: &HashSet<Coordiantes>, rounds: usize) -> usize {
+pub fn get_space_after_rounds(map: &Grove, rounds: usize) -> usize {
     let updated = (0..rounds).fold(
         map.clone(),
         |acc, round| iterate(&acc, round)
@@ -130,16 +175,14 @@ fn get_space_after_rounds(map: &HashSet<Coordiantes>, rounds: usize) -> usize {
     calculate_space(&updated)
 }
 
-fn calculate_space(map: &HashSet<Coordiantes>) -> usize {
-    let ((min_x, min_y), (max_x, max_y)) = bounds(map);
-    let area = (max_x + 1 - min_x) as usize * (max_y + 1 - min_y) as usize;
-
-    area - map.len()
+pub fn calculate_space(map: &Grove) -> usize {
+    map.elves.bounds().map_or(0, |bounds| bounds.area()) - map.elves.count()
 }
 
-fn rounds_until_stable(map: &HashSet<Coordiantes>) -> usize {
+pub fn rounds_until_stable(map: &Grove) -> usize {
     let mut round = 0;
-    let mut current = map.clone();
+    // Grow the map up front, so that it's unchanged by a round where no elves move
+    let mut current = map.with_margin();
 
     loop {
         let next = iterate(&current, round);
@@ -149,14 +192,14 @@ fn rounds_until_stable(map: &HashSet<Coordiantes>) -> usize {
             return round
         }
 
-        current = next;
+        current = next.with_margin();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
-    use crate::day_23::{Coordiantes, get_space_after_rounds, iterate, parse_input, rounds_until_stable};
+    use crate::day_23::{Coordiantes, get_space_after_rounds, Grove, iterate, parse_input, rounds_until_stable};
 
     fn small_sample_elves() -> HashSet<Coordiantes> {
         vec![
@@ -187,7 +230,7 @@ mod tests {
 ..##.
 .....".to_string();
 
-        assert_eq!(parse_input(&small), small_sample_elves());
+        assert_eq!(parse_input(&small).map(|grove| grove.positions()), Ok(small_sample_elves()));
 
         let medium = "....#..
 ..###.#
@@ -197,7 +240,7 @@ mod tests {
 ##.#.##
 .#..#..".to_string();
 
-        assert_eq!(parse_input(&medium), medium_sample_elves());
+        assert_eq!(parse_input(&medium).map(|grove| grove.positions()), Ok(medium_sample_elves()));
     }
 
     #[test]
@@ -223,28 +266,36 @@ mod tests {
 .....
 ..#..".to_string();
 
-        let round_1 = iterate(&small_sample_elves(), 0);
+        let round_1 = iterate(&Grove::from_positions(&small_sample_elves()), 0);
         let round_2 = iterate(&round_1, 1);
         let round_3 = iterate(&round_2, 2);
         let round_4 = iterate(&round_3, 3);
 
-        assert_eq!(round_1, parse_input(&expected_round_1));
-        assert_eq!(round_2, parse_input(&expected_round_2));
-        assert_eq!(round_3, parse_input(&expected_round_3));
-        assert_eq!(round_4, parse_input(&expected_round_3));
+        assert_eq!(round_1.positions(), parse_input(&expected_round_1).unwrap().positions());
+        assert_eq!(round_2.positions(), parse_input(&expected_round_2).unwrap().positions());
+        assert_eq!(round_3.positions(), parse_input(&expected_round_3).unwrap().positions());
+        assert_eq!(round_4.positions(), parse_input(&expected_round_3).unwrap().positions());
     }
 
     #[test]
     fn can_find_space_after_ten_rounds() {
         assert_eq!(
-            get_space_after_rounds(&medium_sample_elves(), 10),
+            get_space_after_rounds(&Grove::from_positions(&medium_sample_elves()), 10),
             110
         )
     }
 
     #[test]
     fn can_find_stable_state() {
-        assert_eq!(rounds_until_stable(&small_sample_elves()), 4);
-        assert_eq!(rounds_until_stable(&medium_sample_elves()), 20);
+        assert_eq!(rounds_until_stable(&Grove::from_positions(&small_sample_elves())), 4);
+        assert_eq!(rounds_until_stable(&Grove::from_positions(&medium_sample_elves())), 20);
+    }
+
+    #[test]
+    fn reports_invalid_grove() {
+        let bad_tile = parse_input(".....\n..##.\n..#o.").unwrap_err();
+        assert_eq!((bad_tile.line, bad_tile.column, bad_tile.text.as_str()), (3, 4, "o"));
+
+        assert!(parse_input(".....\n.....").is_err());
     }
 }
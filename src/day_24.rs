@@ -2,14 +2,17 @@
 //!
 //!
 
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
-use std::fs;
-use itertools::Itertools;
+use std::collections::HashSet;
+use crate::solution::{Answer, Solution};
+use crate::util::bit_grid::BitGrid;
+use crate::util::grid_parser::{GridParser, RaggedRows};
+use crate::util::parse::ParseError;
+use crate::util::point::Point2;
+use crate::util::search::a_star;
 use crate::day_24::Direction::{DOWN, LEFT, RIGHT, UP};
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
-enum Direction {
+pub enum Direction {
     UP,
     RIGHT,
     DOWN,
@@ -30,131 +33,185 @@ impl TryFrom<char> for Direction {
     }
 }
 
-type Blizzard = (usize, usize, Direction);
-type Position = (usize, usize);
+pub type Blizzard = (usize, usize, Direction);
+pub type Position = (usize, usize);
 
-#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
-struct State {
-    pos: Position,
-    dist: usize,
-    cost: usize,
+/// The starting positions of the blizzards, as a bitboard for each direction they move in
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Blizzards {
+    up: BitGrid,
+    right: BitGrid,
+    down: BitGrid,
+    left: BitGrid,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-             .then_with(|| self.pos.cmp(&other.pos))
+impl Blizzards {
+    /// Store the blizzards in a valley `width` by `height` cells, not including the walls
+    pub fn new(&(width, height): &Position, blizzards: &HashSet<Blizzard>) -> Blizzards {
+        let mut bitboards = Blizzards {
+            up: BitGrid::new(width, height),
+            right: BitGrid::new(width, height),
+            down: BitGrid::new(width, height),
+            left: BitGrid::new(width, height),
+        };
+
+        for &(x, y, direction) in blizzards {
+            let bitboard = match direction {
+                UP => &mut bitboards.up,
+                RIGHT => &mut bitboards.right,
+                DOWN => &mut bitboards.down,
+                LEFT => &mut bitboards.left,
+            };
+            bitboard.set(y, x, true);
+        }
+
+        bitboards
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// The cells any blizzard covers after `minute` minutes, found by moving each direction's bitboard as a whole
+    pub fn at(&self, minute: usize) -> BitGrid {
+        let dx = (minute % self.right.width().max(1)) as isize;
+        let dy = (minute % self.down.height().max(1)) as isize;
+
+        let horizontal = &self.right.wrapped(0, dx) | &self.left.wrapped(0, -dx);
+        let vertical = &self.down.wrapped(dy, 0) | &self.up.wrapped(-dy, 0);
+
+        &horizontal | &vertical
     }
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-24-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 24.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-24-input").expect("Failed to read file");
-    let (blizzards, goal) = parse_input(&contents);
-
-    println!(
-        "The shortest path is {} minutes",
-        find_shortest_path_single(&blizzards, goal)
-    );
-
-    println!(
-        "The shortest path when returning for snacks is {} minutes",
-        find_shortest_path_returning(&blizzards, goal)
-    );
-}
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-24-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 24.
+pub struct Day24;
 
-fn parse_input(input: &String) -> (HashSet<Blizzard>, Position) {
-    let mut blizzards = HashSet::new();
+impl Solution for Day24 {
+    type Parsed = (Blizzards, Position);
+    const DAY: usize = 24;
 
-    for (y, line) in input.lines().dropping(1).enumerate() {
-        for (x, chr) in line.chars().dropping(1).enumerate() {
-            if let Ok(dir) = Direction::try_from(chr) {
-                blizzards.insert((x, y, dir));
-            }
-        }
+    fn parse(&self, input: &str) -> Result<(Blizzards, Position), ParseError> {
+        parse_input(input)
     }
 
-    let goal_x = input.lines().next().unwrap().len() - 3;
-    let goal_y = input.lines().count() - 3;
+    fn part_one(&self, (blizzards, goal): &(Blizzards, Position)) -> Answer {
+        find_shortest_path_single(blizzards, *goal).into()
+    }
 
-    (blizzards, (goal_x, goal_y))
-}
+    fn part_two(&self, (blizzards, goal): &(Blizzards, Position)) -> Answer {
+        find_shortest_path_returning(blizzards, *goal).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The shortest path is {} minutes", answer)
+    }
 
-fn manhatten_distance(&(y_a, x_a): &Position, &(y_b, x_b): &Position) -> usize {
-    y_a.abs_diff(y_b) + x_a.abs_diff(x_b)
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The shortest path when returning for snacks is {} minutes", answer)
+    }
 }
 
+pub fn parse_input(input: &str) -> Result<(Blizzards, Position), ParseError> {
+    let grid = GridParser::new()
+        .ragged_rows(RaggedRows::Reject)
+        .expecting("a wall, open ground, or a blizzard")
+        .parse(input, |c| "#.E^>v<".contains(c).then_some(c))?
+        .grid;
+
+    let (width, height) = (grid.width, grid.height());
+    if width < 3 || height < 3 {
+        return Err(ParseError::new(input, input, "Expected a valley surrounded by walls"));
+    }
+
+    // The cells are all ASCII, so each is a single byte of its line
+    let cell_text = |y: usize, x: usize| &input.lines().nth(y).unwrap()[x..x + 1];
+    let entrance = (0, 1);
+    let exit = (height - 1, width - 2);
+
+    let mut blizzards = HashSet::new();
+    for ((y, x), &chr) in grid.iter() {
+        let on_edge = y == 0 || x == 0 || y == height - 1 || x == width - 1;
+
+        let problem = match chr {
+            '.' | 'E' if (y, x) == entrance || (y, x) == exit => None,
+            _ if (y, x) == entrance => Some("Expected an entrance in the top wall, next to the top left corner"),
+            _ if (y, x) == exit => Some("Expected an exit in the bottom wall, next to the bottom right corner"),
+            '#' if on_edge => None,
+            _ if on_edge => Some("Expected a wall"),
+            '#' => Some("Expected open ground or a blizzard inside the walls"),
+            _ => None,
+        };
+
+        if let Some(message) = problem {
+            return Err(ParseError::new(input, cell_text(y, x), message));
+        }
+
+        if let Ok(dir) = Direction::try_from(chr) {
+            blizzards.insert((x - 1, y - 1, dir));
+        }
+    }
+
+    Ok((Blizzards::new(&(width - 2, height - 2), &blizzards), (width - 3, height - 3)))
+}
 
-fn find_shortest_path(
-    blizzards: &HashSet<Blizzard>,
+/// Search for the quickest route through the valley from `start_pos` to `goal`, setting off from the entrance next to
+/// `start_pos` at minute `start_dist`. Returns the minute the exit next to `goal` is reached.
+pub fn find_shortest_path(
+    blizzards: &Blizzards,
     start_pos: Position,
     start_dist: usize,
     goal: Position,
     bounds: Position,
 ) -> usize {
-    let mut heap: BinaryHeap<State> = BinaryHeap::new();
-    // Could wait indefinitely at the entrance use this to track
-    // when a wait of n has been added to the heap
-    let mut wait_added = start_dist;
+    // Could wait indefinitely at the entrance, which is `None` as it's outside the valley. The blizzards repeat at
+    // least this often, so there's no point waiting any longer.
     let max_wait = bounds.0 * bounds.1 + start_dist;
-    let mut seen: HashSet<State> = HashSet::new();
 
-    heap.push(State {
-        pos: start_pos,
-        dist: start_dist + 1,
-        cost: manhatten_distance(&start_pos, &goal) + 1,
-    });
-
-    let deltas = vec![
+    let deltas = [
         (0, 0), (0, -1), (1, 0), (0, 1), (-1, 0),
     ];
 
-    while let Some(State { dist, pos, .. }) = heap.pop() {
-        while wait_added < dist && wait_added < max_wait {
-            wait_added += 1;
-            heap.push(State {
-                pos: start_pos,
-                dist: wait_added + 1,
-                cost: manhatten_distance(&start_pos, &goal) + wait_added + 1,
-            });
-        }
+    let to_point = |(x, y): Position| Point2::new(x as isize, y as isize);
+    let distance_to_goal = |pos: Position| to_point(pos).manhattan_distance(&to_point(goal));
 
-        if !is_clear(blizzards, pos, dist, &bounds) {
-            continue;
-        }
-
-        if pos == goal {
-            return dist + 1;
-        }
+    // The blizzards covering the valley at each minute since setting off, built as the search first needs them
+    let mut occupied: Vec<BitGrid> = Vec::new();
 
-        for nex_pos in apply_deltas(pos, &deltas, &bounds) {
-            let next_state = State {
-                pos: nex_pos,
-                dist: dist + 1,
-                cost: manhatten_distance(&nex_pos, &goal) + dist + 1,
+    let found = a_star(
+        (None, start_dist),
+        |&(pos, dist): &(Option<Position>, usize)| {
+            let (mut next, moves) = match pos {
+                None if dist < max_wait => (vec![((None, dist + 1), 1)], vec![start_pos]),
+                None => (Vec::new(), vec![start_pos]),
+                Some(pos) => (Vec::new(), apply_deltas(pos, &deltas, &bounds)),
             };
 
-            if seen.insert(next_state) {
-                heap.push(next_state);
+            while start_dist + occupied.len() <= dist + 1 {
+                occupied.push(blizzards.at(start_dist + occupied.len()));
             }
-        }
-    }
 
-    unreachable!("Failed to find path")
+            next.extend(
+                moves.into_iter()
+                     .filter(|&next_pos| is_clear(&occupied[dist + 1 - start_dist], next_pos))
+                     .map(|next_pos| ((Some(next_pos), dist + 1), 1))
+            );
+
+            next
+        },
+        |&(pos, _)| pos == Some(goal),
+        |&(pos, _)| match pos {
+            Some(pos) => distance_to_goal(pos),
+            None => distance_to_goal(start_pos) + 1,
+        },
+    ).expect("Failed to find path");
+
+    // Step out of the exit
+    found.goal.1 + 1
 }
 
-fn apply_deltas((x, y): Position, deltas: &Vec<(isize, isize)>, &(max_x, max_y): &Position) -> Vec<Position> {
+pub fn apply_deltas((x, y): Position, deltas: &[(isize, isize)], &(max_x, max_y): &Position) -> Vec<Position> {
     deltas
-        .into_iter()
+        .iter()
         .flat_map(|(dx, dy)| {
             let x1 = x as isize + dx;
             let y1 = y as isize + dy;
@@ -167,39 +224,17 @@ fn apply_deltas((x, y): Position, deltas: &Vec<(isize, isize)>, &(max_x, max_y):
         .collect()
 }
 
-fn is_clear(
-    blizzards: &HashSet<Blizzard>,
-    (pos_x, pos_y): Position,
-    distance: usize,
-    &(wrap_x, wrap_y): &Position,
-) -> bool {
-    let intersecting_blizzards = [
-        (wrapping_add(pos_x, distance as isize, wrap_x), pos_y, LEFT),
-        (wrapping_add(pos_x, -(distance as isize), wrap_x), pos_y, RIGHT),
-        (pos_x, wrapping_add(pos_y, distance as isize, wrap_y), UP),
-        (pos_x, wrapping_add(pos_y, -(distance as isize), wrap_y), DOWN),
-    ];
-
-    intersecting_blizzards
-        .into_iter()
-        .all(|blizzard| !blizzards.contains(&blizzard))
-}
-
-fn wrapping_add(num: usize, delta: isize, wrap: usize) -> usize {
-    let mut value = (num as isize + delta) % wrap as isize;
-    if value < 0 {
-        value += wrap as isize
-    }
-
-    value as usize
+/// Is the position free of blizzards, given the cells they cover at that minute
+pub fn is_clear(occupied: &BitGrid, (pos_x, pos_y): Position) -> bool {
+    !occupied.get(pos_y, pos_x)
 }
 
-fn find_shortest_path_single(blizzards: &HashSet<Blizzard>, goal: Position) -> usize {
+pub fn find_shortest_path_single(blizzards: &Blizzards, goal: Position) -> usize {
     let bounds = (goal.0 + 1, goal.1 + 1);
     find_shortest_path(blizzards, (0, 0), 0, goal, bounds)
 }
 
-fn find_shortest_path_returning(blizzards: &HashSet<Blizzard>, goal: Position) -> usize {
+pub fn find_shortest_path_returning(blizzards: &Blizzards, goal: Position) -> usize {
     let bounds = (goal.0 + 1, goal.1 + 1);
     let first_trip = find_shortest_path(blizzards, (0, 0), 0, goal, bounds);
     let return_trip = find_shortest_path(blizzards, goal, first_trip, (0, 0), bounds);
@@ -210,7 +245,10 @@ fn find_shortest_path_returning(blizzards: &HashSet<Blizzard>, goal: Position) -
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
-    use crate::day_24::{Blizzard, find_shortest_path, find_shortest_path_returning, find_shortest_path_single, is_clear, parse_input};
+    use crate::day_24::{
+        Blizzard, Blizzards, find_shortest_path, find_shortest_path_returning, find_shortest_path_single, is_clear,
+        parse_input,
+    };
     use crate::day_24::Direction::{DOWN, LEFT, RIGHT, UP};
 
     fn sample_blizzards() -> HashSet<Blizzard> {
@@ -240,6 +278,10 @@ mod tests {
         ].into_iter().collect()
     }
 
+    fn sample_valley() -> Blizzards {
+        Blizzards::new(&(6, 4), &sample_blizzards())
+    }
+
     #[test]
     fn can_parse() {
         let input = "#.#####
@@ -255,10 +297,10 @@ mod tests {
                 .into_iter()
                 .collect();
 
-        let (actual, goal) = parse_input(&input);
+        let (actual, goal) = parse_input(&input).unwrap();
 
         assert_eq!(goal, (4, 4));
-        assert_eq!(actual, expected);
+        assert_eq!(actual, Blizzards::new(&(5, 5), &expected));
 
         let input = "#E######
 #>>.<^<#
@@ -267,33 +309,31 @@ mod tests {
 #<^v^^>#
 ######.#".to_string();
 
-        let expected = sample_blizzards();
-        let (actual, goal) = parse_input(&input);
+        let (actual, goal) = parse_input(&input).unwrap();
 
         assert_eq!(goal, (5, 3));
-        assert_eq!(actual, expected);
+        assert_eq!(actual, sample_valley());
     }
 
     #[test]
     fn can_find_shortest_path() {
-        assert_eq!(find_shortest_path(&sample_blizzards(), (0, 0), 0, (5, 3), (6, 4)), 18);
-        assert_eq!(find_shortest_path(&sample_blizzards(), (5, 3), 18, (0, 0), (6, 4)), 18 + 23);
+        assert_eq!(find_shortest_path(&sample_valley(), (0, 0), 0, (5, 3), (6, 4)), 18);
+        assert_eq!(find_shortest_path(&sample_valley(), (5, 3), 18, (0, 0), (6, 4)), 18 + 23);
     }
 
     #[test]
     fn can_find_shortest_path_single() {
-        assert_eq!(find_shortest_path_single(&sample_blizzards(), (5, 3)), 18);
+        assert_eq!(find_shortest_path_single(&sample_valley(), (5, 3)), 18);
     }
 
     #[test]
     fn can_find_shortest_path_returning() {
-        assert_eq!(find_shortest_path_returning(&sample_blizzards(), (5, 3)), 54);
+        assert_eq!(find_shortest_path_returning(&sample_valley(), (5, 3)), 54);
     }
 
     #[test]
     fn can_determine_clear_areas() {
-        let blizzards = sample_blizzards();
-        let bounds = (6, 4);
+        let blizzards = sample_valley();
 
         for (pos, dist, expected) in vec![
             ((0, 0), 1, true),
@@ -303,10 +343,34 @@ mod tests {
             ((2, 1), 6, false),
         ] {
             assert_eq!(
-                is_clear(&blizzards, pos, dist, &bounds),
+                is_clear(&blizzards.at(dist), pos),
                 expected,
                 "is_clear for pos: ({:?}) after {dist} steps", pos
             )
         }
     }
+
+    #[test]
+    fn reports_invalid_valley() {
+        let bad_tile = parse_input("#.####\n#.>..#\n#..x.#\n####.#").unwrap_err();
+        assert_eq!((bad_tile.line, bad_tile.column, bad_tile.text.as_str()), (3, 4, "x"));
+
+        assert!(parse_input("#.#\n#.#").is_err());
+
+        let ragged = parse_input("#.####\n#.>..#>\n#....#\n####.#").unwrap_err();
+        assert_eq!((ragged.line, ragged.column, ragged.message.as_str()), (2, 1, "Expected a row of 6 cells"));
+
+        let no_exit = parse_input("#.#####\n#.>..>#\n#.....#\n#######").unwrap_err();
+        assert_eq!((no_exit.line, no_exit.column, no_exit.text.as_str()), (4, 6, "#"));
+        assert_eq!(no_exit.message, "Expected an exit in the bottom wall, next to the bottom right corner");
+
+        let no_entrance = parse_input("######\n#.>..#\n####.#").unwrap_err();
+        assert_eq!((no_entrance.line, no_entrance.column), (1, 2));
+
+        let broken_wall = parse_input("#.####\n..>..#\n####.#").unwrap_err();
+        assert_eq!((broken_wall.line, broken_wall.column, broken_wall.message.as_str()), (2, 1, "Expected a wall"));
+
+        let inner_wall = parse_input("#.####\n#.>#.#\n####.#").unwrap_err();
+        assert_eq!((inner_wall.line, inner_wall.column, inner_wall.text.as_str()), (2, 4, "#"));
+    }
 }
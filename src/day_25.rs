@@ -2,23 +2,38 @@
 //!
 //!
 
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, ParseError};
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-25-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 25.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-25-input").expect("Failed to read file");
-    let decimals = parse_input(&contents);
-
-    println!(
-        "The number to enter is: {}",
-        sum_and_render(&decimals),
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-25-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 25.
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Parsed = Vec<isize>;
+    const DAY: usize = 25;
+    const HAS_PART_TWO: bool = false;
+
+    fn parse(&self, input: &str) -> Result<Vec<isize>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, decimals: &Vec<isize>) -> Answer {
+        sum_and_render(decimals).into()
+    }
+
+    fn part_two(&self, _: &Vec<isize>) -> Answer {
+        Answer::Empty
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The number to enter is: {}", answer)
+    }
 }
 
-fn to_snafu(decimal: isize) -> String {
+pub fn to_snafu(decimal: isize) -> String {
     fn iter(decimal: isize, acc: String) -> String {
         if decimal == 0 {
             return acc;
@@ -42,29 +57,31 @@ fn to_snafu(decimal: isize) -> String {
     iter(decimal, "".to_string())
 }
 
-fn from_snafu(snafu: String) -> isize {
-    snafu.chars().fold(
+pub fn from_snafu(snafu: &str) -> Result<isize, ParseError> {
+    if snafu.is_empty() {
+        return Err(ParseError::new(snafu, snafu, "Expected a SNAFU number"));
+    }
+
+    snafu.char_indices().try_fold(
         0,
-        |acc, digit|
-            acc * 5 + match digit {
+        |acc, (pos, digit)|
+            Ok(acc * 5 + match digit {
                 '0' => 0,
                 '1' => 1,
                 '2' => 2,
                 '-' => -1,
                 '=' => -2,
-                d => unreachable!("{} is not a SNAFU digit", d)
-            },
+                d => return Err(ParseError::new(snafu, &snafu[pos..pos + d.len_utf8()], "Expected a SNAFU digit: 2, 1, 0, - or ="))
+            }),
     )
 }
 
-fn sum_and_render(decimals: &Vec<isize>) -> String {
+pub fn sum_and_render(decimals: &Vec<isize>) -> String {
     to_snafu(decimals.into_iter().sum())
 }
 
-fn parse_input(input: &String) -> Vec<isize> {
-    input.lines()
-         .map(|line| from_snafu(line.to_string()))
-         .collect()
+pub fn parse_input(input: &str) -> Result<Vec<isize>, ParseError> {
+    parse_lines(input, from_snafu)
 }
 
 #[cfg(test)]
@@ -111,7 +128,7 @@ mod tests {
 
         for (decimal, snafu) in examples {
             assert_eq!(to_snafu(decimal), snafu);
-            assert_eq!(from_snafu(snafu), decimal);
+            assert_eq!(from_snafu(&snafu), Ok(decimal));
         }
     }
 
@@ -131,7 +148,7 @@ mod tests {
 1=
 122".to_string();
 
-        assert_eq!(parse_input(&input), sample_decimals());
+        assert_eq!(parse_input(&input), Ok(sample_decimals()));
     }
 
     #[test]
@@ -141,4 +158,12 @@ mod tests {
             "2=-1=0".to_string()
         )
     }
+
+    #[test]
+    fn reports_invalid_snafu() {
+        let bad_digit = parse_input("1=-0-2\n12111\n2=3").unwrap_err();
+        assert_eq!((bad_digit.line, bad_digit.column, bad_digit.text.as_str()), (3, 3, "3"));
+
+        assert!(parse_input("1=\n\n122").is_err());
+    }
 }
@@ -2,71 +2,247 @@
 //!
 //! Today's task is to find the intersection of various character strings, then use a scoring/priority system to get an
 //! aggregate of the resulting singleton sets.
+//!
+//! Rucksacks are stored as bitmasks of the items they contain, so intersections are a bitwise and. Items are
+//! prioritised by their position in an [`Alphabet`], which defaults to `a-z` then `A-Z`.
+
+use itertools::Itertools;
+use crate::util::parse::{parse_lines, ParseError};
+use crate::solution::{Answer, Solution};
+
+/// A set of items as a bitmask, bit `n` is set for the item with priority `n + 1`
+pub type ItemSet = u128;
 
-use std::collections::{BTreeSet};
-use std::fs;
+/// The items that can be packed, in priority order
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Alphabet {
+    items: Vec<char>,
+}
+
+/// A rucksack's items, in the order they were listed, as their bit in an [`ItemSet`]
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Rucksack {
+    items: Vec<u8>,
+    /// The items as they were written in the input, for error messages
+    text: String,
+}
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-3-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 1.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-3-input").expect("Failed to read file");
-    let rucksacks: Vec<String> = parse_input(&contents);
-
-    println!(
-        "The sum of the mismatched items' priorities is: {}",
-        sum_mismatched_items(&rucksacks)
-    );
-
-    println!(
-        "The sum of the group badge items' priorities is: {}",
-        sum_group_badge_priorities(&rucksacks)
-    )
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-3-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 3.
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Parsed = Vec<Rucksack>;
+    const DAY: usize = 3;
+
+    fn parse(&self, input: &str) -> Result<Vec<Rucksack>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, rucksacks: &Vec<Rucksack>) -> Answer {
+        sum_mismatched_items(rucksacks, 2).into()
+    }
+
+    fn part_two(&self, rucksacks: &Vec<Rucksack>) -> Answer {
+        sum_group_badge_priorities(rucksacks, 3).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sum of the mismatched items' priorities is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The sum of the group badge items' priorities is: {}", answer)
+    }
+}
+
+impl Alphabet {
+    /// The most items an alphabet can have, one for each bit of an [`ItemSet`]
+    pub const MAX_ITEMS: usize = ItemSet::BITS as usize;
+
+    /// The puzzle's items, `a-z` with priorities 1 to 26, then `A-Z` with priorities 27 to 52
+    pub fn standard() -> Alphabet {
+        Alphabet { items: ('a'..='z').chain('A'..='Z').collect() }
+    }
+
+    /// An alphabet where the first item has priority 1, the second 2, etc.
+    pub fn new(items: &str) -> Result<Alphabet, ParseError> {
+        let mut alphabet = Alphabet { items: Vec::new() };
+
+        for (pos, item) in items.char_indices() {
+            let text = &items[pos..pos + item.len_utf8()];
+            if alphabet.items.contains(&item) {
+                return Err(ParseError::new(items, text, "Expected each item to appear once"));
+            }
+            if alphabet.items.len() == Alphabet::MAX_ITEMS {
+                return Err(ParseError::new(items, text, format!("Expected at most {} items", Alphabet::MAX_ITEMS)));
+            }
+
+            alphabet.items.push(item);
+        }
+
+        Ok(alphabet)
+    }
+
+    pub fn priority(&self, item: char) -> Option<u32> {
+        self.items.iter().position(|&c| c == item).map(|index| index as u32 + 1)
+    }
+
+    pub fn item(&self, priority: u32) -> Option<char> {
+        self.items.get((priority as usize).checked_sub(1)?).copied()
+    }
+
+    /// List the items for an error message, with runs of consecutive characters as ranges, e.g. "a-z or A-Z"
+    fn describe(&self) -> String {
+        let runs: Vec<String> =
+            self.items.iter()
+                .copied()
+                .peekable()
+                .batching(|items| {
+                    let first = items.next()?;
+                    let mut last = first;
+                    while let Some(next) = items.next_if(|&next| Some(next) == char::from_u32(last as u32 + 1)) {
+                        last = next;
+                    }
+
+                    Some(if first == last { first.to_string() } else { format!("{}-{}", first, last) })
+                })
+                .collect();
+
+        match runs.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+            None => String::new(),
+        }
+    }
 }
 
-/// Convert the input to a list of the lines as individual strings
-fn parse_input(input: &String) -> Vec<String> {
-    input.lines().map(str::to_string).collect()
+impl Rucksack {
+    /// All the items in the rucksack
+    pub fn contents(&self) -> ItemSet {
+        self.items.iter().fold(0, |set, &item| set | 1 << item)
+    }
+
+    /// Split the items into `parts` equally sized compartments. If the items don't divide evenly the last
+    /// compartment is short, and there are no compartments if `parts` is 0.
+    pub fn compartments(&self, parts: usize) -> Vec<ItemSet> {
+        if parts == 0 {
+            return Vec::new();
+        }
+
+        let size = self.items.len().div_ceil(parts).max(1);
+        let mut compartments: Vec<ItemSet> =
+            self.items.chunks(size)
+                .map(|items| items.iter().fold(0, |set, &item| set | 1 << item))
+                .collect();
+
+        compartments.resize(parts, 0);
+        compartments
+    }
 }
 
-/// Get the character(S) in the second string that are also in the first string
-fn intersect_strings(a: &String, b: &String) -> String {
-    let set: BTreeSet<char> = BTreeSet::from_iter(a.chars());
+/// Parse rucksacks for the puzzle, with the standard alphabet and two compartments
+pub fn parse_input(input: &str) -> Result<Vec<Rucksack>, ParseError> {
+    parse_rucksacks(input, &Alphabet::standard(), 2)
+}
 
-    b.chars().filter(|c| set.contains(c)).collect()
+/// Parse a rucksack per line, see [`parse_rucksack`]
+pub fn parse_rucksacks(input: &str, alphabet: &Alphabet, compartments: usize) -> Result<Vec<Rucksack>, ParseError> {
+    parse_lines(input, |line| parse_rucksack(line, alphabet, compartments))
 }
 
-/// Map the inputs to two compartments, find the singleton intersecting character, map to a priority and sum.
-fn sum_mismatched_items(rucksacks: &Vec<String>) -> u32 {
-    rucksacks.iter()
-        .map(|line| line.split_at(line.len() / 2))
-        .map(|(a, b)| intersect_strings(&a.to_string(), &b.to_string()))
-        .map(|str| map_char_to_priority(&str.chars().next().unwrap()))
-        .sum()
+/// Parse a line's items, checking they are in the alphabet and can be split evenly into `compartments`. Whether
+/// the compartments share exactly one item is checked by [`sum_mismatched_items`], as only part 1 needs it.
+pub fn parse_rucksack(line: &str, alphabet: &Alphabet, compartments: usize) -> Result<Rucksack, ParseError> {
+    if compartments == 0 {
+        return Err(ParseError::new(line, line, "Expected rucksacks to have at least one compartment"));
+    }
+
+    let mut items = Vec::new();
+
+    for (pos, item) in line.char_indices() {
+        let text = &line[pos..pos + item.len_utf8()];
+        let Some(priority) = alphabet.priority(item) else {
+            return Err(ParseError::new(line, text, format!("Expected an item: {}", alphabet.describe())));
+        };
+        items.push((priority - 1) as u8);
+    }
+
+    if !items.len().is_multiple_of(compartments) {
+        return Err(ParseError::new(
+            line,
+            line,
+            format!("Expected the items to split evenly into {} compartments", compartments),
+        ));
+    }
+
+    Ok(Rucksack { items, text: line.to_string() })
 }
 
-/// Chunk the input lines into groups of three, find the singleton intersection of each group and sum their priorities.
-fn sum_group_badge_priorities(rucksacks: &Vec<String>) -> u32 {
-    rucksacks.chunks(3)
-        .map(|chunk| {
-            let intermediate = intersect_strings(&chunk[0], &chunk[1]);
-            intersect_strings(&intermediate, &chunk[2])
-        })
-        .map(|str| map_char_to_priority(&str.chars().next().unwrap()))
-        .sum()
+/// The priority of the only item in all of `sets`, or `None` if there isn't exactly one
+pub fn common_item<I: IntoIterator<Item=ItemSet>>(sets: I) -> Option<u32> {
+    let common = sets.into_iter().fold(ItemSet::MAX, |common, set| common & set);
+
+    (common.count_ones() == 1).then(|| common.trailing_zeros() + 1)
 }
 
-/// Map a character to a priority based on its position in the alphabet and whether it is uppercase.
-fn map_char_to_priority(c: &char) -> u32 {
-    let position = 0b11111 & *c as u32;
+/// The priority of the item that has been packed in all of the rucksack's compartments
+pub fn mismatched_item(rucksack: &Rucksack, compartments: usize) -> Option<u32> {
+    common_item(rucksack.compartments(compartments))
+}
 
-    if c.is_uppercase() { position + 26 } else { position }
+/// The priority of the item that is in all the rucksacks in the group
+pub fn group_badge(group: &[Rucksack]) -> Option<u32> {
+    common_item(group.iter().map(Rucksack::contents))
+}
+
+/// Split each rucksack into compartments, find the singleton intersecting item, and sum their priorities. Errors
+/// give the line of the first rucksack without exactly one item in all of its compartments.
+pub fn sum_mismatched_items(rucksacks: &[Rucksack], compartments: usize) -> Result<u32, ParseError> {
+    rucksacks.iter()
+             .enumerate()
+             .map(|(index, rucksack)| {
+                 mismatched_item(rucksack, compartments).ok_or_else(|| ParseError::at(
+                     index + 1,
+                     1,
+                     &rucksack.text,
+                     format!("Expected exactly one item in all {} compartments", compartments),
+                 ))
+             })
+             .sum()
+}
+
+/// Chunk the rucksacks into groups, find the singleton intersection of each group and sum their priorities. Errors
+/// give the line of the first rucksack in a group that is incomplete, or doesn't have exactly one item in common.
+pub fn sum_group_badge_priorities(rucksacks: &[Rucksack], group_size: usize) -> Result<u32, ParseError> {
+    if group_size == 0 {
+        return Err(ParseError::at(1, 1, "0", "Expected groups of at least one rucksack"));
+    }
+
+    rucksacks.chunks(group_size)
+             .enumerate()
+             .map(|(index, group)| {
+                 let error = |message: String| ParseError::at(index * group_size + 1, 1, &group[0].text, message);
+
+                 if group.len() != group_size {
+                     return Err(error(format!("Expected the rucksacks to split into groups of {}", group_size)));
+                 }
+
+                 group_badge(group).ok_or_else(|| {
+                     error(format!("Expected exactly one item in all {} rucksacks of the group", group_size))
+                 })
+             })
+             .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_3::{intersect_strings, map_char_to_priority, parse_input, sum_group_badge_priorities, sum_mismatched_items};
+    use crate::day_3::{
+        Alphabet, common_item, group_badge, mismatched_item, parse_input, parse_rucksack, parse_rucksacks,
+        sum_group_badge_priorities, sum_mismatched_items,
+    };
 
     fn get_sample_data() -> String {
         return "vJrwpWtwJgWrhcsFMMfFFhFp
@@ -78,47 +254,116 @@ CrZsJsPPZsGzwwsLwLmpwMDw".to_string();
     }
 
     #[test]
-    fn can_intersect_strings() {
-        assert_eq!(intersect_strings(&"abcd".to_string(), &"defg".to_string()), "d".to_string());
-        assert_eq!(intersect_strings(&"abcd".to_string(), &"cdef".to_string()), "cd".to_string());
-        assert_eq!(intersect_strings(&"cafH".to_string(), &"wHcl".to_string()), "Hc".to_string());
-        assert_eq!(intersect_strings(&"ttgJtRGJ".to_string(), &"QctTZtZT".to_string()), "tt".to_string());
+    fn can_find_common_items() {
+        assert_eq!(common_item([0b1010, 0b0011]), Some(2));
+        assert_eq!(common_item([0b1110, 0b0111, 0b1101]), Some(3));
+        assert_eq!(common_item([0b1100, 0b0111]), Some(3));
+        assert_eq!(common_item([0b1110, 0b0111]), None);
+        assert_eq!(common_item([0b1100, 0b0011]), None);
+        assert_eq!(common_item([1 << 127]), Some(128));
     }
 
     #[test]
     fn can_map_to_priorities() {
+        let alphabet = Alphabet::standard();
+
         assert_eq!(
-            vec!['p', 'L', 'P', 'v', 't', 's']
-                .iter()
-                .map(map_char_to_priority)
-                .collect::<Vec<u32>>(),
-            vec![16, 38, 42, 22, 20, 19]
+            vec!['p', 'L', 'P', 'v', 't', 's', 'a', 'z', 'A', 'Z']
+                .into_iter()
+                .map(|item| alphabet.priority(item))
+                .collect::<Vec<Option<u32>>>(),
+            vec![16, 38, 42, 22, 20, 19, 1, 26, 27, 52].into_iter().map(Some).collect::<Vec<Option<u32>>>()
         );
 
-        assert_eq!(
-            vec!['a', 'z', 'A', 'Z']
-                .iter()
-                .map(map_char_to_priority)
-                .collect::<Vec<u32>>(),
-            vec![1, 26, 27, 52]
-        )
+        assert_eq!(alphabet.priority('1'), None);
+        assert_eq!(alphabet.item(38), Some('L'));
+        assert_eq!(alphabet.item(0), None);
+
+        let digits = Alphabet::new("0123456789").unwrap();
+        assert_eq!(digits.priority('0'), Some(1));
+        assert_eq!(digits.item(10), Some('9'));
+    }
+
+    #[test]
+    fn reports_invalid_alphabets() {
+        let duplicate = Alphabet::new("abcb").unwrap_err();
+        assert_eq!((duplicate.column, duplicate.text.as_str()), (4, "b"));
+
+        let items: String = (0..200).filter_map(|code| char::from_u32(0x100 + code)).collect();
+        assert_eq!(Alphabet::new(&items[..]).unwrap_err().message, "Expected at most 128 items");
+        assert!(Alphabet::new(&items.chars().take(128).collect::<String>()).is_ok());
     }
 
     #[test]
     fn can_sum_mismatched_items_priorities() {
-        let rucksacks: Vec<String> = get_sample_data().lines().map(|line| line.to_string()).collect();
+        let rucksacks = parse_input(&get_sample_data()).unwrap();
         assert_eq!(
-            sum_mismatched_items(&rucksacks),
-            157
+            sum_mismatched_items(&rucksacks, 2),
+            Ok(157)
         )
     }
 
     #[test]
     fn can_sum_badge_priorities() {
-        let rucksacks: Vec<String> = parse_input(&get_sample_data());
+        let rucksacks = parse_input(&get_sample_data()).unwrap();
         assert_eq!(
-            sum_group_badge_priorities(&rucksacks),
-            70
+            sum_group_badge_priorities(&rucksacks, 3),
+            Ok(70)
         )
     }
+
+    #[test]
+    fn can_use_other_alphabets_compartments_and_groups() {
+        let alphabet = Alphabet::new("αβγδ+-").unwrap();
+
+        let rucksack = parse_rucksack("αβ+γ+δ+-γ", &alphabet, 3).unwrap();
+        assert_eq!(mismatched_item(&rucksack, 3), Some(5));
+        assert_eq!(rucksack.compartments(3), vec![0b010011, 0b011100, 0b110100]);
+
+        let rucksacks = parse_rucksacks("βαβγ\nδβ+β\nγγ\nγ-γ+", &alphabet, 2).unwrap();
+        assert_eq!(sum_mismatched_items(&rucksacks, 2), Ok(2 + 2 + 3 + 3));
+        assert_eq!(group_badge(&rucksacks[0..2]), Some(2));
+        assert_eq!(sum_group_badge_priorities(&rucksacks, 2), Ok(2 + 3));
+
+        let rucksacks = parse_rucksacks("γγ\nγγ\nαα\nββ", &alphabet, 2).unwrap();
+        let no_badge = sum_group_badge_priorities(&rucksacks, 2).unwrap_err();
+        assert_eq!((no_badge.line, no_badge.column, no_badge.text.as_str()), (3, 1, "αα"));
+        assert_eq!(no_badge.message, "Expected exactly one item in all 2 rucksacks of the group");
+
+        let bad_item = parse_rucksack("αβz", &alphabet, 1).unwrap_err();
+        assert_eq!((bad_item.column, bad_item.text.as_str()), (3, "z"));
+        assert_eq!(bad_item.message, "Expected an item: α-δ, + or -");
+    }
+
+    #[test]
+    fn reports_invalid_rucksacks() {
+        let bad_item = parse_input("abcd\nab1d").unwrap_err();
+        assert_eq!((bad_item.line, bad_item.column, bad_item.text.as_str()), (2, 3, "1"));
+        assert_eq!(bad_item.message, "Expected an item: a-z or A-Z");
+
+        let odd_length = parse_input("abcd\nabc").unwrap_err();
+        assert_eq!((odd_length.line, odd_length.column, odd_length.text.as_str()), (2, 1, "abc"));
+
+        let no_compartments = parse_rucksack("abcd", &Alphabet::standard(), 0).unwrap_err();
+        assert_eq!(no_compartments.message, "Expected rucksacks to have at least one compartment");
+    }
+
+    #[test]
+    fn reports_rucksacks_each_part_cant_use() {
+        // Each part accepts input the other rejects
+        let rucksacks = parse_input("abca\nabcd\naeaf").unwrap();
+        let no_mismatch = sum_mismatched_items(&rucksacks, 2).unwrap_err();
+        assert_eq!((no_mismatch.line, no_mismatch.column, no_mismatch.text.as_str()), (2, 1, "abcd"));
+        assert_eq!(no_mismatch.message, "Expected exactly one item in all 2 compartments");
+        assert_eq!(sum_group_badge_priorities(&rucksacks, 3), Ok(1));
+
+        let rucksacks = parse_input("abca\nazdz\naefe\nabcb").unwrap();
+        assert_eq!(sum_mismatched_items(&rucksacks, 2), Ok(1 + 26 + 5 + 2));
+        let short_group = sum_group_badge_priorities(&rucksacks, 3).unwrap_err();
+        assert_eq!((short_group.line, short_group.column, short_group.text.as_str()), (4, 1, "abcb"));
+        assert_eq!(short_group.message, "Expected the rucksacks to split into groups of 3");
+
+        assert!(sum_group_badge_priorities(&rucksacks, 0).is_err());
+        assert_eq!(rucksacks[0].compartments(0), vec![]);
+    }
 }
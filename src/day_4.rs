@@ -1,76 +1,199 @@
 //! This is my solution for [Advent of Code - Day 4 - _Camp Cleanup_](https://adventofcode.com/2022/day/4)
 //!
 //! Today is comparing the ranges of the base camp pairs of elves have to clean to check for redundancy.
+//!
+//! There are also queries across every elf's assignment, see [`coverage`]. For these the pairs are flattened into one
+//! list of elves with [`elves`], so the elves in pair `n` have the indices `2n` and `2n + 1`.
 
-/// Represents a length of beach an elf is assigned to clean
-type Range = (u32, u32);
-/// The assignments for a pair of elves
-type Pair = (Range, Range);
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use crate::solution::{Answer, Solution};
+use crate::util::interval::{Interval, IntervalSet};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 
-use std::fs;
+/// The assignments for a pair of elves, each is the length of beach that elf is assigned to clean
+pub type Pair = (Interval, Interval);
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-4-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 4.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-4-input").expect("Failed to read file");
-    let pairs = parse_input(&contents);
-
-    println!(
-        "There are {} redundant pairs of elves",
-        count_pairs_matching(&pairs, pair_has_redundant_elf)
-    );
-
-    println!(
-        "There are {} overlapping pairs of elves",
-        count_pairs_matching(&pairs, pair_overlaps)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-4-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 4.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Parsed = Vec<Pair>;
+    const DAY: usize = 4;
+
+    fn parse(&self, input: &str) -> Result<Vec<Pair>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, pairs: &Vec<Pair>) -> Answer {
+        count_pairs_matching(pairs, pair_has_redundant_elf).into()
+    }
+
+    fn part_two(&self, pairs: &Vec<Pair>) -> Answer {
+        count_pairs_matching(pairs, pair_overlaps).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("There are {} redundant pairs of elves", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("There are {} overlapping pairs of elves", answer)
+    }
 }
 
 /// Parse the string puzzle inut into a list of elf pairs
-fn parse_input(input: &String) -> Vec<Pair> {
-    input.lines().map(parse_line).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Pair>, ParseError> {
+    parse_lines(input, parse_line)
 }
 
 /// Parse a lne of the input as a single pair of elves
-fn parse_line(line: &str) -> Pair {
-    let parts: Vec<&str> = line.split(',').collect();
-    (
-        parse_range(parts[0]),
-        parse_range(parts[1])
-    )
+pub fn parse_line(line: &str) -> Result<Pair, ParseError> {
+    let (elf1, elf2) = split_once(line, line, ",")?;
+    Ok((
+        parse_range(line, elf1)?,
+        parse_range(line, elf2)?
+    ))
 }
 
 /// Parse the bounds of the range assigned to one elf
-fn parse_range(spec: &str) -> Range {
-    let limits: Vec<u32> =
-        spec.split('-')
-            .map(|str| str.parse::<u32>().unwrap())
-            .collect();
+pub fn parse_range(line: &str, spec: &str) -> Result<Interval, ParseError> {
+    let (start, end) = split_once(line, spec, "-")?;
+    let (start, end) = (parse_number(line, start)?, parse_number(line, end)?);
 
-    (limits[0], limits[1])
+    if start > end {
+        return Err(ParseError::new(line, spec, "Expected the start of the range to not be after its end"));
+    }
+
+    Ok(Interval::new(start, end))
 }
 
 /// Predicate for counting pairs that wholly overlao
-fn pair_has_redundant_elf(((elf1_start, elf1_end), (elf2_start, elf2_end)): Pair) -> bool {
-    (elf1_start <= elf2_start && elf1_end >= elf2_end) ||
-        (elf1_start >= elf2_start && elf1_end <= elf2_end)
+pub fn pair_has_redundant_elf((elf1, elf2): Pair) -> bool {
+    elf1.contains_interval(&elf2) || elf2.contains_interval(&elf1)
 }
 
 /// Predicate for counting pairs that wholly or partially overlap
-fn pair_overlaps(((elf1_start, elf1_end), (elf2_start, elf2_end)): Pair) -> bool {
-    elf1_start <= elf2_end && elf1_end >= elf2_start
+pub fn pair_overlaps((elf1, elf2): Pair) -> bool {
+    elf1.overlaps(&elf2)
 }
 
 /// Given a list of elf pairs, count those that match the given predicate
-fn count_pairs_matching(pairs: &Vec<Pair>, predicate: fn(Pair) -> bool) -> usize {
+pub fn count_pairs_matching(pairs: &[Pair], predicate: fn(Pair) -> bool) -> usize {
     pairs.iter().filter(|&&pair| predicate(pair)).count()
 }
 
+/// Every elf's assignment, in the order they're listed
+pub fn elves(pairs: &[Pair]) -> Vec<Interval> {
+    pairs.iter().flat_map(|&(elf1, elf2)| [elf1, elf2]).collect()
+}
+
+/// Split the assigned sections into runs covered by the same elves, returning each run with the indices of those
+/// elves in order. Sections no one is assigned are left out.
+pub fn coverage(elves: &[Interval]) -> Vec<(Interval, Vec<usize>)> {
+    // Each elf's first section, and the section after its last, where the set of elves covering a section changes
+    let mut events: Vec<(isize, usize)> =
+        elves.iter()
+             .enumerate()
+             .flat_map(|(index, elf)| {
+                 [Some((elf.start(), index)), elf.end().checked_add(1).map(|after| (after, index))]
+             })
+             .flatten()
+             .collect();
+    events.sort_unstable();
+
+    let mut active = BTreeSet::new();
+    let mut runs = Vec::new();
+    let mut next = 0;
+
+    while let Some(&(position, _)) = events.get(next) {
+        while let Some(&(_, index)) = events.get(next).filter(|&&(event_position, _)| event_position == position) {
+            if !active.remove(&index) {
+                active.insert(index);
+            }
+            next += 1;
+        }
+
+        if !active.is_empty() {
+            let end = events.get(next).map_or(isize::MAX, |&(after, _)| after - 1);
+            runs.push((Interval::new(position, end), active.iter().copied().collect()));
+        }
+    }
+
+    runs
+}
+
+/// The sections assigned to the most elves, as runs of sections with the indices of the elves covering them
+pub fn most_covered_sections(elves: &[Interval]) -> Vec<(Interval, Vec<usize>)> {
+    let runs = coverage(elves);
+    let most = runs.iter().map(|(_, covering)| covering.len()).max().unwrap_or(0);
+
+    runs.into_iter().filter(|(_, covering)| covering.len() == most).collect()
+}
+
+/// The indices of elves whose sections are all assigned to at least one other elf. Each is redundant on its own,
+/// removing all of them at once may leave sections uncovered, see [`minimum_cover`] for that.
+pub fn redundant_elves(elves: &[Interval]) -> Vec<usize> {
+    let covered_once: IntervalSet =
+        coverage(elves).into_iter()
+                       .filter(|(_, covering)| covering.len() == 1)
+                       .map(|(sections, _)| sections)
+                       .collect();
+
+    (0..elves.len()).filter(|&index| !covered_once.overlaps(&elves[index])).collect()
+}
+
+/// The indices of the fewest elves that between them cover every assigned section, in the order of the sections they
+/// cover. Where elves are equally good the one listed first is picked.
+pub fn minimum_cover(elves: &[Interval]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..elves.len()).collect();
+    order.sort_by_key(|&index| elves[index].start());
+    // Elves reaching further are better, then those listed earlier
+    let rank = |index: usize| (elves[index].end(), Reverse(index));
+
+    let mut chosen = Vec::new();
+    let mut covered_to: Option<isize> = None;
+    let mut next = 0;
+
+    // Repeatedly pick the elf that extends furthest from the first uncovered section, among the elves starting by then
+    while let Some(&first) = order.get(next) {
+        let from = match covered_to {
+            Some(isize::MAX) => break,
+            Some(end) if elves[first].start() <= end + 1 => end + 1,
+            _ => elves[first].start(),
+        };
+
+        let mut best: Option<usize> = None;
+        while let Some(&index) = order.get(next).filter(|&&index| elves[index].start() <= from) {
+            if elves[index].end() >= from && best.is_none_or(|best| rank(index) > rank(best)) {
+                best = Some(index);
+            }
+            next += 1;
+        }
+
+        if let Some(best) = best {
+            chosen.push(best);
+            covered_to = Some(elves[best].end());
+        }
+    }
+
+    chosen
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::day_4::{count_pairs_matching, Pair, pair_has_redundant_elf, pair_overlaps, parse_input};
+    use crate::day_4::{
+        count_pairs_matching, coverage, elves, minimum_cover, most_covered_sections, Pair, pair_has_redundant_elf,
+        pair_overlaps, parse_input, redundant_elves,
+    };
+    use crate::util::interval::{Interval, IntervalSet};
+
+    fn pair(((start1, end1), (start2, end2)): ((isize, isize), (isize, isize))) -> Pair {
+        (Interval::new(start1, end1), Interval::new(start2, end2))
+    }
 
     fn sample_pairs() -> Vec<Pair> {
         vec![
@@ -80,7 +203,7 @@ mod tests {
             ((2, 8), (3, 7)),
             ((6, 6), (4, 6)),
             ((2, 6), (4, 8)),
-        ]
+        ].into_iter().map(pair).collect()
     }
 
     #[test]
@@ -92,7 +215,7 @@ mod tests {
 6-6,4-6
 2-6,4-8".to_string();
 
-        assert_eq!(parse_input(&input), sample_pairs());
+        assert_eq!(parse_input(&input), Ok(sample_pairs()));
     }
 
     #[test]
@@ -107,7 +230,7 @@ mod tests {
             ((4, 6), (4, 4)),
             ((4, 6), (6, 6)),
         ];
-        for pair in boundaries {
+        for pair in boundaries.into_iter().map(pair) {
             assert_eq!(pair_has_redundant_elf(pair), true, "Check pair {:?} redundancy", pair);
         }
     }
@@ -122,7 +245,7 @@ mod tests {
             (((4, 5), (6, 7)), false),
             (((6, 7), (4, 5)), false),
         ];
-        for (pair, expected) in possibilities {
+        for (pair, expected) in possibilities.into_iter().map(|(spec, expected)| (pair(spec), expected)) {
             assert_eq!(pair_overlaps(pair), expected, "Check pair {:?} overlap", pair);
         }
     }
@@ -132,4 +255,101 @@ mod tests {
         assert_eq!(count_pairs_matching(&sample_pairs(), pair_has_redundant_elf), 2);
         assert_eq!(count_pairs_matching(&sample_pairs(), pair_overlaps), 4);
     }
+
+    #[test]
+    fn reports_invalid_pairs() {
+        let bad_number = parse_input("2-4,6-8\n2-3,4-x").unwrap_err();
+        assert_eq!((bad_number.line, bad_number.column, bad_number.text.as_str()), (2, 7, "x"));
+
+        let missing_elf = parse_input("2-4").unwrap_err();
+        assert_eq!(missing_elf.message, "Expected ','");
+
+        let missing_end = parse_input("2-4,6").unwrap_err();
+        assert_eq!((missing_end.column, missing_end.message.as_str()), (5, "Expected '-'"));
+
+        let backwards = parse_input("2-4,8-6").unwrap_err();
+        assert_eq!((backwards.column, backwards.text.as_str()), (5, "8-6"));
+    }
+
+    fn intervals(specs: &[(isize, isize)]) -> Vec<Interval> {
+        specs.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    /// Find the size of the smallest covering set by trying every combination of elves
+    fn brute_force_cover_size(elves: &[Interval]) -> usize {
+        let all: IntervalSet = elves.iter().copied().collect();
+
+        (0..1usize << elves.len())
+            .filter(|mask| {
+                let chosen: IntervalSet =
+                    (0..elves.len()).filter(|index| mask & (1 << index) != 0).map(|index| elves[index]).collect();
+                chosen == all
+            })
+            .map(|mask| mask.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn can_find_coverage() {
+        let elves = elves(&sample_pairs());
+        assert_eq!(elves.len(), 12);
+        assert_eq!(elves[3], Interval::new(4, 5));
+
+        let runs = coverage(&elves);
+        assert_eq!(runs.len(), 8);
+        assert_eq!(runs[0], (Interval::new(2, 2), vec![0, 2, 6, 10]));
+        assert_eq!(runs[7], (Interval::new(9, 9), vec![5]));
+
+        assert_eq!(
+            coverage(&intervals(&[(1, 4), (3, 5), (10, 10)])),
+            vec![
+                (Interval::new(1, 2), vec![0]),
+                (Interval::new(3, 4), vec![0, 1]),
+                (Interval::new(5, 5), vec![1]),
+                (Interval::new(10, 10), vec![2]),
+            ]
+        );
+        assert_eq!(coverage(&[]), vec![]);
+    }
+
+    #[test]
+    fn can_find_most_covered_sections() {
+        assert_eq!(
+            most_covered_sections(&elves(&sample_pairs())),
+            vec![(Interval::new(6, 6), vec![1, 4, 6, 7, 8, 9, 10, 11])]
+        );
+
+        assert_eq!(
+            most_covered_sections(&intervals(&[(1, 2), (2, 3), (5, 6), (6, 8)])),
+            vec![(Interval::new(2, 2), vec![0, 1]), (Interval::new(6, 6), vec![2, 3])]
+        );
+    }
+
+    #[test]
+    fn can_find_redundant_elves() {
+        assert_eq!(redundant_elves(&elves(&sample_pairs())), vec![0, 1, 2, 3, 4, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(redundant_elves(&intervals(&[(1, 4), (3, 5), (1, 3), (4, 4)])), vec![0, 2, 3]);
+        assert_eq!(redundant_elves(&intervals(&[(1, 4), (1, 4), (7, 9)])), vec![0, 1]);
+    }
+
+    #[test]
+    fn can_find_minimum_cover() {
+        assert_eq!(minimum_cover(&elves(&sample_pairs())), vec![6, 5]);
+
+        let gappy = intervals(&[(1, 3), (2, 5), (4, 4), (8, 10), (9, 12), (12, 12), (20, 20)]);
+        assert_eq!(minimum_cover(&gappy), vec![0, 1, 3, 4, 6]);
+        assert_eq!(minimum_cover(&intervals(&[(1, 3), (3, 8), (2, 8)])), vec![0, 1]);
+
+        for elves in [elves(&sample_pairs()), gappy, intervals(&[(1, 10), (2, 3), (5, 12), (11, 15), (13, 13)])] {
+            let cover = minimum_cover(&elves);
+            let covered: IntervalSet = cover.iter().map(|&index| elves[index]).collect();
+
+            assert_eq!(covered, elves.iter().copied().collect());
+            assert_eq!(cover.len(), brute_force_cover_size(&elves));
+        }
+
+        assert_eq!(minimum_cover(&intervals(&[(0, isize::MAX), (5, isize::MAX)])), vec![0]);
+        assert_eq!(minimum_cover(&[]), vec![]);
+    }
 }
@@ -4,19 +4,22 @@
 //! instruction list.
 
 use std::collections::VecDeque;
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
 use itertools::Itertools;
 
 /// Specifies one move of a stack of crates: `(number_of_crates, source_stack, target_stack)`
-type Move = (usize, usize, usize);
+pub type Move = (usize, usize, usize);
 
 /// Represents the current state of the set of stacks being moved.
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct SupplyStacks {
-    stacks: Vec<VecDeque<char>>
+pub struct SupplyStacks {
+    pub stacks: Vec<VecDeque<char>>
 }
 
-impl From<&str> for SupplyStacks {
+impl TryFrom<&str> for SupplyStacks {
+    type Error = ParseError;
+
     /// Parses a diagram of crates into the internal representation.
     ///
     /// Example input:
@@ -26,26 +29,36 @@ impl From<&str> for SupplyStacks {
     /// [Z] [M] [P]
     ///  1   2   3
     /// ```
-    fn from(input: &str) -> Self {
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
         let mut stacks: Vec<VecDeque<char>> = Vec::new();
         let mut lines = input.lines().rev();
-        let numbers = lines.next().unwrap();
+        let numbers = lines.next().ok_or_else(|| ParseError::new(input, input, "Expected a diagram of crates"))?;
 
         for _ in numbers.split_whitespace() {
             stacks.push(VecDeque::new())
         };
 
         for line in lines {
-            for (i, chunk) in line.chars().chunks(4).into_iter().enumerate() {
+            if let Some((pos, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+                return Err(ParseError::new(input, &line[pos..pos + c.len_utf8()], "Expected a crate e.g. '[A]'"));
+            }
+
+            for (i, start) in (0..line.len()).step_by(4).enumerate() {
                 // Chunk is either `[#] ` ore `    `, the last in each line will be missing the final space
-                let character: char = chunk.dropping(1).next().unwrap();
-                if character.is_alphabetic() {
-                    stacks[i].push_front(character)
+                let chunk = &line[start..line.len().min(start + 3)];
+                match chunk.as_bytes() {
+                    &[b'[', label, b']'] if label.is_ascii_alphabetic() => {
+                        let not_numbered =
+                            || ParseError::new(input, chunk, "Expected crate to be above a numbered stack");
+                        stacks.get_mut(i).ok_or_else(not_numbered)?.push_front(label as char)
+                    }
+                    _ if chunk.trim().is_empty() => {}
+                    _ => return Err(ParseError::new(input, chunk, "Expected a crate e.g. '[A]'"))
                 }
             }
         }
 
-        SupplyStacks { stacks }
+        Ok(SupplyStacks { stacks })
     }
 }
 
@@ -75,57 +88,105 @@ impl SupplyStacks {
 
     /// Combine the characters at the top of each stack into a string used as the puzzle output.
     fn get_top_crates(&self) -> String {
-        self.stacks.to_owned().into_iter().map(|stack| stack[0]).join("")
+        self.stacks.iter().filter_map(|stack| stack.front()).join("")
     }
 }
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-5-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 5.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-5-input").expect("Failed to read file");
-    let (mut part_1_stacks, moves) = parse_input(&contents);
-    let mut part_2_stacks = part_1_stacks.clone();
-
-    part_1_stacks.do_moves(&moves, false);
-    println!(
-        "After moving one crate at a time, the top of the stacks are: {}",
-        part_1_stacks.get_top_crates()
-    );
-
-    part_2_stacks.do_moves(&moves, true);
-    println!(
-        "After moving the crates in bulk, the top of the stacks are: {}",
-        part_2_stacks.get_top_crates()
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-5-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 5.
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Parsed = (SupplyStacks, Vec<Move>);
+    const DAY: usize = 5;
+
+    fn parse(&self, input: &str) -> Result<(SupplyStacks, Vec<Move>), ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, (stacks, moves): &(SupplyStacks, Vec<Move>)) -> Answer {
+        let mut part_1_stacks = stacks.clone();
+        part_1_stacks.do_moves(moves, false);
+        part_1_stacks.get_top_crates().into()
+    }
+
+    fn part_two(&self, (stacks, moves): &(SupplyStacks, Vec<Move>)) -> Answer {
+        let mut part_2_stacks = stacks.clone();
+        part_2_stacks.do_moves(moves, true);
+        part_2_stacks.get_top_crates().into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("After moving one crate at a time, the top of the stacks are: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("After moving the crates in bulk, the top of the stacks are: {}", answer)
+    }
 }
 
 /// SPlit the input into the two sections and independently parse each one
-fn parse_input(input: &String) -> (SupplyStacks, Vec<Move>) {
-    let (stack_spec, moves_spec) = input.split_once("\n\n").unwrap();
+pub fn parse_input(input: &str) -> Result<(SupplyStacks, Vec<Move>), ParseError> {
+    let (stack_spec, moves_spec) = split_once(input, input, "\n\n")?;
+    let stacks = SupplyStacks::try_from(stack_spec).map_err(|err| err.within(input, stack_spec))?;
+    let moves = parse_moves(moves_spec, stacks.stacks.len()).map_err(|err| err.within(input, moves_spec))?;
+    check_crate_counts(moves_spec, &stacks, &moves).map_err(|err| err.within(input, moves_spec))?;
+
+    Ok((stacks, moves))
+}
+
+/// Follow the height of each stack through the moves, checking no move takes more crates than its stack holds. The
+/// heights change the same way whether crates are moved one at a time or in bulk, so this covers both parts.
+pub fn check_crate_counts(input: &str, stacks: &SupplyStacks, moves: &[Move]) -> Result<(), ParseError> {
+    let mut heights: Vec<usize> = stacks.stacks.iter().map(VecDeque::len).collect();
+
+    for (line, &(count, from, to)) in input.lines().zip(moves) {
+        if heights[from - 1] < count {
+            let count_spec = line.split_whitespace().nth(1).unwrap_or(line);
+            let message = format!("Expected at most {} crate(s) to move from stack {}", heights[from - 1], from);
+            return Err(ParseError::new(input, count_spec, message));
+        }
 
-    (SupplyStacks::from(stack_spec), parse_moves(moves_spec))
+        heights[from - 1] -= count;
+        heights[to - 1] += count;
+    }
+
+    Ok(())
 }
 
 /// Map the list of moves to the internal representation
-fn parse_moves(input: &str) -> Vec<Move> {
-    input.lines().map(parse_move).collect()
+pub fn parse_moves(input: &str, stack_count: usize) -> Result<Vec<Move>, ParseError> {
+    parse_lines(input, |line| parse_move(line, stack_count))
 }
 
 /// Parse a single move line in the format `move 2 from 2 to 1`
-fn parse_move(line: &str) -> Move {
-    let parts: Vec<usize> =
-        line.split_whitespace()
-            .flat_map(|str| str.parse::<usize>())
-            .collect();
+pub fn parse_move(line: &str, stack_count: usize) -> Result<Move, ParseError> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+
+    match parts[..] {
+        ["move", count, "from", from, "to", to] => Ok((
+            parse_number(line, count)?,
+            parse_stack_number(line, from, stack_count)?,
+            parse_stack_number(line, to, stack_count)?,
+        )),
+        _ => Err(ParseError::new(line, line, "Expected a move e.g. 'move 1 from 2 to 3'"))
+    }
+}
 
-    (parts[0], parts[1], parts[2])
+/// Parse the number of a stack in a move, checking the stack exists
+pub fn parse_stack_number(line: &str, spec: &str, stack_count: usize) -> Result<usize, ParseError> {
+    match parse_number(line, spec)? {
+        stack if (1..=stack_count).contains(&stack) => Ok(stack),
+        _ => Err(ParseError::new(line, spec, format!("Expected a stack from 1 to {}", stack_count)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day_5::{SupplyStacks, Move, parse_input};
+    use crate::util::parse::ParseError;
 
     #[test]
     fn can_parse() {
@@ -139,7 +200,7 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2".to_string();
 
-        let (actual_stacks, actual_moves) = parse_input(&sample_input);
+        let (actual_stacks, actual_moves) = parse_input(&sample_input).unwrap();
 
         assert_eq!(actual_stacks, sample_stacks());
         assert_eq!(actual_moves, sample_moves());
@@ -206,5 +267,54 @@ move 1 from 1 to 2".to_string();
     fn can_get_stack_tops() {
         assert_eq!(sample_stacks().get_top_crates(), "NDP");
         assert_eq!(sample_stacks_after_moving_one_at_a_time().get_top_crates(), "CMZ");
+
+        let (mut emptied, moves) = parse_input("[N] [C]\n 1   2\n\nmove 1 from 1 to 2").unwrap();
+        emptied.do_moves(&moves, true);
+        assert_eq!(emptied.get_top_crates(), "N");
+    }
+
+    #[test]
+    fn reports_invalid_input() {
+        let position = |err: ParseError| (err.line, err.column, err.text);
+
+        assert_eq!(
+            parse_input("[N] [C]\n 1   2\n\nmove 1 from 2 to 1").map_err(position),
+            Ok((
+                SupplyStacks { stacks: vec![vec!['N'].into_iter().collect(), vec!['C'].into_iter().collect()] },
+                vec![(1, 2, 1)]
+            ))
+        );
+
+        assert_eq!(
+            parse_input("[N] (C)\n 1   2\n\nmove 1 from 2 to 1").map_err(position),
+            Err((1, 5, "(C)".to_string()))
+        );
+
+        assert_eq!(
+            parse_input("[N] [C] [D]\n 1   2\n\nmove 1 from 2 to 1").map_err(position),
+            Err((1, 9, "[D]".to_string()))
+        );
+
+        assert_eq!(
+            parse_input("[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 3 to 1").map_err(position),
+            Err((5, 13, "3".to_string()))
+        );
+
+        assert_eq!(
+            parse_input("[N] [C]\n 1   2\n\nmove one from 2 to 1").map_err(position),
+            Err((4, 6, "one".to_string()))
+        );
+
+        assert_eq!(
+            parse_input("[N] [C]\n 1   2\n\nshift 1 from 2 to 1").map_err(position),
+            Err((4, 1, "shift 1 from 2 to 1".to_string()))
+        );
+
+        assert_eq!(
+            parse_input("[N] [C]\n[M] [D]\n 1   2\n\nmove 2 from 1 to 2\nmove 5 from 2 to 1").map_err(position),
+            Err((6, 6, "5".to_string()))
+        );
+
+        assert!(parse_input("[N] [C]\n 1   2\n").is_err());
     }
 }
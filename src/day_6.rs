@@ -3,12 +3,13 @@
 //! Find substrings with a unique set of characters in a much larger string
 
 use std::collections::{HashMap};
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::ParseError;
 use itertools::Itertools;
 
 /// Represents a window of characters over a data stream by their counts
-struct Counts {
-    counts: HashMap<char, usize>,
+pub struct Counts {
+    pub counts: HashMap<char, usize>,
 }
 
 impl Counts {
@@ -55,20 +56,52 @@ impl Counts {
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-6-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 6.
-pub fn run() {
-    let _contents = fs::read_to_string("res/day-6-input").expect("Failed to read file");
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-6-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 6.
+pub struct Day6;
 
-    let start_of_packet = find_non_repeating_string_of_length(&_contents, 4);
-    println!("The start of packet is detected after {} characters", start_of_packet);
+impl Solution for Day6 {
+    type Parsed = String;
+    const DAY: usize = 6;
 
-    let start_of_message = find_non_repeating_string_of_length(&_contents, 14);
-    println!("The start of packet is detected after {} characters", start_of_message);
+    fn parse(&self, input: &str) -> Result<String, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, data_stream: &String) -> Answer {
+        find_non_repeating_string_of_length(data_stream, 4).into()
+    }
+
+    fn part_two(&self, data_stream: &String) -> Answer {
+        find_non_repeating_string_of_length(data_stream, 14).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The start of packet is detected after {} characters", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The start of packet is detected after {} characters", answer)
+    }
+}
+
+/// The data stream is a single line of lowercase letters, long enough to hold a start of message marker
+pub fn parse_input(input: &str) -> Result<String, ParseError> {
+    let data_stream = input.trim_end();
+
+    if let Some((pos, c)) = data_stream.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        return Err(ParseError::new(input, &data_stream[pos..pos + c.len_utf8()], "Expected a lowercase letter"));
+    }
+
+    if data_stream.len() < 14 {
+        return Err(ParseError::new(input, data_stream, "Expected at least 14 characters"));
+    }
+
+    Ok(data_stream.to_string())
 }
 
 /// Find the first substring of unique consecutive characters with length `window_size`
-fn find_non_repeating_string_of_length(data_stream: &String, window_size: usize) -> usize {
+pub fn find_non_repeating_string_of_length(data_stream: &str, window_size: usize) -> usize {
     let (init, rest) = data_stream.split_at(window_size);
     let mut counts = Counts::new(init);
 
@@ -89,7 +122,7 @@ fn find_non_repeating_string_of_length(data_stream: &String, window_size: usize)
 
 #[cfg(test)]
 mod tests {
-    use crate::day_6::find_non_repeating_string_of_length;
+    use crate::day_6::{find_non_repeating_string_of_length, parse_input};
 
     #[test]
     fn can_find_start_of_packet() {
@@ -126,4 +159,15 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn can_parse_data_stream() {
+        assert_eq!(parse_input("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n"), Ok("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string()));
+
+        let bad_character = parse_input("mjqjpqmgbljsphdz\ntnvjfqwrcgsmlb").unwrap_err();
+        assert_eq!((bad_character.line, bad_character.column, bad_character.text.as_str()), (1, 17, "\n"));
+
+        let too_short = parse_input("mjqjpqmgbljsp").unwrap_err();
+        assert_eq!(too_short.message, "Expected at least 14 characters");
+    }
 }
@@ -2,24 +2,24 @@
 //!
 //! Parse and recursively walk a directory structure to find the optimal directory to delete
 
-use std::fs;
-use std::num::ParseIntError;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_number, ParseError, split_once};
 use itertools::Itertools;
 use crate::day_7::Command::{AddDir, AddFile, PopDir, PushDir, RootDir};
 
 /// Represent a file in a filesystem
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct File {
-    name: String,
-    size: usize,
+pub struct File {
+    pub name: String,
+    pub size: usize,
 }
 
 /// Represent a directory in a file system
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct Directory {
-    name: String,
-    sub_dirs: Vec<Directory>,
-    files: Vec<File>,
+pub struct Directory {
+    pub name: String,
+    pub sub_dirs: Vec<Directory>,
+    pub files: Vec<File>,
 }
 
 impl From<&str> for Directory {
@@ -30,7 +30,7 @@ impl From<&str> for Directory {
 
 /// Represent console output as a command to update the state of a [`FileSystem`] to match
 #[derive(Eq, PartialEq, Debug, Clone)]
-enum Command {
+pub enum Command {
     PushDir(String),
     PopDir,
     RootDir,
@@ -39,23 +39,26 @@ enum Command {
 }
 
 impl TryFrom<&str> for Command {
-    type Error = ParseIntError;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             str if str.starts_with("$ cd /") => Ok(RootDir),
             str if str.starts_with("$ cd ..") => Ok(PopDir),
             str if str.starts_with("$ cd") => {
-                let dir = str.split_whitespace().dropping(2).next().unwrap();
+                let dir = str.split_whitespace().dropping(2).next()
+                             .ok_or_else(|| ParseError::new(str, str, "Expected a directory to change to"))?;
                 Ok(PushDir(dir.to_string()))
             }
             str if str.starts_with("dir") => {
-                let dir = str.split_whitespace().dropping(1).next().unwrap();
+                let dir = str.split_whitespace().dropping(1).next()
+                             .ok_or_else(|| ParseError::new(str, str, "Expected a directory name"))?;
                 Ok(AddDir(Directory::from(dir)))
             }
+            str if str.starts_with('$') => Err(ParseError::new(str, str, "Expected a command: 'cd' or 'ls'")),
             str => {
-                let (file_size, name) = str.split_once(" ").unwrap();
-                file_size.parse::<usize>().map(|size: usize| AddFile(File { size, name: name.to_string() }))
+                let (file_size, name) = split_once(str, str, " ")?;
+                Ok(AddFile(File { size: parse_number(str, file_size)?, name: name.to_string() }))
             }
         }
     }
@@ -63,9 +66,9 @@ impl TryFrom<&str> for Command {
 
 /// Represent a file system with it's contents in a tree starting at `root` and the current working directory in `path`
 #[derive(Eq, PartialEq, Debug, Clone)]
-struct FileSystem {
-    root: Directory,
-    path: Vec<String>,
+pub struct FileSystem {
+    pub root: Directory,
+    pub path: Vec<String>,
 }
 
 impl From<Vec<Command>> for FileSystem {
@@ -147,32 +150,52 @@ impl Directory {
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-7-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 7.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-7-input").expect("Failed to read file");
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-7-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 7.
+pub struct Day7;
 
-    let file_system = FileSystem::from(parse_commands(&contents));
+impl Solution for Day7 {
+    type Parsed = FileSystem;
+    const DAY: usize = 7;
 
-    println!("The sum of small directory sizes is: {}", get_small_dirs_size_sum(&file_system));
+    fn parse(&self, input: &str) -> Result<FileSystem, ParseError> {
+        parse_commands(input).map(FileSystem::from)
+    }
+
+    fn part_one(&self, file_system: &FileSystem) -> Answer {
+        get_small_dirs_size_sum(file_system).into()
+    }
+
+    fn part_two(&self, file_system: &FileSystem) -> Answer {
+        find_directory_size_to_delete(file_system).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The sum of small directory sizes is: {}", answer)
+    }
 
-    println!("The size of the directory selected for deletion is: {}", find_directory_size_to_delete(&file_system));
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The size of the directory selected for deletion is: {}", answer)
+    }
 }
 
 /// Turn console lines into structured data representing the change to the file system state indicated by that line
-/// of output.
-fn parse_commands(input: &String) -> Vec<Command> {
-    input.lines().flat_map(Command::try_from).collect()
+/// of output. `ls` doesn't change the state, so it is skipped.
+pub fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
+    input.lines()
+         .filter(|&line| line != "$ ls")
+         .map(|line| Command::try_from(line).map_err(|err| err.within(input, line)))
+         .collect()
 }
 
 /// Part 1: Sum all the directories whose total contents are 100_000 units or less
-fn get_small_dirs_size_sum(fs: &FileSystem) -> usize {
+pub fn get_small_dirs_size_sum(fs: &FileSystem) -> usize {
     fs.root.dir_sizes().iter().filter(|&&size| size <= 100_000).sum()
 }
 
 /// Part 2: Find the smallest directory that needs to be deleted to leave 30M units of space free from a total of 70M
 /// units of space.
-fn find_directory_size_to_delete(fs: &FileSystem) -> usize {
+pub fn find_directory_size_to_delete(fs: &FileSystem) -> usize {
     let sizes = fs.root.dir_sizes();
     let total_used = sizes.last().unwrap_or(&0);
     let total_free = 70_000_000 - total_used;
@@ -236,7 +259,7 @@ $ ls
 5626152 d.ext
 7214296 k".to_string();
 
-        assert_eq!(parse_commands(&input), sample_commands())
+        assert_eq!(parse_commands(&input), Ok(sample_commands()))
     }
 
     fn sample_filesystem<'a>() -> FileSystem {
@@ -311,4 +334,16 @@ $ ls
     fn can_find_dir_to_delete() {
         assert_eq!(find_directory_size_to_delete(&sample_filesystem()), 24933642)
     }
+
+    #[test]
+    fn reports_invalid_output() {
+        let bad_size = parse_commands("$ cd /\n$ ls\n12a b.txt").unwrap_err();
+        assert_eq!((bad_size.line, bad_size.column, bad_size.text.as_str()), (3, 1, "12a"));
+
+        let bad_command = parse_commands("$ cd /\n$ rm -rf a").unwrap_err();
+        assert_eq!((bad_command.line, bad_command.column, bad_command.text.as_str()), (2, 1, "$ rm -rf a"));
+
+        let missing_dir = parse_commands("$ cd /\n$ cd").unwrap_err();
+        assert_eq!(missing_dir.line, 2);
+    }
 }
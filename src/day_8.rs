@@ -2,32 +2,54 @@
 //!
 //! Identify the best tree in a grid to build a tree house in, it must be hidden and have a good view.
 
-use std::fs;
+use crate::solution::{Answer, Solution};
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
 use crate::util::grid::Grid;
+use crate::util::grid_parser::GridParser;
+use crate::util::parse::ParseError;
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-8-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 8.
-pub fn run() {
-    let contents = fs::read_to_string("res/day-8-input").expect("Failed to read file");
-    let grid = Grid::from(contents);
-
-    println!(
-        "The count of visible trees is: {}",
-        find_visible_count(&grid)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-8-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 8.
+pub struct Day8;
 
-    println!(
-        "The tree with the highest scenery score is: {}",
-        find_best_scenery_score(&grid)
-    );
+impl Solution for Day8 {
+    type Parsed = Grid<u8>;
+    const DAY: usize = 8;
+
+    fn parse(&self, input: &str) -> Result<Grid<u8>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, grid: &Grid<u8>) -> Answer {
+        find_visible_count(grid).into()
+    }
+
+    fn part_two(&self, grid: &Grid<u8>) -> Answer {
+        find_best_scenery_score(grid).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The count of visible trees is: {}", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The tree with the highest scenery score is: {}", answer)
+    }
+}
+
+/// Check the input is a rectangle of tree heights while turning it into a [`Grid`]
+pub fn parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    GridParser::new()
+        .expecting("a tree height: 0-9")
+        .parse(input, |c| c.to_digit(10).map(|d| d as u8))
+        .map(|parsed| parsed.grid)
 }
 
 /// Count the trees visible from the edges of the grid.
-fn find_visible_count(grid: &Grid) -> usize {
+pub fn find_visible_count(grid: &Grid<u8>) -> usize {
     let mut visible = Grid::new(
         grid.width,
         grid.height(),
@@ -49,20 +71,20 @@ fn find_visible_count(grid: &Grid) -> usize {
 
 /// For a given row or column start or end step forwards or backwards marking those that can be seen from the starting
 /// position
-fn mark_visible_trees(
+pub fn mark_visible_trees(
     (origin_y, origin_x): (usize, usize),
     (dy, dx): (isize, isize),
-    trees_grid: &Grid,
-    visibility_grid: &mut Grid,
+    trees_grid: &Grid<u8>,
+    visibility_grid: &mut Grid<u8>,
 ) {
-    let mut max_height = trees_grid.get(origin_x, origin_y).unwrap();
+    let mut max_height = *trees_grid.get(origin_x, origin_y).unwrap();
 
     (1..)
         .map(
             |pos| {
                 with_delta(origin_y, dy, pos)
                     .zip(with_delta(origin_x, dx, pos))
-                    .and_then(|(y, x)| trees_grid.get(y, x).map(|h| (y, x, h)))
+                    .and_then(|(y, x)| trees_grid.get(y, x).map(|&h| (y, x, h)))
             })
         .while_some()
         .for_each(|(y, x, h)| {
@@ -74,7 +96,7 @@ fn mark_visible_trees(
 }
 
 /// Apply a multiple of a delta to a starting position to get the position for a step if it is positive
-fn with_delta(init: usize, delta: isize, multiplier: isize) -> Option<usize> {
+pub fn with_delta(init: usize, delta: isize, multiplier: isize) -> Option<usize> {
     isize::try_from(init)
         .map(|init_i| init_i + delta * multiplier)
         .and_then(|result| usize::try_from(result))
@@ -82,26 +104,26 @@ fn with_delta(init: usize, delta: isize, multiplier: isize) -> Option<usize> {
 }
 
 /// For each tree in the grid, multiply the trees they can see in each direction. Return the best score.
-fn find_best_scenery_score(grid: &Grid) -> usize {
+pub fn find_best_scenery_score(grid: &Grid<u8>) -> usize {
     let deltas: Vec<(isize, isize)> = vec![(-1, 0), (0, -1), (1, 0), (0, 1)];
 
     grid.iter().map(
         |(pos, height)| {
             deltas.iter()
-                  .map(|&delta| count_visible_with_delta(pos, delta, height, grid))
+                  .map(|&delta| count_visible_with_delta(pos, delta, *height, grid))
                   .reduce(|a, b| a * b).unwrap_or(0)
         }
     ).max().unwrap_or(0)
 }
 
 /// For a given tree, count how many trees can be seen in a given direction
-fn count_visible_with_delta((origin_y, origin_x): (usize, usize), (dy, dx): (isize, isize), origin_height: u8, grid: &Grid) -> usize {
+pub fn count_visible_with_delta((origin_y, origin_x): (usize, usize), (dy, dx): (isize, isize), origin_height: u8, grid: &Grid<u8>) -> usize {
     (1..)
         .map(
             |pos| {
                 with_delta(origin_y, dy, pos)
                     .zip(with_delta(origin_x, dx, pos))
-                    .and_then(|(y, x)| grid.get(y, x))
+                    .and_then(|(y, x)| grid.get(y, x).copied())
             })
         .while_some()
         .fold_while(
@@ -113,17 +135,17 @@ fn count_visible_with_delta((origin_y, origin_x): (usize, usize), (dy, dx): (isi
 
 #[cfg(test)]
 mod tests {
-    use crate::day_8::{find_best_scenery_score, find_visible_count};
+    use crate::day_8::{find_best_scenery_score, find_visible_count, parse_input};
     use crate::util::grid::Grid;
 
-    fn sample_grid() -> Grid {
+    fn sample_grid() -> Grid<u8> {
         let input = "30373
 25512
 65332
 33549
-35390".to_string();
+35390";
 
-        Grid::from(input)
+        input.parse().unwrap()
     }
 
     #[test]
@@ -135,4 +157,17 @@ mod tests {
     fn can_find_max_score() {
         assert_eq!(find_best_scenery_score(&sample_grid()), 8);
     }
+
+    #[test]
+    fn can_parse() {
+        assert_eq!(parse_input("30373\n25512\n65332\n33549\n35390"), Ok(sample_grid()));
+
+        let bad_height = parse_input("30373\n255x2").unwrap_err();
+        assert_eq!((bad_height.line, bad_height.column, bad_height.text.as_str()), (2, 4, "x"));
+
+        let ragged = parse_input("30373\n2551").unwrap_err();
+        assert_eq!((ragged.line, ragged.column, ragged.text.as_str()), (2, 1, "2551"));
+
+        assert!(parse_input("").is_err());
+    }
 }
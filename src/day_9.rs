@@ -2,98 +2,85 @@
 //!
 //!
 
-use std::fs;
+use crate::solution::{Answer, Solution};
+use crate::util::parse::{parse_lines, parse_number, ParseError, split_once};
+use crate::util::point::{Direction, Point2};
 use itertools::Itertools;
-use crate::day_9::Direction::*;
-
-/// Represent the directions a motion can be in
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Direction {
-    UP,
-    DOWN,
-    LEFT,
-    RIGHT,
-}
 
 /// An instruction to move the head of the rope in a direction for a distance
-type Motion = (Direction, usize);
-type Position = (isize, isize);
+pub type Motion = (Direction, usize);
 
 /// The entry point for running the solutions with the 'real' puzzle input.
 ///
-/// - The puzzle input is expected to be at `<project_root>/res/day-9-input`
-/// - It is expected this will be called by [`super::main()`] when the user elects to run day 9.
-pub fn run() {
-    let contents =
-        fs::read_to_string("res/day-9-input").expect("Failed to read file");
-
-    let motions = parse_input(&contents);
-
-    println!(
-        "The tail of the rope with one knot passes through {} unique positions",
-        count_tail_positions(&motions, 1)
-    );
-
-    println!(
-        "The tail of the rope with 9 knots passes through {} unique positions",
-        count_tail_positions(&motions, 9)
-    );
+/// - The puzzle input is read by the runner binary, by default from `<project_root>/res/day-9-input`
+/// - It is registered in [`crate::solution::solutions`] so that the runner can run day 9.
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Parsed = Vec<Motion>;
+    const DAY: usize = 9;
+
+    fn parse(&self, input: &str) -> Result<Vec<Motion>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part_one(&self, motions: &Vec<Motion>) -> Answer {
+        count_tail_positions(motions, 1).into()
+    }
+
+    fn part_two(&self, motions: &Vec<Motion>) -> Answer {
+        count_tail_positions(motions, 9).into()
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("The tail of the rope with one knot passes through {} unique positions", answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("The tail of the rope with 9 knots passes through {} unique positions", answer)
+    }
 }
 
 /// Map the input file to the internal representation
-fn parse_input(input: &String) -> Vec<Motion> {
-    input.lines().map(parse_motion).collect()
+pub fn parse_input(input: &str) -> Result<Vec<Motion>, ParseError> {
+    parse_lines(input, parse_motion)
 }
 
 /// Map a line of the input to the internal representation
-fn parse_motion(line: &str) -> Motion {
-    let (letter, number) = line.split_once(" ").unwrap();
+pub fn parse_motion(line: &str) -> Result<Motion, ParseError> {
+    let (letter, number) = split_once(line, line, " ")?;
 
     let direction = match letter {
-        "U" => UP,
-        "D" => DOWN,
-        "L" => LEFT,
-        "R" => RIGHT,
-        _ => unreachable!()
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        _ => return Err(ParseError::new(line, letter, "Expected a direction: U, D, L or R"))
     };
 
-    let distance = number.parse().unwrap();
+    let distance = parse_number(line, number)?;
 
-    (direction, distance)
+    Ok((direction, distance))
 }
 
 /// Map a specification of a move of the head of the rope to the list of positions it follows
-fn apply_motion((x, y): Position, (direction, distance): Motion) -> Vec<Position> {
-    let mut positions = Vec::new();
-    for d in 1..=distance {
-        let d_i = isize::try_from(d).unwrap();
-        positions.push(
-            match direction {
-                UP => (x, y - d_i),
-                DOWN => (x, y + d_i),
-                LEFT => (x - d_i, y),
-                RIGHT => (x + d_i, y),
-            }
-        )
-    }
-
-    positions
+pub fn apply_motion(position: Point2, (direction, distance): Motion) -> Vec<Point2> {
+    (1..=distance)
+        .map(|d| position + direction.delta() * isize::try_from(d).unwrap())
+        .collect()
 }
 
 /// Give a new head position, move the tail so it is still touching
-fn update_tail((head_x, head_y): Position, (tail_x, tail_y): Position) -> Position {
-    if (head_x - tail_x).abs() <= 1 && (head_y - tail_y).abs() <= 1 {
-        (tail_x, tail_y)
+pub fn update_tail(head: Point2, tail: Point2) -> Point2 {
+    if head.chebyshev_distance(&tail) <= 1 {
+        tail
     } else {
-        (
-            if tail_x < head_x { tail_x + 1 } else if tail_x > head_x { tail_x - 1 } else { tail_x },
-            if tail_y < head_y { tail_y + 1 } else if tail_y > head_y { tail_y - 1 } else { tail_y },
-        )
+        tail + (head - tail).signum()
     }
 }
 
 /// map a list of motions specifications to the list of positions it follows
-fn apply_motions(origin: Position, motions: &Vec<Motion>) -> Vec<Position> {
+pub fn apply_motions(origin: Point2, motions: &Vec<Motion>) -> Vec<Point2> {
     let mut positions = Vec::new();
     positions.push(origin);
 
@@ -107,7 +94,7 @@ fn apply_motions(origin: Position, motions: &Vec<Motion>) -> Vec<Position> {
 }
 
 // Map the positions the previous section of a rope follows to the positions the next section follows
-fn follow_head(origin: Position, head_positions: Vec<Position>) -> Vec<Position> {
+pub fn follow_head(origin: Point2, head_positions: Vec<Point2>) -> Vec<Point2> {
     let mut tail_positions = Vec::new();
     tail_positions.push(origin);
 
@@ -124,30 +111,35 @@ fn follow_head(origin: Position, head_positions: Vec<Position>) -> Vec<Position>
 }
 
 /// Map the movement specification to the tail movement of an arbitrary length of rope.
-fn count_tail_positions(head_motions: &Vec<Motion>, rope_length: usize) -> usize {
+pub fn count_tail_positions(head_motions: &Vec<Motion>, rope_length: usize) -> usize {
     (0..rope_length)
         .fold(
-            apply_motions((0, 0), head_motions),
-            |previous_knot, _| follow_head((0, 0), previous_knot),
+            apply_motions(Point2::ORIGIN, head_motions),
+            |previous_knot, _| follow_head(Point2::ORIGIN, previous_knot),
         )
         .iter().unique().count()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day_9::Direction::*;
     use crate::day_9::{apply_motion, apply_motions, count_tail_positions, follow_head, Motion, parse_input, update_tail};
+    use crate::util::point::Direction::{Down, Left, Right, Up};
+    use crate::util::point::Point2;
+
+    fn points(positions: &[(isize, isize)]) -> Vec<Point2> {
+        positions.iter().map(|&position| Point2::from(position)).collect()
+    }
 
     fn sample_motions() -> Vec<Motion> {
         vec![
-            (RIGHT, 4),
-            (UP, 4),
-            (LEFT, 3),
-            (DOWN, 1),
-            (RIGHT, 4),
-            (DOWN, 1),
-            (LEFT, 5),
-            (RIGHT, 2),
+            (Right, 4),
+            (Up, 4),
+            (Left, 3),
+            (Down, 1),
+            (Right, 4),
+            (Down, 1),
+            (Left, 5),
+            (Right, 2),
         ]
     }
 
@@ -162,48 +154,48 @@ D 1
 L 5
 R 2".to_string();
 
-        assert_eq!(parse_input(&input), sample_motions());
+        assert_eq!(parse_input(&input), Ok(sample_motions()));
     }
 
     #[test]
     fn can_move_head() {
         assert_eq!(
-            apply_motion((0, 0), (RIGHT, 4)),
-            vec![(1, 0), (2, 0), (3, 0), (4, 0)]
+            apply_motion(Point2::ORIGIN, (Right, 4)),
+            points(&[(1, 0), (2, 0), (3, 0), (4, 0)])
         );
 
         assert_eq!(
-            apply_motion((4, 0), (UP, 2)),
-            vec![(4, -1), (4, -2)]
+            apply_motion(Point2::new(4, 0), (Up, 2)),
+            points(&[(4, -1), (4, -2)])
         )
     }
 
     #[test]
     fn can_apply_motions() {
         assert_eq!(
-            apply_motions((0, 0), &vec![(RIGHT, 4), (UP, 2)]),
-            vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, -1), (4, -2)]
+            apply_motions(Point2::ORIGIN, &vec![(Right, 4), (Up, 2)]),
+            points(&[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, -1), (4, -2)])
         );
     }
 
     #[test]
     fn can_update_tail_for_head() {
-        assert_eq!(update_tail((0, 0), (0, 0)), (0, 0));
-        assert_eq!(update_tail((0, 1), (0, 0)), (0, 0));
-        assert_eq!(update_tail((1, 1), (0, 0)), (0, 0));
-        assert_eq!(update_tail((2, 2), (0, 0)), (1, 1));
-        assert_eq!(update_tail((-2, 0), (0, 0)), (-1, 0));
-        assert_eq!(update_tail((-2, 1), (0, 0)), (-1, 1));
+        assert_eq!(update_tail(Point2::new(0, 0), Point2::new(0, 0)), Point2::new(0, 0));
+        assert_eq!(update_tail(Point2::new(0, 1), Point2::new(0, 0)), Point2::new(0, 0));
+        assert_eq!(update_tail(Point2::new(1, 1), Point2::new(0, 0)), Point2::new(0, 0));
+        assert_eq!(update_tail(Point2::new(2, 2), Point2::new(0, 0)), Point2::new(1, 1));
+        assert_eq!(update_tail(Point2::new(-2, 0), Point2::new(0, 0)), Point2::new(-1, 0));
+        assert_eq!(update_tail(Point2::new(-2, 1), Point2::new(0, 0)), Point2::new(-1, 1));
     }
 
     #[test]
     fn can_follow_head() {
         assert_eq!(
             follow_head(
-                (0, 0),
-                vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, -1), (4, -2)],
+                Point2::ORIGIN,
+                points(&[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (4, -1), (4, -2)]),
             ),
-            vec![(0, 0), (0, 0), (0, 0), (1, 0), (2, 0), (3, 0), (3, 0), (4, -1)]
+            points(&[(0, 0), (0, 0), (0, 0), (1, 0), (2, 0), (3, 0), (3, 0), (4, -1)])
         )
     }
 
@@ -220,8 +212,17 @@ R 17
 D 10
 L 25
 U 20".to_string();
-        let larger_example = parse_input(&larger_input);
+        let larger_example = parse_input(&larger_input).unwrap();
         assert_eq!(count_tail_positions(&larger_example, 9), 36);
 
     }
+
+    #[test]
+    fn reports_invalid_motions() {
+        let bad_direction = parse_input("R 4\nX 4").unwrap_err();
+        assert_eq!((bad_direction.line, bad_direction.column, bad_direction.text.as_str()), (2, 1, "X"));
+
+        let bad_distance = parse_input("R 4\nU 4\nL -3").unwrap_err();
+        assert_eq!((bad_distance.line, bad_distance.column, bad_distance.text.as_str()), (3, 3, "-3"));
+    }
 }
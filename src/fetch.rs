@@ -0,0 +1,243 @@
+//! Downloads puzzle inputs, keeping a local cache so each input is only fetched once.
+//!
+//! The fetcher is configured from environment variables:
+//!
+//! - `AOC_SESSION`, or a file named by `AOC_SESSION_FILE`, holds the session token from the Advent of Code site's
+//!   `session` cookie. Without a token only the cache is used.
+//! - `AOC_BASE_URL` overrides where inputs are fetched from, e.g. a local stub server. Inputs are fetched from
+//!   `<base url>/day/<day>/input`, and the default base URL is `https://adventofcode.com/2022`.
+//! - `AOC_CACHE_DIR` overrides where fetched inputs are cached, by default `.cache/`.
+//!
+//! There is no HTTP client dependency, so `http://` URLs are fetched with a minimal HTTP/1.0 request, and `https://`
+//! URLs are fetched by running `curl`.
+
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use crate::input::input_file_name;
+
+/// The environment variable holding the session token
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// The environment variable naming a file that holds the session token
+pub const SESSION_FILE_VAR: &str = "AOC_SESSION_FILE";
+/// The environment variable overriding the URL inputs are fetched from
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+/// The environment variable overriding the cache directory
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2022";
+const DEFAULT_CACHE_DIR: &str = ".cache";
+
+/// Advent of Code asks that automated requests identify where they come from
+const USER_AGENT: &str = "github.com/kamioftea/advent-of-code-2022";
+
+/// Provides puzzle inputs from the cache, fetching them on a miss
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Fetcher {
+    /// Inputs are fetched from `<base_url>/day/<day>/input`
+    pub base_url: String,
+    /// Where fetched inputs are stored
+    pub cache_dir: PathBuf,
+    /// The session token, inputs can only be fetched when this is set
+    pub session: Option<String>,
+}
+
+impl Fetcher {
+    /// Configure the fetcher from the environment, see the [module docs](self)
+    pub fn from_env() -> Result<Fetcher, String> {
+        let session = match (env::var(SESSION_VAR), env::var_os(SESSION_FILE_VAR)) {
+            (Ok(session), _) => Some(session),
+            (Err(_), Some(path)) => {
+                let path = Path::new(&path);
+                let token = fs::read_to_string(path)
+                    .map_err(|err| format!("Failed to read session token from {}: {}", path.display(), err))?;
+                Some(token)
+            }
+            (Err(_), None) => None,
+        };
+
+        Ok(Fetcher {
+            base_url: env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            cache_dir: env::var_os(CACHE_DIR_VAR).map(PathBuf::from).unwrap_or_else(|| DEFAULT_CACHE_DIR.into()),
+            session: session.map(|session| session.trim().to_string()).filter(|session| !session.is_empty()),
+        })
+    }
+
+    /// Find the cached input for a day, fetching and caching it if it's missing.
+    ///
+    /// Returns `Ok(None)` if the input isn't cached and can't be fetched, i.e. there is no session token, or it is a
+    /// named input. Named inputs belong to other accounts, so they are only ever read from the cache.
+    pub fn provide(&self, day: usize, name: Option<&str>) -> Result<Option<PathBuf>, String> {
+        let path = self.cache_dir.join(input_file_name(day, name));
+        if path.exists() {
+            return Ok(Some(path));
+        }
+
+        let Some(session) = self.session.as_deref().filter(|_| name.is_none()) else {
+            return Ok(None);
+        };
+
+        let url = format!("{}/day/{}/input", self.base_url.trim_end_matches('/'), day);
+        let contents = http_get(&url, session)?;
+
+        fs::create_dir_all(&self.cache_dir)
+            .and_then(|_| fs::write(&path, contents))
+            .map_err(|err| format!("Failed to cache input in {}: {}", path.display(), err))?;
+
+        Ok(Some(path))
+    }
+}
+
+/// Fetch a URL with the session cookie set, returning the body of a successful response
+fn http_get(url: &str, session: &str) -> Result<String, String> {
+    if let Some(address) = url.strip_prefix("http://") {
+        plain_http_get(url, address, session)
+    } else if url.starts_with("https://") {
+        curl_get(url, session)
+    } else {
+        Err(format!("Unsupported URL '{}', expected http:// or https://", url))
+    }
+}
+
+/// Make a HTTP/1.0 request, so the response isn't chunked and the body runs until the connection closes
+fn plain_http_get(url: &str, address: &str, session: &str) -> Result<String, String> {
+    let (authority, path) = address.find('/').map(|pos| address.split_at(pos)).unwrap_or((address, "/"));
+    let socket_address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    let failed = |err: std::io::Error| format!("Failed to fetch {}: {}", url, err);
+
+    let mut stream = TcpStream::connect(&socket_address).map_err(failed)?;
+    stream.set_read_timeout(Some(Duration::from_secs(30))).map_err(failed)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\n\r\n",
+        path,
+        authority,
+        USER_AGENT,
+        session,
+    ).map_err(failed)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(failed)?;
+
+    let (head, body) = response.split_once("\r\n\r\n")
+                               .ok_or_else(|| format!("Failed to fetch {}: malformed response", url))?;
+
+    match head.lines().next().and_then(|status_line| status_line.split_whitespace().nth(1)) {
+        Some("200") => Ok(body.to_string()),
+        Some(status) => Err(format!("Failed to fetch {}: HTTP status {}", url, status)),
+        None => Err(format!("Failed to fetch {}: malformed response", url)),
+    }
+}
+
+/// Use `curl` for HTTPS. The cookie is passed on stdin so the token isn't visible in the process list.
+fn curl_get(url: &str, session: &str) -> Result<String, String> {
+    let failed = |err: std::io::Error| format!("Failed to run curl to fetch {}: {}", url, err);
+
+    let mut curl =
+        Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--user-agent", USER_AGENT, "--header", "@-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(failed)?;
+
+    if let Some(mut stdin) = curl.stdin.take() {
+        writeln!(stdin, "Cookie: session={}", session).map_err(failed)?;
+    }
+
+    let output = curl.wait_with_output().map_err(failed)?;
+    if !output.status.success() {
+        return Err(format!("Failed to fetch {}: {}", url, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| format!("Failed to fetch {}: the input wasn't UTF-8", url))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::thread;
+    use crate::fetch::Fetcher;
+
+    /// Serve a single request with a canned response, returning the base URL and a handle that yields the request
+    fn stub_server(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2022", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (base_url, handle)
+    }
+
+    fn temp_cache(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-2022-fetch-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn fetches_and_caches_inputs() {
+        let (base_url, server) = stub_server("HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n1000\n2000");
+        let cache_dir = temp_cache("hit");
+        let fetcher = Fetcher { base_url, cache_dir: cache_dir.clone(), session: Some("abc123".to_string()) };
+
+        let path = fetcher.provide(1, None).unwrap().unwrap();
+        assert_eq!(path, cache_dir.join("day-1-input"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2022/day/1/input HTTP/1.0\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc123\r\n"));
+
+        // The server has stopped, so this must come from the cache
+        assert_eq!(fetcher.provide(1, None), Ok(Some(path)));
+
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn reports_failed_fetches() {
+        let (base_url, server) = stub_server("HTTP/1.1 404 Not Found\r\n\r\nPlease don't repeatedly request this");
+        let cache_dir = temp_cache("miss");
+        let fetcher = Fetcher { base_url, cache_dir: cache_dir.clone(), session: Some("abc123".to_string()) };
+
+        let error = fetcher.provide(26, None).unwrap_err();
+        assert!(error.ends_with("HTTP status 404"), "{}", error);
+        assert!(!cache_dir.join("day-26-input").exists());
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn only_fetches_with_a_session_for_unnamed_inputs() {
+        let cache_dir = temp_cache("offline");
+        let offline = Fetcher { base_url: "http://127.0.0.1:1".to_string(), cache_dir, session: None };
+        assert_eq!(offline.provide(1, None), Ok(None));
+
+        let named = Fetcher { session: Some("abc123".to_string()), ..offline };
+        assert_eq!(named.provide(1, Some("alice")), Ok(None));
+
+        let unsupported = Fetcher { base_url: "ftp://example.com".to_string(), ..named };
+        assert!(unsupported.provide(1, None).is_err());
+    }
+}
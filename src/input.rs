@@ -0,0 +1,142 @@
+//! Works out where each day's puzzle input should be read from.
+//!
+//! In order of preference an input is read from:
+//!
+//! 1. The path given with `--input`, or stdin if that path is `-`
+//! 2. The directory named by the `AOC_INPUT_DIR` environment variable, if the input file exists there
+//! 3. The [`Fetcher`]'s cache, downloading the input if it's missing and there is a session token
+//! 4. The default `res/` directory
+//!
+//! Named inputs allow more than one input per day, e.g. `--name alice` reads `day-7-input.alice`.
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use crate::fetch::Fetcher;
+
+/// The environment variable that can point to a directory of puzzle inputs
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The directory inputs are read from when no other source is given
+const DEFAULT_INPUT_DIR: &str = "res";
+
+/// Where a day's puzzle input is read from
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Input {
+    /// Read the input from a file
+    File(PathBuf),
+    /// Read the input from stdin, requested with `--input -`
+    Stdin,
+}
+
+impl Input {
+    /// Pick the input for a day, see the [module docs](self) for the order sources are tried in. If fetching the input
+    /// fails, the error is reported on stderr and the next source is used.
+    pub fn resolve(
+        day: usize,
+        explicit: Option<&Path>,
+        name: Option<&str>,
+        input_dir: Option<&Path>,
+        fetcher: Option<&Fetcher>,
+    ) -> Input {
+        if let Some(path) = explicit {
+            return if path == Path::new("-") { Input::Stdin } else { Input::File(path.to_path_buf()) };
+        }
+
+        let file_name = input_file_name(day, name);
+
+        if let Some(path) = input_dir.map(|dir| dir.join(&file_name)).filter(|path| path.exists()) {
+            return Input::File(path);
+        }
+
+        match fetcher.map(|fetcher| fetcher.provide(day, name)) {
+            Some(Ok(Some(path))) => return Input::File(path),
+            Some(Err(message)) => eprintln!("{}", message),
+            _ => {}
+        }
+
+        Input::File(Path::new(DEFAULT_INPUT_DIR).join(&file_name))
+    }
+
+    /// Read the whole input
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Input::File(path) => fs::read_to_string(path),
+            Input::Stdin => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                Ok(contents)
+            }
+        }
+    }
+
+    /// The file the input is read from, if it isn't stdin
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Input::File(path) => Some(path),
+            Input::Stdin => None,
+        }
+    }
+}
+
+impl Display for Input {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::File(path) => write!(f, "{}", path.display()),
+            Input::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// `day-7-input`, or `day-7-input.alice` for a named input
+pub fn input_file_name(day: usize, name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("day-{}-input.{}", day, name),
+        None => format!("day-{}-input", day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use crate::input::Input;
+
+    #[test]
+    fn prefers_explicit_input() {
+        assert_eq!(
+            Input::resolve(7, Some(Path::new("other/input.txt")), Some("alice"), None, None),
+            Input::File(PathBuf::from("other/input.txt"))
+        );
+        assert_eq!(Input::resolve(7, Some(Path::new("-")), None, None, None), Input::Stdin);
+    }
+
+    #[test]
+    fn defaults_to_res() {
+        assert_eq!(Input::resolve(7, None, None, None, None), Input::File(PathBuf::from("res/day-7-input")));
+        assert_eq!(Input::resolve(7, None, Some("bob"), None, None), Input::File(PathBuf::from("res/day-7-input.bob")));
+    }
+
+    #[test]
+    fn uses_input_dir_when_the_file_exists() {
+        let dir = env::temp_dir().join(format!("aoc-2022-input-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day-7-input.alice"), "$ cd /").unwrap();
+
+        assert_eq!(
+            Input::resolve(7, None, Some("alice"), Some(&dir), None),
+            Input::File(dir.join("day-7-input.alice"))
+        );
+        assert_eq!(Input::resolve(7, None, None, Some(&dir), None), Input::File(PathBuf::from("res/day-7-input")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn can_display_inputs() {
+        assert_eq!(Input::File(PathBuf::from("res/day-1-input")).to_string(), "res/day-1-input");
+        assert_eq!(Input::Stdin.to_string(), "stdin");
+    }
+}
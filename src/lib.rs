@@ -0,0 +1,42 @@
+//! My solutions for [Advent of Code 2022](https://adventofcode.com/2022).
+//!
+//! Each day's parser, solver functions and domain types are public in its `day_N` module, so they can be reused on
+//! their own, e.g. [`day_21::determine_value_to_shout`]. Each day also implements [`solution::Solution`], which is
+//! what the runner binary in `main.rs` uses, along with the supporting modules for the command line, inputs, recorded
+//! answers, fetching inputs, reports, and benchmarks.
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+pub mod day_23;
+pub mod day_24;
+pub mod day_25;
+pub mod fetch;
+pub mod input;
+pub mod report;
+pub mod runner;
+pub mod solution;
+pub mod util;
+
@@ -1,84 +1,212 @@
-mod day_1;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-mod day_20;
-mod day_21;
-mod day_22;
-mod day_23;
-mod day_24;
-mod day_25;
-mod util;
+//! The runner for the solutions in the [`advent_of_code_2022`] library.
+//!
+//! Parses the command-line arguments, or prompts for a day to run, then solves, checks, reports on or benchmarks each
+//! of the selected days.
 
+use std::{env, fs, process, thread};
 use std::io::{self, Write};
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use advent_of_code_2022::{bench, cli, day_2, report, runner, solution};
+use advent_of_code_2022::bench::Baseline;
+use advent_of_code_2022::cli::{Bench, Mode, Options, Part};
+use advent_of_code_2022::day_2::{Day2, Game};
+use advent_of_code_2022::fetch::Fetcher;
+use advent_of_code_2022::input::{Input, INPUT_DIR_VAR};
+use advent_of_code_2022::runner::DayRun;
+use advent_of_code_2022::solution::Puzzle;
 
-extern crate core;
-
-extern crate itertools;
 #[macro_use]
 extern crate text_io;
 
 fn main() {
-    print!("Which day? (0 to run all): ");
-    io::stdout().flush().unwrap();
+    let options = match cli::parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => prompt_for_options(),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(if message == cli::USAGE { 0 } else { 2 })
+        }
+    };
+
+    let mut solutions = solution::solutions();
+    if let Some(path) = env::var_os(day_2::RULES_VAR) {
+        let path = Path::new(&path);
+        // Answers with these rules are recorded under the name of the rules file, e.g. `rpsls` for `rpsls.rules`
+        let name = path.file_stem().map_or("custom".into(), |stem| stem.to_string_lossy());
+
+        match Game::read(path) {
+            Ok(game) => replace_solution(&mut solutions, Box::new(Day2::with_rules(&name, game))),
+            Err(err) => {
+                eprintln!("Failed to load the day 2 rules: {}", err);
+                process::exit(2)
+            }
+        }
+    }
+
+    let input_dir = env::var_os(INPUT_DIR_VAR).map(PathBuf::from);
+    let fetcher = Fetcher::from_env().map(Some).unwrap_or_else(|message| {
+        eprintln!("{}, inputs won't be fetched", message);
+        None
+    });
+
+    // When the report is written to stdout it replaces the usual output
+    let quiet = options.report.is_some() && options.report_file.is_none();
+
+    let jobs = match options.jobs {
+        0 => thread::available_parallelism().map(|jobs| jobs.get()).unwrap_or(1),
+        jobs => jobs,
+    };
+
+    let mut days: Vec<(&dyn Puzzle, Input)> = Vec::new();
+    for &day in &options.days {
+        let Some(solution) = solutions.iter().find(|solution| solution.day() == day) else {
+            if !quiet {
+                println!("No solution for Day {}", day);
+            }
+            continue;
+        };
+
+        let input = Input::resolve(
+            day,
+            options.input.as_deref(),
+            options.name.as_deref(),
+            input_dir.as_deref(),
+            fetcher.as_ref(),
+        );
+        days.push((solution.as_ref(), input));
+    }
 
-    let day: i32 = read!();
-    let days: Vec<Box<dyn Fn() -> ()>> = vec![
-        Box::new(|| day_1::run()),
-        Box::new(|| day_2::run()),
-        Box::new(|| day_3::run()),
-        Box::new(|| day_4::run()),
-        Box::new(|| day_5::run()),
-        Box::new(|| day_6::run()),
-        Box::new(|| day_7::run()),
-        Box::new(|| day_8::run()),
-        Box::new(|| day_9::run()),
-        Box::new(|| day_10::run()),
-        Box::new(|| day_11::run()),
-        Box::new(|| day_12::run()),
-        Box::new(|| day_13::run()),
-        Box::new(|| day_14::run()),
-        Box::new(|| day_15::run()),
-        Box::new(|| day_16::run()),
-        Box::new(|| day_17::run()),
-        Box::new(|| day_18::run()),
-        Box::new(|| day_19::run()),
-        Box::new(|| day_20::run()),
-        Box::new(|| day_21::run()),
-        Box::new(|| day_22::run()),
-        Box::new(|| day_23::run()),
-        Box::new(|| day_24::run()),
-        Box::new(|| day_25::run()),
-    ];
+    if let Some(bench) = &options.bench {
+        process::exit(run_benchmarks(&days, options.part, bench));
+    }
 
     let start = Instant::now();
-    match days.get((day - 1) as usize) {
-        Some(solution) => solution(),
-        None if day == 0 => days.iter().enumerate().for_each(|(i, solution)| {
-            let start = Instant::now();
-            println!("==== Day {} ====", i + 1);
-            solution();
-            println!("-- took {:.2?}", start.elapsed());
-        }),
-        None => println!("Invalid Day {}", day),
+    let run_all = options.days.len() > 1;
+    let mut runs = Vec::new();
+    runner::run_days(&days, options.part, options.mode, jobs, |run| {
+        if !quiet {
+            print_run(&run, run_all);
+        }
+
+        runs.push(run);
+    });
+    let wall_time = start.elapsed();
+
+    if let Some(format) = options.report {
+        let rendered = report::render(&runs, format);
+        match &options.report_file {
+            Some(path) => {
+                if let Err(err) = fs::write(path, rendered) {
+                    eprintln!("Failed to write report to {}: {}", path.display(), err);
+                    process::exit(1);
+                }
+            }
+            None => print!("{}", rendered),
+        }
+    }
+
+    let failures: usize = runs.iter().map(|run| run.failures).sum();
+
+    if !quiet {
+        println!();
+        // Summing the time each day took on its thread gives the CPU time, to compare with the wall-clock time
+        let cpu_time: Duration = runs.iter().map(|run| run.took).sum();
+        println!("Finished in {:.2?}, summed CPU time {:.2?} on {} thread(s)", wall_time, cpu_time, jobs);
     }
 
-    println!();
-    println!("Finished in {:.2?}", start.elapsed());
+    if options.mode != Mode::Solve && failures > 0 {
+        eprintln!("{} check(s) failed", failures);
+        process::exit(1);
+    }
+}
+
+/// Swap the registered solution for the same day, e.g. to run a day with other rules
+fn replace_solution(solutions: &mut [Box<dyn Puzzle>], replacement: Box<dyn Puzzle>) {
+    if let Some(solution) = solutions.iter_mut().find(|solution| solution.day() == replacement.day()) {
+        *solution = replacement;
+    }
+}
+
+/// Print the outcome of a day, with a header and timing when more than one day is being run
+fn print_run(run: &DayRun, run_all: bool) {
+    if run_all {
+        println!("==== Day {} ====", run.day);
+    }
+
+    for line in run.lines() {
+        println!("{}", line);
+    }
+
+    if run_all {
+        println!("-- took {:.2?}", run.took);
+    }
+}
+
+/// Benchmark each day, then save or compare with a baseline. Returns the exit code, which is 1 if any stage has
+/// regressed.
+fn run_benchmarks(days: &[(&dyn Puzzle, Input)], part: Part, options: &Bench) -> i32 {
+    let baseline = match options.baseline.as_deref().map(Baseline::load).transpose() {
+        Ok(baseline) => baseline,
+        Err(err) => {
+            eprintln!("Failed to load baseline: {}", err);
+            return 2;
+        }
+    };
+
+    let mut benches = Vec::new();
+    let mut regressions = 0;
+    for (solution, input) in days {
+        println!("==== Day {} ====", solution.day());
+
+        let result =
+            input.read()
+                 .map_err(|err| format!("Failed to read {}: {}", input, err))
+                 .and_then(|contents| bench::bench_day(*solution, &contents, part, options.warmup, options.runs));
+
+        match result {
+            Ok(day_bench) => {
+                let (lines, day_regressions) = bench::describe(&day_bench, baseline.as_ref(), options.threshold);
+                for line in lines {
+                    println!("{}", line);
+                }
+
+                regressions += day_regressions;
+                benches.push(day_bench);
+            }
+            Err(message) => println!("{}", message),
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        match Baseline::from_benches(&benches).save(path) {
+            Ok(()) => println!("Saved baseline to {}", path.display()),
+            Err(err) => {
+                eprintln!("Failed to save baseline to {}: {}", path.display(), err);
+                return 2;
+            }
+        }
+    }
+
+    if regressions > 0 {
+        eprintln!("{} stage(s) are more than {}% slower than the baseline", regressions, options.threshold);
+        return 1;
+    }
+
+    0
+}
+
+/// The interactive fallback when no arguments are provided
+fn prompt_for_options() -> Options {
+    print!("Which day? (0 to run all): ");
+    io::stdout().flush().unwrap();
+
+    let day: usize = read!();
+    match Options::for_prompted_day(day) {
+        Ok(options) => options,
+        Err(message) => {
+            println!("{}", message);
+            process::exit(2)
+        }
+    }
 }
@@ -0,0 +1,288 @@
+//! A machine-readable report of a run, with one record per day and part.
+//!
+//! Each record holds the day, part, answer, the time taken to parse the input and to solve the part, and a status:
+//!
+//! - `SOLVED` when the answer wasn't compared with a recorded answer
+//! - `PASS`, `FAIL` or `NEW` when checking, see [`crate::answers::Status`]
+//! - `ERROR` when the day couldn't be solved, e.g. its input couldn't be read or parsed, or the input doesn't suit that
+//!   part. The answer is empty.
+//!
+//! Times are in whole microseconds.
+
+use std::fmt::Write;
+use crate::answers::Status;
+use crate::runner::DayRun;
+use crate::solution::Answer;
+
+/// The formats a report can be written in
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Format {
+    /// An array of objects
+    Json,
+    /// A header row, then a row per record
+    Csv,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            other => Err(format!("Invalid report format '{}', expected json or csv", other)),
+        }
+    }
+}
+
+/// A row of the report
+#[derive(Eq, PartialEq, Debug, Clone)]
+struct Record {
+    day: usize,
+    part: usize,
+    answer: Option<String>,
+    parse_time: u128,
+    solve_time: u128,
+    status: &'static str,
+}
+
+/// Flatten the runs into one record per day and part
+fn records(runs: &[DayRun]) -> Vec<Record> {
+    let mut records = Vec::new();
+
+    for run in runs {
+        match &run.parts {
+            Ok(parts) => records.extend(parts.iter().map(|part| Record {
+                day: run.day,
+                part: part.part,
+                answer: match part.answer {
+                    Answer::Error(_) => None,
+                    _ => Some(part.answer.to_string()),
+                },
+                parse_time: run.parse_time.as_micros(),
+                solve_time: part.solve_time.as_micros(),
+                status: match (&part.answer, &part.status) {
+                    (Answer::Error(_), _) => "ERROR",
+                    (_, None) => "SOLVED",
+                    (_, Some(Status::Pass)) => "PASS",
+                    (_, Some(Status::Fail(_))) => "FAIL",
+                    (_, Some(Status::New)) => "NEW",
+                },
+            })),
+            Err(_) => {
+                let requested =
+                    [(1, run.requested.includes_one()), (2, run.requested.includes_two())]
+                        .into_iter()
+                        .filter(|&(_, included)| included);
+
+                records.extend(requested.map(|(part, _)| Record {
+                    day: run.day,
+                    part,
+                    answer: None,
+                    parse_time: run.parse_time.as_micros(),
+                    solve_time: 0,
+                    status: "ERROR",
+                }))
+            }
+        }
+    }
+
+    records
+}
+
+/// Render the report for a set of runs
+pub fn render(runs: &[DayRun], format: Format) -> String {
+    let records = records(runs);
+
+    match format {
+        Format::Json => render_json(&records),
+        Format::Csv => render_csv(&records),
+    }
+}
+
+fn render_json(records: &[Record]) -> String {
+    let mut output = "[\n".to_string();
+
+    for (i, record) in records.iter().enumerate() {
+        let answer = record.answer.as_deref().map(json_string).unwrap_or_else(|| "null".to_string());
+        let separator = if i + 1 < records.len() { "," } else { "" };
+
+        writeln!(
+            output,
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"parse_time_us\": {}, \"solve_time_us\": {}, \"status\": {}}}{}",
+            record.day,
+            record.part,
+            answer,
+            record.parse_time,
+            record.solve_time,
+            json_string(record.status),
+            separator,
+        ).unwrap();
+    }
+
+    output.push_str("]\n");
+    output
+}
+
+fn render_csv(records: &[Record]) -> String {
+    let mut output = "day,part,answer,parse_time_us,solve_time_us,status\n".to_string();
+
+    for record in records {
+        writeln!(
+            output,
+            "{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            record.answer.as_deref().map(csv_field).unwrap_or_default(),
+            record.parse_time,
+            record.solve_time,
+            record.status,
+        ).unwrap();
+    }
+
+    output
+}
+
+/// Quote and escape a string for JSON
+fn json_string(value: &str) -> String {
+    let mut quoted = "\"".to_string();
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use crate::answers::Status;
+    use crate::cli::Part;
+    use crate::report::{csv_field, Format, json_string, render};
+    use crate::runner::{DayRun, PartRun};
+    use crate::solution::Answer;
+    use crate::util::parse::ParseError;
+
+    fn sample_runs() -> Vec<DayRun> {
+        let part = |part: usize, answer: Answer, status: Option<Status>| PartRun {
+            part,
+            answer,
+            description: String::new(),
+            solve_time: Duration::from_micros(20 * part as u64),
+            status,
+        };
+
+        vec![
+            DayRun {
+                day: 1,
+                requested: Part::Both,
+                parse_time: Duration::from_micros(150),
+                parts: Ok(vec![
+                    part(1, Answer::from(24000), Some(Status::Pass)),
+                    part(2, Answer::from(45000), Some(Status::Fail("45001".to_string()))),
+                ]),
+                notes: Vec::new(),
+                failures: 1,
+                took: Duration::from_micros(300),
+            },
+            DayRun {
+                day: 3,
+                requested: Part::Both,
+                parse_time: Duration::from_micros(8),
+                parts: Ok(vec![
+                    part(1, Answer::from(3), None),
+                    part(2, Answer::Error(ParseError::at(2, 1, "bb", "Expected groups of 3")), None),
+                ]),
+                notes: Vec::new(),
+                failures: 0,
+                took: Duration::from_micros(50),
+            },
+            DayRun {
+                day: 10,
+                requested: Part::Two,
+                parse_time: Duration::from_micros(5),
+                parts: Ok(vec![part(2, Answer::from("\n#..\n.#,"), None)]),
+                notes: Vec::new(),
+                failures: 0,
+                took: Duration::from_micros(100),
+            },
+            DayRun {
+                day: 11,
+                requested: Part::Both,
+                parse_time: Duration::ZERO,
+                parts: Err("Failed to read res/day-11-input".to_string()),
+                notes: Vec::new(),
+                failures: 0,
+                took: Duration::from_micros(10),
+            },
+        ]
+    }
+
+    #[test]
+    fn can_render_json() {
+        assert_eq!(
+            render(&sample_runs(), Format::Json),
+            r#"[
+  {"day": 1, "part": 1, "answer": "24000", "parse_time_us": 150, "solve_time_us": 20, "status": "PASS"},
+  {"day": 1, "part": 2, "answer": "45000", "parse_time_us": 150, "solve_time_us": 40, "status": "FAIL"},
+  {"day": 3, "part": 1, "answer": "3", "parse_time_us": 8, "solve_time_us": 20, "status": "SOLVED"},
+  {"day": 3, "part": 2, "answer": null, "parse_time_us": 8, "solve_time_us": 40, "status": "ERROR"},
+  {"day": 10, "part": 2, "answer": "\n#..\n.#,", "parse_time_us": 5, "solve_time_us": 40, "status": "SOLVED"},
+  {"day": 11, "part": 1, "answer": null, "parse_time_us": 0, "solve_time_us": 0, "status": "ERROR"},
+  {"day": 11, "part": 2, "answer": null, "parse_time_us": 0, "solve_time_us": 0, "status": "ERROR"}
+]
+"#
+        );
+
+        assert_eq!(render(&[], Format::Json), "[\n]\n");
+    }
+
+    #[test]
+    fn can_render_csv() {
+        assert_eq!(
+            render(&sample_runs(), Format::Csv),
+            "day,part,answer,parse_time_us,solve_time_us,status
+1,1,24000,150,20,PASS
+1,2,45000,150,40,FAIL
+3,1,3,8,20,SOLVED
+3,2,,8,40,ERROR
+10,2,\"\n#..\n.#,\",5,40,SOLVED
+11,1,,0,0,ERROR
+11,2,,0,0,ERROR
+"
+        );
+    }
+
+    #[test]
+    fn can_escape_values() {
+        assert_eq!(json_string("a \"b\"\\\t\u{1}"), "\"a \\\"b\\\"\\\\\\t\\u0001\"");
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn can_parse_formats() {
+        assert_eq!(Format::try_from("json"), Ok(Format::Json));
+        assert_eq!(Format::try_from("csv"), Ok(Format::Csv));
+        assert!(Format::try_from("xml").is_err());
+    }
+}
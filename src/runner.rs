@@ -0,0 +1,369 @@
+//! Runs a single day: reading its input, parsing it, solving the requested parts, and checking or recording the
+//! answers. The outcome is returned as a [`DayRun`] rather than printed, so that the runner binary can decide how to
+//! present it. [`run_days`] runs a list of days, optionally spreading them across several threads.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::answers::{RecordedAnswers, Status};
+use crate::cli::{Mode, Part};
+use crate::input::Input;
+use crate::solution::{Answer, Puzzle};
+
+/// The outcome of solving one part of a day
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct PartRun {
+    /// Which part this is, 1 or 2
+    pub part: usize,
+    /// The answer found
+    pub answer: Answer,
+    /// The answer explained as a sentence, see [`crate::solution::Solution::describe_part_one`]
+    pub description: String,
+    /// How long solving this part took, excluding parsing
+    pub solve_time: Duration,
+    /// How the answer compares to the recorded answer, only when checking
+    pub status: Option<Status>,
+}
+
+/// The outcome of running one day
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct DayRun {
+    /// The day that was run
+    pub day: usize,
+    /// The parts that were requested
+    pub requested: Part,
+    /// How long parsing the input took
+    pub parse_time: Duration,
+    /// The solved parts, or why the day couldn't be solved
+    pub parts: Result<Vec<PartRun>, String>,
+    /// Anything else to report, e.g. where the answers were recorded
+    pub notes: Vec<String>,
+    /// The number of checks that failed, this includes failing to solve the day when checking or recording
+    pub failures: usize,
+    /// How long the whole day took, including reading the input
+    pub took: Duration,
+}
+
+impl DayRun {
+    /// The lines to print for this run, in the format used when running interactively
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        match &self.parts {
+            Ok(parts) => {
+                for part in parts {
+                    lines.push(part.description.clone());
+                }
+
+                for part in parts {
+                    if let Some(status) = &part.status {
+                        lines.push(format!("Part {}: {}", part.part, status));
+                    }
+                }
+            }
+            Err(error) => lines.push(error.clone()),
+        }
+
+        lines.extend(self.notes.iter().cloned());
+
+        lines
+    }
+}
+
+/// Run the requested parts of a day, then check or record the answers depending on the `mode`
+pub fn run_day(solution: &dyn Puzzle, input: &Input, part: Part, mode: Mode) -> DayRun {
+    let start = Instant::now();
+    let mut run = DayRun {
+        day: solution.day(),
+        requested: part,
+        parse_time: Duration::ZERO,
+        parts: Ok(Vec::new()),
+        notes: Vec::new(),
+        failures: 0,
+        took: Duration::ZERO,
+    };
+
+    run.parts = match input.read() {
+        Ok(contents) => solve(solution, &contents, part, &mut run.parse_time),
+        Err(err) => Err(format!("Failed to read {}: {}", input, err)),
+    };
+
+    match (mode, &mut run.parts, input.path()) {
+        (Mode::Solve, _, _) => {}
+        (_, Err(_), _) => run.failures += 1,
+        (_, Ok(_), None) => {
+            run.notes.push(format!("Answers can only be checked or recorded for input files, not {}", input));
+            run.failures += 1;
+        }
+        (Mode::Check, Ok(parts), Some(path)) =>
+            check_answers(path, solution.variant(), parts, &mut run.notes, &mut run.failures),
+        (Mode::Record, Ok(parts), Some(path)) =>
+            record_answers(path, solution.variant(), parts, &mut run.notes, &mut run.failures),
+    }
+
+    run.took = start.elapsed();
+    run
+}
+
+/// Run each day with its input on up to `jobs` threads, see [`run_day`].
+///
+/// The days are taken from the list in order as threads become free, and `on_run` is called with each day's outcome in
+/// the same order as `days`, as soon as it and all the days before it have finished.
+pub fn run_days<F>(days: &[(&dyn Puzzle, Input)], part: Part, mode: Mode, jobs: usize, mut on_run: F)
+    where F: FnMut(DayRun)
+{
+    if jobs <= 1 {
+        for (solution, input) in days {
+            on_run(run_day(*solution, input, part, mode));
+        }
+
+        return;
+    }
+
+    let next_day = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(days.len()) {
+            let sender = sender.clone();
+            let next_day = &next_day;
+
+            scope.spawn(move || {
+                loop {
+                    let index = next_day.fetch_add(1, Ordering::Relaxed);
+                    let Some((solution, input)) = days.get(index) else { break };
+
+                    if sender.send((index, run_day(*solution, input, part, mode))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        // Only the threads' senders should keep the channel open
+        drop(sender);
+
+        // Hold on to days that finish early until the days before them are done
+        let mut finished = BTreeMap::new();
+        let mut next_to_report = 0;
+        for (index, run) in receiver {
+            finished.insert(index, run);
+
+            while let Some(run) = finished.remove(&next_to_report) {
+                on_run(run);
+                next_to_report += 1;
+            }
+        }
+    });
+}
+
+/// Parse the input then solve the selected part(s), timing each step
+fn solve(
+    solution: &dyn Puzzle,
+    contents: &str,
+    part: Part,
+    parse_time: &mut Duration,
+) -> Result<Vec<PartRun>, String> {
+    let parse_start = Instant::now();
+    let parsed = solution.parse(contents).map_err(|err| format!("Failed to parse input: {}", err))?;
+    *parse_time = parse_start.elapsed();
+
+    let mut parts = Vec::new();
+
+    if part.includes_one() {
+        let solve_start = Instant::now();
+        let answer = parsed.part_one();
+        let solve_time = solve_start.elapsed();
+        let description = describe(1, &answer, |answer| parsed.describe_part_one(answer));
+        parts.push(PartRun { part: 1, answer, description, solve_time, status: None });
+    }
+
+    if part.includes_two() && solution.has_part_two() {
+        let solve_start = Instant::now();
+        let answer = parsed.part_two();
+        let solve_time = solve_start.elapsed();
+        let description = describe(2, &answer, |answer| parsed.describe_part_two(answer));
+        parts.push(PartRun { part: 2, answer, description, solve_time, status: None });
+    }
+
+    Ok(parts)
+}
+
+/// Explain an answer as a sentence, or why the part couldn't be solved
+fn describe<F>(part: usize, answer: &Answer, describe_answer: F) -> String
+    where F: Fn(&Answer) -> String
+{
+    match answer {
+        Answer::Error(err) => format!("Failed to solve part {}: {}", part, err),
+        answer => describe_answer(answer),
+    }
+}
+
+/// Compare the answers with those recorded for the input, counting how many have changed
+fn check_answers(
+    input_path: &Path,
+    variant: Option<&str>,
+    parts: &mut [PartRun],
+    notes: &mut Vec<String>,
+    failures: &mut usize,
+) {
+    let recorded = match RecordedAnswers::load(input_path, variant) {
+        Ok(recorded) => recorded,
+        Err(err) => {
+            notes.push(format!("Failed to load answers for {}: {}", input_path.display(), err));
+            *failures += 1;
+            return;
+        }
+    };
+
+    for part in parts {
+        // A part that couldn't be solved has nothing to compare, but still fails the check
+        if let Answer::Error(_) = part.answer {
+            *failures += 1;
+            continue;
+        }
+
+        let status = recorded.check(part.part, &part.answer);
+        if let Status::Fail(_) = status {
+            *failures += 1;
+        }
+
+        part.status = Some(status);
+    }
+}
+
+/// Save the answers next to the input, keeping any recorded for parts that weren't run or couldn't be solved
+fn record_answers(
+    input_path: &Path,
+    variant: Option<&str>,
+    parts: &[PartRun],
+    notes: &mut Vec<String>,
+    failures: &mut usize,
+) {
+    let saved = RecordedAnswers::load(input_path, variant).and_then(|mut recorded| {
+        for part in parts {
+            match part.answer {
+                Answer::Error(_) => *failures += 1,
+                _ => recorded.record(part.part, &part.answer),
+            }
+        }
+
+        recorded.save(input_path, variant)
+    });
+
+    match saved {
+        Ok(path) => notes.push(format!("Recorded answers in {}", path.display())),
+        Err(err) => {
+            notes.push(format!("Failed to record answers for {}: {}", input_path.display(), err));
+            *failures += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use crate::answers::Status;
+    use crate::cli::{Mode, Part};
+    use crate::day_2::{Day2, Game};
+    use crate::day_25::Day25;
+    use crate::day_3::Day3;
+    use crate::input::Input;
+    use crate::runner::{run_day, run_days};
+    use crate::solution::{Answer, Puzzle, solutions};
+
+    #[test]
+    fn can_run_a_day() {
+        let path = std::env::temp_dir().join(format!("aoc-2022-runner-test-{}", std::process::id()));
+        std::fs::write(&path, "1=-0-2\n12111\n2=0=\n21\n2=01\n111\n20012\n112\n1=-1=\n1-12\n12\n1=\n122").unwrap();
+        let input = Input::File(path.clone());
+
+        let run = run_day(&Day25, &input, Part::Both, Mode::Solve);
+        let parts = run.parts.clone().unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].answer, Answer::from("2=-1=0"));
+        assert_eq!(run.lines(), vec!["The number to enter is: 2=-1=0".to_string()]);
+
+        let check = run_day(&Day25, &input, Part::One, Mode::Check);
+        assert_eq!(check.parts.unwrap()[0].status, Some(Status::New));
+        assert_eq!(check.failures, 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn runs_days_in_order_across_threads() {
+        let missing = |day: usize| Input::File(PathBuf::from(format!("does/not/exist/day-{}-input", day)));
+        let solutions = solutions();
+        let days: Vec<(&dyn Puzzle, Input)> =
+            solutions.iter().map(|solution| (solution.as_ref(), missing(solution.day()))).collect();
+
+        for jobs in [1, 4] {
+            let mut order = Vec::new();
+            run_days(&days, Part::Both, Mode::Solve, jobs, |run| order.push(run.day));
+            assert_eq!(order, (1..=25).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn shows_empty_answers_for_days_with_a_second_part() {
+        let path = std::env::temp_dir().join(format!("aoc-2022-runner-empty-{}", std::process::id()));
+        std::fs::write(&path, "A Y\nC X").unwrap();
+        let input = Input::File(path.clone());
+
+        // The guide uses move letters, which aren't outcomes with these rules
+        let rules = "move Rock 1 A X\nmove Paper 2 B Y\nmove Scissors 3 C Z\n\n\
+            beats Rock Scissors\nbeats Paper Rock\nbeats Scissors Paper\n\n\
+            outcome loss 0 L\noutcome draw 3 D\noutcome win 6 W";
+        let day_2 = Day2::with_rules("letters", Game::from_config(rules).unwrap());
+
+        let run = run_day(&day_2, &input, Part::Both, Mode::Solve);
+        assert_eq!(
+            run.lines(),
+            vec![
+                "Following the guide assuming moves, my score would be: 15".to_string(),
+                "The guide can't be read as outcomes with these rules".to_string(),
+            ]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reports_parts_that_cant_be_solved() {
+        let path = std::env::temp_dir().join(format!("aoc-2022-runner-unsolvable-{}", std::process::id()));
+        std::fs::write(&path, "aa\nbb\n").unwrap();
+        let input = Input::File(path.clone());
+
+        let run = run_day(&Day3, &input, Part::Both, Mode::Solve);
+        assert_eq!(
+            run.lines(),
+            vec![
+                "The sum of the mismatched items' priorities is: 3".to_string(),
+                "Failed to solve part 2: Day 3, line 1, column 1: Expected the rucksacks to split into groups of 3, \
+                found 'aa'".to_string(),
+            ]
+        );
+        assert_eq!(run.failures, 0);
+
+        let check = run_day(&Day3, &input, Part::Both, Mode::Check);
+        assert_eq!(check.parts.unwrap()[1].status, None);
+        assert_eq!(check.failures, 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reports_failures() {
+        let missing = Input::File(PathBuf::from("does/not/exist"));
+
+        let solve = run_day(&Day25, &missing, Part::Both, Mode::Solve);
+        assert!(solve.parts.is_err());
+        assert_eq!(solve.failures, 0);
+
+        assert_eq!(run_day(&Day25, &missing, Part::Both, Mode::Check).failures, 1);
+    }
+}
@@ -0,0 +1,275 @@
+//! The common interface implemented by each day's solution.
+//!
+//! Each day implements [`Solution`], with a typed parse step and typed answers. The runner doesn't care what each day
+//! parses its input into, so [`Puzzle`] provides a type-erased view over any [`Solution`], and [`solutions`] lists them
+//! all in day order.
+
+use std::fmt::{Display, Formatter};
+use crate::util::parse::ParseError;
+use crate::{
+    day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9, day_10, day_11, day_12, day_13, day_14, day_15,
+    day_16, day_17, day_18, day_19, day_20, day_21, day_22, day_23, day_24, day_25,
+};
+
+/// The answer to one part of a day's puzzle
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Answer {
+    /// Most puzzles have a numeric answer, `i128` is wide enough to hold any of the integer types used
+    Number(i128),
+    /// Some answers are text, e.g. the crate labels on day 5 or the screen on day 10
+    Text(String),
+    /// There is no answer for this part, e.g. the second part of day 25, or a day 2 guide that can't be read that way
+    Empty,
+    /// The input parsed, but this part can't be solved from it, e.g. day 3's rucksacks don't split into groups
+    Error(ParseError),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Empty => write!(f, "-"),
+            Answer::Error(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Generate the `From` implementations for the integer types the days use for their answers
+macro_rules! answer_from_number {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(number: $number) -> Self {
+                    Answer::Number(number as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u32, u64, usize, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl<T: Into<Answer>> From<Result<T, ParseError>> for Answer {
+    fn from(result: Result<T, ParseError>) -> Self {
+        result.map_or_else(Answer::Error, Into::into)
+    }
+}
+
+impl Answer {
+    /// Tag an error with the day it was found on, as [`Puzzle::parse`] does for errors parsing the input
+    fn for_day(self, day: usize) -> Self {
+        match self {
+            Answer::Error(err) => Answer::Error(err.for_day(day)),
+            answer => answer,
+        }
+    }
+}
+
+/// A day's solution: parse the puzzle input once, then solve both parts from the parsed representation.
+///
+/// Solutions must be [`Sync`] so that several days can be run in parallel.
+pub trait Solution: Sync {
+    /// The representation the puzzle input is parsed into
+    type Parsed: 'static;
+
+    /// The day of the advent calendar this solves
+    const DAY: usize;
+
+    /// Whether the day has a second part, only day 25 doesn't. The runner leaves out part 2 of days without one.
+    const HAS_PART_TWO: bool = true;
+
+    /// A name for the rules this is solving with, when they aren't the puzzle's own. Answers for a variant are
+    /// recorded separately from the puzzle's, see [`crate::answers::answers_path`].
+    fn variant(&self) -> Option<&str> {
+        None
+    }
+
+    /// Turn the puzzle input into the representation used by both parts, or explain why the input is invalid
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError>;
+
+    /// Solve part 1 of the puzzle
+    fn part_one(&self, parsed: &Self::Parsed) -> Answer;
+
+    /// Solve part 2 of the puzzle
+    fn part_two(&self, parsed: &Self::Parsed) -> Answer;
+
+    /// Explain the answer to part 1 as a sentence
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        format!("Part 1: {}", answer)
+    }
+
+    /// Explain the answer to part 2 as a sentence
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        format!("Part 2: {}", answer)
+    }
+}
+
+/// A type-erased [`Solution`], so that solutions with different parsed types can be stored together
+pub trait Puzzle: Sync {
+    /// See [`Solution::DAY`]
+    fn day(&self) -> usize;
+
+    /// See [`Solution::variant`]
+    fn variant(&self) -> Option<&str>;
+
+    /// See [`Solution::HAS_PART_TWO`]
+    fn has_part_two(&self) -> bool;
+
+    /// Parse the puzzle input, the result can then be used to solve each part. Errors are tagged with the day.
+    fn parse<'a>(&'a self, input: &str) -> Result<Box<dyn ParsedPuzzle + 'a>, ParseError>;
+}
+
+/// A puzzle with its input already parsed, ready to be solved
+pub trait ParsedPuzzle {
+    /// See [`Solution::part_one`]
+    fn part_one(&self) -> Answer;
+
+    /// See [`Solution::part_two`]
+    fn part_two(&self) -> Answer;
+
+    /// See [`Solution::describe_part_one`]
+    fn describe_part_one(&self, answer: &Answer) -> String;
+
+    /// See [`Solution::describe_part_two`]
+    fn describe_part_two(&self, answer: &Answer) -> String;
+}
+
+/// Pairs a solution with its parsed input to implement [`ParsedPuzzle`]
+struct Parsed<'a, S: Solution> {
+    solution: &'a S,
+    parsed: S::Parsed,
+}
+
+impl<S: Solution> Puzzle for S {
+    fn day(&self) -> usize {
+        S::DAY
+    }
+
+    fn variant(&self) -> Option<&str> {
+        Solution::variant(self)
+    }
+
+    fn has_part_two(&self) -> bool {
+        S::HAS_PART_TWO
+    }
+
+    fn parse<'a>(&'a self, input: &str) -> Result<Box<dyn ParsedPuzzle + 'a>, ParseError> {
+        let parsed = Solution::parse(self, input).map_err(|err| err.for_day(S::DAY))?;
+
+        Ok(Box::new(Parsed { solution: self, parsed }))
+    }
+}
+
+impl<'a, S: Solution> ParsedPuzzle for Parsed<'a, S> {
+    fn part_one(&self) -> Answer {
+        self.solution.part_one(&self.parsed).for_day(S::DAY)
+    }
+
+    fn part_two(&self) -> Answer {
+        self.solution.part_two(&self.parsed).for_day(S::DAY)
+    }
+
+    fn describe_part_one(&self, answer: &Answer) -> String {
+        self.solution.describe_part_one(answer)
+    }
+
+    fn describe_part_two(&self, answer: &Answer) -> String {
+        self.solution.describe_part_two(answer)
+    }
+}
+
+/// All the days' solutions, in day order
+pub fn solutions() -> Vec<Box<dyn Puzzle>> {
+    vec![
+        Box::new(day_1::Day1),
+        Box::new(day_2::Day2::default()),
+        Box::new(day_3::Day3),
+        Box::new(day_4::Day4),
+        Box::new(day_5::Day5),
+        Box::new(day_6::Day6),
+        Box::new(day_7::Day7),
+        Box::new(day_8::Day8),
+        Box::new(day_9::Day9),
+        Box::new(day_10::Day10),
+        Box::new(day_11::Day11),
+        Box::new(day_12::Day12),
+        Box::new(day_13::Day13),
+        Box::new(day_14::Day14),
+        Box::new(day_15::Day15),
+        Box::new(day_16::Day16),
+        Box::new(day_17::Day17),
+        Box::new(day_18::Day18),
+        Box::new(day_19::Day19),
+        Box::new(day_20::Day20),
+        Box::new(day_21::Day21),
+        Box::new(day_22::Day22),
+        Box::new(day_23::Day23),
+        Box::new(day_24::Day24),
+        Box::new(day_25::Day25),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::{Answer, solutions};
+    use crate::util::parse::ParseError;
+
+    #[test]
+    fn can_convert_and_display_answers() {
+        assert_eq!(Answer::from(24000u32), Answer::Number(24000));
+        assert_eq!(Answer::from(-3isize), Answer::Number(-3));
+        assert_eq!(Answer::from(u64::MAX), Answer::Number(u64::MAX as i128));
+        assert_eq!(Answer::from("CMZ"), Answer::Text("CMZ".to_string()));
+
+        assert_eq!(Answer::from(157usize).to_string(), "157");
+        assert_eq!(Answer::from("2=-1=0".to_string()).to_string(), "2=-1=0");
+        assert_eq!(Answer::Empty.to_string(), "-");
+
+        let error = ParseError::at(2, 1, "abc", "Expected an even number of items");
+        assert_eq!(Answer::from(Ok::<u32, ParseError>(157)), Answer::Number(157));
+        assert_eq!(Answer::from(Err::<u32, ParseError>(error.clone())), Answer::Error(error.clone()));
+        assert_eq!(Answer::Error(error).to_string(), "line 2, column 1: Expected an even number of items, found 'abc'");
+    }
+
+    #[test]
+    fn solutions_are_in_day_order() {
+        let days: Vec<usize> = solutions().iter().map(|solution| solution.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn can_solve_through_registry() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000".to_string();
+        let solutions = solutions();
+        let parsed = solutions[0].parse(&input).unwrap();
+
+        assert_eq!(parsed.part_one(), Answer::Number(24000));
+        assert_eq!(parsed.part_two(), Answer::Number(45000));
+        assert_eq!(
+            parsed.describe_part_one(&parsed.part_one()),
+            "The most calories carried by one elf is: 24000"
+        );
+    }
+
+    #[test]
+    fn parse_errors_are_tagged_with_day() {
+        let solutions = solutions();
+        let error = solutions[3].parse("2-4,6-8\n2-3,4-x").err().unwrap();
+
+        assert_eq!(error.day, Some(4));
+        assert_eq!((error.line, error.column), (2, 7));
+    }
+}
@@ -0,0 +1,360 @@
+//! A 2D grid of booleans packed into bits, for occupancy maps that would otherwise be a `HashSet` of co-ordinates.
+//!
+//! Each row is stored as a run of `u64` words, with column `x` in bit `x % 64` of word `x / 64`. Shifting the grid and
+//! combining grids with `&`, `|`, `^` and `!` work on whole words at a time, so a step of a cellular automaton can be
+//! written as a handful of grid operations rather than a loop over every cell. Bits past the width of each row are
+//! always kept clear.
+
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not};
+use crate::util::grid::Grid;
+use crate::util::sparse_grid::Bounds;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed size grid of booleans, co-ordinates are `(y, x)` to match [`Grid`]
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// An empty grid, with every cell unset
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        BitGrid { width, height, words_per_row, words: vec![0; words_per_row * height] }
+    }
+
+    /// Build a bit grid the same size as a [`Grid`], setting the cells that match the predicate
+    pub fn from_grid<T, F>(grid: &Grid<T>, predicate: F) -> BitGrid
+        where F: Fn(&T) -> bool
+    {
+        let mut bits = BitGrid::new(grid.width, grid.height());
+        for ((y, x), val) in grid.iter() {
+            if predicate(val) {
+                bits.set(y, x, true);
+            }
+        }
+
+        bits
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Is the cell set, cells outside the grid are never set
+    pub fn get(&self, y: usize, x: usize) -> bool {
+        y < self.height && x < self.width && self.row(y)[x / WORD_BITS] & (1 << (x % WORD_BITS)) != 0
+    }
+
+    /// Set or clear a cell, returns false if the cell is outside the grid
+    pub fn set(&mut self, y: usize, x: usize, value: bool) -> bool {
+        if y >= self.height || x >= self.width {
+            return false;
+        }
+
+        let word = &mut self.words[y * self.words_per_row + x / WORD_BITS];
+        if value {
+            *word |= 1 << (x % WORD_BITS);
+        } else {
+            *word &= !(1 << (x % WORD_BITS));
+        }
+
+        true
+    }
+
+    /// The words that make up a row
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// The number of cells that are set
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Are no cells set
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Iterate over the co-ordinates of the set cells, in reading order
+    pub fn iter(&self) -> impl Iterator<Item=(usize, usize)> + '_ {
+        self.words.chunks(self.words_per_row.max(1)).enumerate().flat_map(|(y, row)| {
+            row.iter().enumerate().flat_map(move |(i, &word)| {
+                std::iter::successors(Some(word), |&rest| (rest != 0).then(|| rest & (rest - 1)))
+                    .take_while(|&rest| rest != 0)
+                    .map(move |rest| (y, i * WORD_BITS + rest.trailing_zeros() as usize))
+            })
+        })
+    }
+
+    /// The smallest rectangle containing every set cell, `None` if no cells are set
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut bounds: Option<Bounds> = None;
+
+        for y in 0..self.height {
+            let row = self.row(y);
+            let Some(first) = row.iter().position(|&word| word != 0) else { continue };
+            let last = row.iter().rposition(|&word| word != 0).unwrap();
+
+            let min_x = (first * WORD_BITS + row[first].trailing_zeros() as usize) as isize;
+            let max_x = (last * WORD_BITS + (WORD_BITS - 1 - row[last].leading_zeros() as usize)) as isize;
+
+            bounds = Some(match bounds {
+                Some(Bounds { min_y, min_x: prev_min, max_x: prev_max, .. }) =>
+                    Bounds { min_y, min_x: prev_min.min(min_x), max_y: y as isize, max_x: prev_max.max(max_x) },
+                None => Bounds { min_y: y as isize, min_x, max_y: y as isize, max_x },
+            });
+        }
+
+        bounds
+    }
+
+    /// A copy of the grid with every cell moved `dy` rows down and `dx` columns right, negative values move up and
+    /// left. Cells moved outside the grid are dropped, and the cells left behind are unset.
+    pub fn shifted(&self, dy: isize, dx: isize) -> BitGrid {
+        let mut shifted = BitGrid::new(self.width, self.height);
+
+        for y in 0..self.height {
+            let Some(src_y) = y.checked_add_signed(-dy).filter(|&src_y| src_y < self.height) else { continue };
+            shift_row_into(self.row(src_y), shifted.row_mut(y), dx);
+            shifted.clear_tail(y);
+        }
+
+        shifted
+    }
+
+    /// A copy of the grid with every cell moved `dy` rows down and `dx` columns right, like [`BitGrid::shifted`], but
+    /// cells moved off one edge wrap around to the opposite edge
+    pub fn wrapped(&self, dy: isize, dx: isize) -> BitGrid {
+        if self.width == 0 || self.height == 0 {
+            return self.clone();
+        }
+
+        let (height, width) = (self.height as isize, self.width as isize);
+        let (dy, dx) = (dy.rem_euclid(height), dx.rem_euclid(width));
+
+        &(&self.shifted(dy, dx) | &self.shifted(dy - height, dx))
+            | &(&self.shifted(dy, dx - width) | &self.shifted(dy - height, dx - width))
+    }
+
+    /// A copy of the grid with `margin` unset cells added on every side
+    pub fn expanded(&self, margin: usize) -> BitGrid {
+        let mut expanded = BitGrid::new(self.width + 2 * margin, self.height + 2 * margin);
+
+        for y in 0..self.height {
+            shift_row_into(self.row(y), expanded.row_mut(y + margin), margin as isize);
+        }
+
+        expanded
+    }
+
+    /// Render the grid as a string, with a line per row, using `cell_renderer` for each cell
+    pub fn print_with<F>(&self, cell_renderer: F) -> String
+        where F: Fn(bool) -> String
+    {
+        (0..self.height)
+            .map(|y| (0..self.width).map(|x| cell_renderer(self.get(y, x))).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Render the grid as `#` for set cells and `.` for unset ones
+    pub fn print(&self) -> String {
+        self.print_with(|set| if set { "#" } else { "." }.to_string())
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Unset any bits past the end of a row, that a shift or negation may have set
+    fn clear_tail(&mut self, y: usize) {
+        let used = self.width % WORD_BITS;
+        if used != 0 {
+            let last = (y + 1) * self.words_per_row - 1;
+            self.words[last] &= (1 << used) - 1;
+        }
+    }
+
+    /// Combine two grids of the same size word by word
+    fn zip_with<F>(&self, other: &BitGrid, op: F) -> BitGrid
+        where F: Fn(u64, u64) -> u64
+    {
+        assert_eq!((self.width, self.height), (other.width, other.height), "Can only combine grids of the same size");
+
+        BitGrid {
+            words: self.words.iter().zip(&other.words).map(|(&a, &b)| op(a, b)).collect(),
+            ..*self
+        }
+    }
+}
+
+/// Copy a row of bits into an empty row moved `dx` columns to the right, or to the left if negative. Bits moved past
+/// either end of `dst` are dropped, except those in the unused bits of its last word.
+fn shift_row_into(src: &[u64], dst: &mut [u64], dx: isize) {
+    let (word_shift, bit_shift) = (dx.unsigned_abs() / WORD_BITS, dx.unsigned_abs() % WORD_BITS);
+    // Words before the start of `src` are treated as empty
+    let word = |i: Option<usize>| i.and_then(|i| src.get(i)).copied().unwrap_or(0);
+
+    for (i, out) in dst.iter_mut().enumerate() {
+        // The two source words that contribute to this word, `low` provides its least significant bits
+        let (low, high) = if dx >= 0 {
+            let high = i.checked_sub(word_shift);
+            (high.and_then(|j| j.checked_sub(1)), high)
+        } else {
+            (Some(i + word_shift), Some(i + word_shift + 1))
+        };
+
+        *out = match (bit_shift, dx >= 0) {
+            (0, true) => word(high),
+            (0, false) => word(low),
+            (_, true) => word(high) << bit_shift | word(low) >> (WORD_BITS - bit_shift),
+            (_, false) => word(low) >> bit_shift | word(high) << (WORD_BITS - bit_shift),
+        };
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, other: &BitGrid) -> BitGrid {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut inverted = BitGrid { words: self.words.iter().map(|word| !word).collect(), ..*self };
+        for y in 0..inverted.height {
+            inverted.clear_tail(y);
+        }
+
+        inverted
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, other: &BitGrid) {
+        *self = &*self & other;
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, other: &BitGrid) {
+        *self = &*self | other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::bit_grid::BitGrid;
+    use crate::util::grid::Grid;
+    use crate::util::sparse_grid::Bounds;
+
+    fn bits(input: &str) -> BitGrid {
+        BitGrid::from_grid(&Grid::from_string_with_mapping(input, |c| c), |&c| c == '#')
+    }
+
+    #[test]
+    fn can_set_and_get() {
+        let mut grid = BitGrid::new(70, 2);
+        assert!(grid.is_empty());
+
+        assert!(grid.set(0, 3, true));
+        assert!(grid.set(1, 69, true));
+        assert!(grid.set(1, 64, true));
+        assert!(!grid.set(2, 0, true));
+        assert!(!grid.set(0, 70, true));
+
+        assert!(grid.get(0, 3) && grid.get(1, 69) && grid.get(1, 64));
+        assert!(!grid.get(0, 4) && !grid.get(0, 70) && !grid.get(5, 5));
+        assert_eq!(grid.count(), 3);
+        assert_eq!(grid.iter().collect::<Vec<_>>(), vec![(0, 3), (1, 64), (1, 69)]);
+        assert_eq!(grid.bounds(), Some(Bounds { min_y: 0, min_x: 3, max_y: 1, max_x: 69 }));
+
+        assert!(grid.set(1, 64, false));
+        assert_eq!(grid.count(), 2);
+        assert_eq!(BitGrid::new(3, 3).bounds(), None);
+    }
+
+    #[test]
+    fn can_shift() {
+        let grid = bits("#..#\n.##.\n...#");
+
+        assert_eq!(grid.shifted(0, 1).print(), ".#..\n..##\n....");
+        assert_eq!(grid.shifted(0, -1).print(), "..#.\n##..\n..#.");
+        assert_eq!(grid.shifted(1, 0).print(), "....\n#..#\n.##.");
+        assert_eq!(grid.shifted(-1, 2).print(), "...#\n....\n....");
+        assert_eq!(grid.shifted(0, 4), BitGrid::new(4, 3));
+        assert_eq!(grid.expanded(1).print(), "......\n.#..#.\n..##..\n....#.\n......");
+    }
+
+    #[test]
+    fn can_wrap() {
+        let grid = bits("#..#\n.##.\n...#");
+
+        assert_eq!(grid.wrapped(0, 1).print(), "##..\n..##\n#...");
+        assert_eq!(grid.wrapped(0, -1).print(), "..##\n##..\n..#.");
+        assert_eq!(grid.wrapped(1, 0).print(), "...#\n#..#\n.##.");
+        assert_eq!(grid.wrapped(-4, 6).print(), "#..#\n.#..\n.##.");
+        assert_eq!(grid.wrapped(3, -4), grid);
+        assert_eq!(BitGrid::new(0, 0).wrapped(1, 1), BitGrid::new(0, 0));
+    }
+
+    #[test]
+    fn can_shift_across_words() {
+        let mut grid = BitGrid::new(130, 1);
+        grid.set(0, 63, true);
+        grid.set(0, 64, true);
+        grid.set(0, 129, true);
+
+        assert_eq!(grid.shifted(0, 1).iter().collect::<Vec<_>>(), vec![(0, 64), (0, 65)]);
+        assert_eq!(grid.shifted(0, -64).iter().collect::<Vec<_>>(), vec![(0, 0), (0, 65)]);
+        assert_eq!(grid.shifted(0, 66).iter().collect::<Vec<_>>(), vec![(0, 129)]);
+        assert_eq!(grid.expanded(1).iter().collect::<Vec<_>>(), vec![(1, 64), (1, 65), (1, 130)]);
+    }
+
+    #[test]
+    fn can_combine() {
+        let a = bits("##..\n#.#.");
+        let b = bits("#.#.\n#..#");
+
+        assert_eq!((&a & &b).print(), "#...\n#...");
+        assert_eq!((&a | &b).print(), "###.\n#.##");
+        assert_eq!((&a ^ &b).print(), ".##.\n..##");
+        assert_eq!((!&a).print(), "..##\n.#.#");
+        assert_eq!((!&a).count(), 4);
+
+        let mut c = a.clone();
+        c |= &b;
+        c &= &!&a;
+        assert_eq!(c.print(), "..#.\n...#");
+    }
+}
@@ -1,89 +1,101 @@
-/// A representation of a 2D grid of u8s.
+use std::str::FromStr;
+use crate::util::grid_parser::GridParser;
+use crate::util::parse::ParseError;
+
+/// A representation of a 2D grid of cells of any type, e.g. `u8` heights, or an enum of map tiles.
 #[derive(Debug, Eq, PartialEq, Clone)]
-pub struct Grid {
-    /// Store the numbers in a 1D list...
-    pub numbers: Vec<u8>,
+pub struct Grid<T> {
+    /// Store the cells in a 1D list...
+    pub cells: Vec<T>,
     /// ...and use the width to determine the 1D offset as a 2D co-ordinate
     pub width: usize,
 }
 
-impl From<String> for Grid {
+impl FromStr for Grid<u8> {
+    type Err = ParseError;
+
     /// Turn the characters into digits and concatenate, caching the width
-    fn from(string: String) -> Self {
-        Grid::from_string_with_mapping(
-            &string,
-            |c| {
-                c.to_digit(10)
-                 .expect(format!("{} is not a digit", c).as_str()) as u8
-            },
-        )
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        GridParser::new()
+            .expecting("a digit: 0-9")
+            .parse(input, |c| c.to_digit(10).map(|d| d as u8))
+            .map(|parsed| parsed.grid)
     }
 }
 
+/// The `(dy, dx)` deltas to the neighbours above, right, below and left of a cell
+pub const ORTHOGONAL_DELTAS: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The `(dy, dx)` deltas to all eight neighbours of a cell, clockwise from above
+pub const MOORE_DELTAS: [(isize, isize); 8] = [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)];
+
 /// Temporary struct representing an iterator over a grid
-pub struct GridCoords<'a> {
+pub struct GridCoords<'a, T> {
     /// Reference to the grid being iterated
-    grid: &'a Grid,
+    grid: &'a Grid<T>,
     /// The current position of the iterator
     pos: usize,
 }
 
-impl<'a> Iterator for GridCoords<'a> {
-    type Item = ((usize, usize), u8);
+impl<'a, T> Iterator for GridCoords<'a, T> {
+    type Item = ((usize, usize), &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
         let curr = self.grid.get_with_coords(self.pos);
-        self.pos = self.pos + 1;
+        self.pos += 1;
 
         curr
     }
 }
 
-impl Grid {
+impl<T> Grid<T> {
     /// Build a new grid with a mapping function to generate the date for each cell
     pub fn new<F>(width: usize, height: usize, init: F) -> Self
-        where F: Fn(usize, usize) -> u8
+        where F: Fn(usize, usize) -> T
     {
-        let mut numbers = Vec::new();
+        let mut cells = Vec::new();
         for y in 0..height {
             for x in 0..width {
-                numbers.push(init(x, y))
+                cells.push(init(x, y))
             }
         }
 
-        Self { width, numbers }
+        Self { width, cells }
     }
 
-    pub fn from_string_with_mapping(input: &String, mapping: fn(char) -> u8) -> Self {
-        let width: usize = input.lines().next().unwrap_or("").len();
+    /// Build a grid from lines of characters, using `mapping` to turn each character into a cell. The closure can
+    /// capture state, e.g. to note where a marker character was seen. The width is taken from the first line without
+    /// checking the others, use a [`GridParser`] to validate the input.
+    pub fn from_string_with_mapping<F>(input: &str, mapping: F) -> Self
+        where F: FnMut(char) -> T
+    {
+        let width: usize = input.lines().next().unwrap_or("").chars().count();
 
-        let numbers = input
+        let cells = input
             .lines()
-            .flat_map(|line| {
-                return line.chars().map(mapping);
-            })
+            .flat_map(|line| line.chars())
+            .map(mapping)
             .collect();
 
-        Self { numbers, width }
+        Self { cells, width }
     }
 
     /// Helper to abstract iterating over the whole grid
-    pub fn iter(&self) -> GridCoords {
+    pub fn iter(&self) -> GridCoords<'_, T> {
         GridCoords { grid: self, pos: 0 }
     }
 
     /// Return the value at the given co-ordinates
-    pub fn get(&self, y: usize, x: usize) -> Option<u8> {
+    pub fn get(&self, y: usize, x: usize) -> Option<&T> {
         self.pos_of((y, x))
-            .and_then(|p| self.numbers.get(p))
-            .map(|&v| v)
+            .and_then(|p| self.cells.get(p))
     }
 
     /// Update the value in a given cell
-    pub fn set(&mut self, y: usize, x: usize, val: u8) -> bool {
+    pub fn set(&mut self, y: usize, x: usize, val: T) -> bool {
         match self.pos_of((y, x)) {
             Some(pos) => {
-                self.numbers[pos] = val;
+                self.cells[pos] = val;
                 true
             }
             None => false,
@@ -98,36 +110,50 @@ impl Grid {
 
         let pos = x + y * self.width;
 
-        if pos >= self.numbers.len() {
+        if pos >= self.cells.len() {
             return None;
         }
 
-        return Some(pos);
+        Some(pos)
     }
 
-    /// Calculate the height from the
+    /// Calculate the height from the number of cells and the width
     pub fn height(&self) -> usize {
-        (self.numbers.len() + self.width - 1) / self.width
-    }
-
-    /// Sum the cells in the grid
-    pub fn sum(&self) -> usize {
-        self.numbers.iter().map(|&v| usize::from(v)).sum()
+        self.cells.len().div_ceil(self.width)
     }
 
     /// Used by [`GridCoords::next`]
-    pub fn get_with_coords(&self, pos: usize) -> Option<((usize, usize), u8)> {
+    pub fn get_with_coords(&self, pos: usize) -> Option<((usize, usize), &T)> {
         let x = pos % self.width;
         let y = pos / self.width;
 
-        self.numbers.get(pos).map(|&val| ((y, x), val))
+        self.cells.get(pos).map(|val| ((y, x), val))
     }
 
-    pub fn get_orthogonal_surrounds(&self, (y, x): (usize, usize)) -> Vec<((usize, usize), u8)> {
-        [(-1, 0), (0, 1), (1, 0), (0, -1)] // N E S W
-            .iter()
-            .flat_map(|&(dy, dx)| self.get_relative(y, x, dy, dx))
-            .collect()
+    /// The neighbours above, right, below and left of a cell that are within the grid
+    pub fn get_orthogonal_surrounds(&self, pos: (usize, usize)) -> Vec<((usize, usize), &T)> {
+        self.get_stencil(pos, &ORTHOGONAL_DELTAS)
+    }
+
+    /// All eight neighbours of a cell that are within the grid, clockwise from above
+    pub fn get_moore_surrounds(&self, pos: (usize, usize)) -> Vec<((usize, usize), &T)> {
+        self.get_stencil(pos, &MOORE_DELTAS)
+    }
+
+    /// The cells offset from a cell by each of the `(dy, dx)` deltas, in the same order, skipping any that fall
+    /// outside the grid
+    pub fn get_stencil(&self, (y, x): (usize, usize), deltas: &[(isize, isize)]) -> Vec<((usize, usize), &T)> {
+        deltas.iter()
+              .flat_map(|&(dy, dx)| self.get_relative(y, x, dy, dx))
+              .collect()
+    }
+
+    /// The cells offset from a cell by each of the `(dy, dx)` deltas, in the same order, wrapping around the edges as
+    /// if the grid were a torus. Unlike [`Grid::get_stencil`] there is always a cell for each delta.
+    pub fn get_wrapping_stencil(&self, (y, x): (usize, usize), deltas: &[(isize, isize)]) -> Vec<((usize, usize), &T)> {
+        deltas.iter()
+              .flat_map(|&(dy, dx)| self.get_wrapping_relative(y, x, dy, dx))
+              .collect()
     }
 
     pub fn get_relative(
@@ -136,7 +162,7 @@ impl Grid {
         x: usize,
         dy: isize,
         dx: isize,
-    ) -> Option<((usize, usize), u8)> {
+    ) -> Option<((usize, usize), &T)> {
         let y1 = (y as isize) + dy;
         let x1 = (x as isize) + dx;
 
@@ -148,21 +174,31 @@ impl Grid {
         }
     }
 
-    /// Dump the grid to stdout - useful for visualising the grid when debugging
-    #[allow(dead_code)]
-    pub fn print(&self) -> String {
-        self.print_with(
-            |v| if v <= 9 {
-                v.to_string()
-            } else {
-                "#".to_string()
-            }
-        )
+    /// Like [`Grid::get_relative`], but wrapping around the edges of the grid rather than stopping at them
+    pub fn get_wrapping_relative(
+        &self,
+        y: usize,
+        x: usize,
+        dy: isize,
+        dx: isize,
+    ) -> Option<((usize, usize), &T)> {
+        // Checked before working out the height, which divides by the width
+        if self.width == 0 || self.cells.is_empty() {
+            return None;
+        }
+
+        let height = self.height() as isize;
+        let width = self.width as isize;
+
+        let y1 = (y as isize + dy).rem_euclid(height) as usize;
+        let x1 = (x as isize + dx).rem_euclid(width) as usize;
+
+        self.get(y1, x1).map(|val| ((y1, x1), val))
     }
 
-    #[allow(dead_code)]
+    /// Render the grid as a string, with a line per row, using `cell_renderer` for each cell
     pub fn print_with<F>(&self, cell_renderer: F) -> String
-        where F: Fn(u8) -> String
+        where F: Fn(&T) -> String
     {
         let (_, out) = self
             .iter()
@@ -182,9 +218,194 @@ impl Grid {
     }
 }
 
+impl<T: Clone> Grid<T> {
+    /// Build a `height` x `width` grid where each cell is copied from the `(y, x)` returned by `source` for that
+    /// cell's `(y, x)`. Used to implement the transformations, which all expect a rectangular grid.
+    fn remap<F>(&self, height: usize, width: usize, source: F) -> Grid<T>
+        where F: Fn(usize, usize) -> (usize, usize)
+    {
+        Grid::new(width, height, |x, y| {
+            let (source_y, source_x) = source(y, x);
+            self.get(source_y, source_x).expect("transformations require a rectangular grid").clone()
+        })
+    }
+
+    /// Turn the grid a quarter turn clockwise, so the first column becomes the first row
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        let height = self.height();
+        self.remap(self.width, height, |y, x| (height - 1 - x, y))
+    }
+
+    /// Turn the grid a quarter turn anticlockwise, so the last column becomes the first row
+    pub fn rotate_anticlockwise(&self) -> Grid<T> {
+        let width = self.width;
+        self.remap(width, self.height(), |y, x| (x, width - 1 - y))
+    }
+
+    /// Turn the grid upside down
+    pub fn rotate_half(&self) -> Grid<T> {
+        let (height, width) = (self.height(), self.width);
+        self.remap(height, width, |y, x| (height - 1 - y, width - 1 - x))
+    }
+
+    /// Mirror the grid left to right
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let width = self.width;
+        self.remap(self.height(), width, |y, x| (y, width - 1 - x))
+    }
+
+    /// Mirror the grid top to bottom
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let height = self.height();
+        self.remap(height, self.width, |y, x| (height - 1 - y, x))
+    }
+
+    /// Swap rows and columns, i.e. mirror along the leading diagonal
+    pub fn transpose(&self) -> Grid<T> {
+        self.remap(self.width, self.height(), |y, x| (x, y))
+    }
+
+    /// Copy the section of the grid with its top left corner at `(y, x)`, or `None` if it is empty or doesn't fit
+    /// in the grid
+    pub fn crop(&self, y: usize, x: usize, height: usize, width: usize) -> Option<Grid<T>> {
+        self.view(y, x, height, width).map(|view| view.to_grid())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Check a section of the grid has at least one cell and is fully within the grid
+    fn contains_section(&self, y: usize, x: usize, height: usize, width: usize) -> bool {
+        height > 0 && width > 0 && x + width <= self.width && (y + height) * self.width <= self.cells.len()
+    }
+
+    /// Borrow the section of the grid with its top left corner at `(y, x)`, or `None` if it is empty or doesn't fit
+    /// in the grid
+    pub fn view(&self, y: usize, x: usize, height: usize, width: usize) -> Option<GridView<'_, T>> {
+        self.contains_section(y, x, height, width)
+            .then_some(GridView { grid: self, y, x, height, width })
+    }
+
+    /// Mutably borrow the section of the grid with its top left corner at `(y, x)`, or `None` if it is empty or doesn't
+    /// fit in the grid
+    pub fn view_mut(&mut self, y: usize, x: usize, height: usize, width: usize) -> Option<GridViewMut<'_, T>> {
+        self.contains_section(y, x, height, width)
+            .then_some(GridViewMut { grid: self, y, x, height, width })
+    }
+}
+
+/// A borrowed rectangular section of a [`Grid`], addressed with co-ordinates relative to its top left corner
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    y: usize,
+    x: usize,
+    height: usize,
+    width: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the value at the given co-ordinates within the view
+    pub fn get(&self, y: usize, x: usize) -> Option<&'a T> {
+        if y < self.height && x < self.width {
+            self.grid.get(self.y + y, self.x + x)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the view's cells row by row, with co-ordinates relative to the view
+    pub fn iter(&self) -> impl Iterator<Item=((usize, usize), &'a T)> + 'a {
+        let (x, width) = (self.x, self.width);
+
+        self.grid.cells
+            .chunks(self.grid.width)
+            .skip(self.y)
+            .take(self.height)
+            .enumerate()
+            .flat_map(move |(y, row)| row[x..x + width].iter().enumerate().map(move |(x, val)| ((y, x), val)))
+    }
+
+    /// Copy the view into a grid of its own
+    pub fn to_grid(&self) -> Grid<T> where T: Clone {
+        Grid { cells: self.iter().map(|(_, val)| val.clone()).collect(), width: self.width }
+    }
+}
+
+/// A mutably borrowed rectangular section of a [`Grid`], addressed with co-ordinates relative to its top left corner
+#[derive(Debug)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    y: usize,
+    x: usize,
+    height: usize,
+    width: usize,
+}
+
+impl<T> GridViewMut<'_, T> {
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Return the value at the given co-ordinates within the view
+    pub fn get(&self, y: usize, x: usize) -> Option<&T> {
+        if y < self.height && x < self.width {
+            self.grid.get(self.y + y, self.x + x)
+        } else {
+            None
+        }
+    }
+
+    /// Update the value in a given cell of the view, returning false if it is outside the view
+    pub fn set(&mut self, y: usize, x: usize, val: T) -> bool {
+        y < self.height && x < self.width && self.grid.set(self.y + y, self.x + x, val)
+    }
+
+    /// Iterate over the view's cells row by row, with co-ordinates relative to the view, allowing them to be updated
+    pub fn iter_mut(&mut self) -> impl Iterator<Item=((usize, usize), &mut T)> {
+        let (x, width) = (self.x, self.width);
+
+        self.grid.cells
+            .chunks_mut(self.grid.width)
+            .skip(self.y)
+            .take(self.height)
+            .enumerate()
+            .flat_map(move |(y, row)| row[x..x + width].iter_mut().enumerate().map(move |(x, val)| ((y, x), val)))
+    }
+}
+
+impl Grid<u8> {
+    /// Sum the cells in the grid
+    pub fn sum(&self) -> usize {
+        self.cells.iter().map(|&v| usize::from(v)).sum()
+    }
+
+    /// Dump the grid to stdout - useful for visualising the grid when debugging
+    pub fn print(&self) -> String {
+        self.print_with(
+            |&v| if v <= 9 {
+                v.to_string()
+            } else {
+                "#".to_string()
+            }
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::grid::Grid;
+    use crate::util::grid::{Grid, MOORE_DELTAS};
 
     fn sample_input() -> String {
         "12345\n\
@@ -198,26 +419,26 @@ mod tests {
 
     #[test]
     fn can_set_and_get() {
-        let mut grid = Grid::from(sample_input());
+        let mut grid = sample_input().parse::<Grid<u8>>().unwrap();
 
-        assert_eq!(grid.get(0, 0), Some(1));
-        assert_eq!(grid.get(0, 4), Some(5));
-        assert_eq!(grid.get(4, 0), Some(5));
-        assert_eq!(grid.get(4, 4), Some(9));
+        assert_eq!(grid.get(0, 0), Some(&1));
+        assert_eq!(grid.get(0, 4), Some(&5));
+        assert_eq!(grid.get(4, 0), Some(&5));
+        assert_eq!(grid.get(4, 4), Some(&9));
         assert_eq!(grid.get(5, 4), None);
         assert_eq!(grid.get(3, 5), None);
         assert_eq!(grid.get(17, 29), None);
 
         grid.set(4, 4, 17);
 
-        assert_eq!(grid.get(4, 4), Some(17));
+        assert_eq!(grid.get(4, 4), Some(&17));
     }
 
     #[test]
     fn can_print() {
         let input = sample_input();
 
-        let mut grid = Grid::from(input.clone());
+        let mut grid = input.parse::<Grid<u8>>().unwrap();
 
         assert_eq!(grid.print(), input);
 
@@ -230,7 +451,7 @@ mod tests {
     #[test]
     fn can_print_with_custom_output() {
         let input = sample_input();
-        let grid = Grid::from(input);
+        let grid = input.parse::<Grid<u8>>().unwrap();
 
         let expected = "abcde\n\
         bcdef\n\
@@ -239,12 +460,12 @@ mod tests {
         efghi"
             .to_string();
 
-        assert_eq!(grid.print_with(|v| char::from(v + 96).to_string()), expected);
+        assert_eq!(grid.print_with(|&v| char::from(v + 96).to_string()), expected);
     }
 
     #[test]
     fn set_ignores_out_of_bounds() {
-        let mut grid = Grid::from(sample_input());
+        let mut grid = sample_input().parse::<Grid<u8>>().unwrap();
 
         assert_eq!(grid.set(5, 0, 9), false);
         assert_eq!(grid.set(0, 5, 9), false);
@@ -253,11 +474,20 @@ mod tests {
         assert_eq!(grid.print(), sample_input());
     }
 
+    #[test]
+    fn reports_invalid_digits() {
+        let error = "123\n4x6".parse::<Grid<u8>>().unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
+        assert_eq!(error.message, "Expected a digit: 0-9");
+
+        assert_eq!("123\n45".parse::<Grid<u8>>().unwrap_err().text, "45");
+    }
+
     #[test]
     fn can_calc_height() {
-        assert_eq!(Grid::from("1\n2\n3\n4".to_string()).height(), 4);
-        assert_eq!(Grid::from("12\n34\n56".to_string()).height(), 3);
-        assert_eq!(Grid::from("123\n34".to_string()).height(), 2);
+        assert_eq!("1\n2\n3\n4".parse::<Grid<u8>>().unwrap().height(), 4);
+        assert_eq!("12\n34\n56".parse::<Grid<u8>>().unwrap().height(), 3);
+        assert_eq!(Grid { cells: vec![1, 2, 3, 3, 4], width: 3 }.height(), 2);
     }
 
     #[test]
@@ -268,9 +498,124 @@ mod tests {
         assert_eq!(grid.print(), "012\n123\n234");
     }
 
+    #[test]
+    fn can_map_any_cell_type() {
+        #[derive(Eq, PartialEq, Debug, Clone, Copy)]
+        enum Tile { Open, Wall }
+
+        let mut start = None;
+        let mut x = 0;
+        let mut grid = Grid::from_string_with_mapping("..#\n#S.", |c| {
+            if c == 'S' {
+                start = Some(x);
+            }
+            x += 1;
+
+            if c == '#' { Tile::Wall } else { Tile::Open }
+        });
+
+        assert_eq!(start, Some(4));
+        assert_eq!(grid.get(0, 2), Some(&Tile::Wall));
+        assert_eq!(grid.get(1, 1), Some(&Tile::Open));
+        assert_eq!(
+            grid.get_orthogonal_surrounds((1, 1)),
+            vec![((0, 1), &Tile::Open), ((1, 2), &Tile::Open), ((1, 0), &Tile::Wall)]
+        );
+        assert_eq!(grid.get_relative(1, 1, -1, 1), Some(((0, 2), &Tile::Wall)));
+
+        grid.set(0, 0, Tile::Wall);
+        assert_eq!(grid.print_with(|&t| if t == Tile::Wall { "#" } else { "." }.to_string()), "#.#\n#..");
+
+        let names = Grid::new(2, 1, |x, _| format!("cell {}", x));
+        assert_eq!(names.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>(), vec!["cell 0", "cell 1"]);
+    }
+
     #[test]
     fn can_sum_grid() {
         let grid = Grid::new(3, 3, |x, y| u8::try_from(x).unwrap() + u8::try_from(y).unwrap());
         assert_eq!(grid.sum(), 18)
     }
+
+    #[test]
+    fn can_transform() {
+        let grid = "123\n456".parse::<Grid<u8>>().unwrap();
+
+        assert_eq!(grid.rotate_clockwise().print(), "41\n52\n63");
+        assert_eq!(grid.rotate_anticlockwise().print(), "36\n25\n14");
+        assert_eq!(grid.rotate_half().print(), "654\n321");
+        assert_eq!(grid.flip_horizontal().print(), "321\n654");
+        assert_eq!(grid.flip_vertical().print(), "456\n123");
+        assert_eq!(grid.transpose().print(), "14\n25\n36");
+
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        assert_eq!(grid.rotate_clockwise().rotate_clockwise(), grid.rotate_half());
+    }
+
+    #[test]
+    fn can_crop() {
+        let grid = sample_input().parse::<Grid<u8>>().unwrap();
+
+        assert_eq!(grid.crop(1, 2, 2, 3).map(|cropped| cropped.print()), Some("456\n567".to_string()));
+        assert_eq!(grid.crop(3, 3, 2, 2).map(|cropped| cropped.print()), Some("78\n89".to_string()));
+        assert_eq!(grid.crop(4, 4, 2, 1), None);
+        assert_eq!(grid.crop(0, 4, 1, 2), None);
+        assert_eq!(grid.crop(1, 1, 0, 2), None);
+        assert_eq!(grid.crop(1, 1, 2, 0), None);
+    }
+
+    #[test]
+    fn can_view_sections() {
+        let mut grid = sample_input().parse::<Grid<u8>>().unwrap();
+
+        let view = grid.view(1, 2, 2, 3).unwrap();
+        assert_eq!((view.height(), view.width()), (2, 3));
+        assert_eq!(view.get(0, 0), Some(&4));
+        assert_eq!(view.get(1, 2), Some(&7));
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.get(0, 3), None);
+        assert_eq!(
+            view.iter().collect::<Vec<_>>(),
+            vec![((0, 0), &4), ((0, 1), &5), ((0, 2), &6), ((1, 0), &5), ((1, 1), &6), ((1, 2), &7)]
+        );
+
+        let mut view = grid.view_mut(3, 0, 2, 2).unwrap();
+        for (_, val) in view.iter_mut() {
+            *val = 0;
+        }
+        assert!(view.set(0, 1, 9));
+        assert!(!view.set(0, 2, 9));
+        assert_eq!(view.get(0, 1), Some(&9));
+
+        assert_eq!(grid.print(), "12345\n23456\n34567\n09678\n00789");
+        assert!(grid.view_mut(0, 0, 6, 1).is_none());
+        assert!(grid.view(2, 2, 0, 0).is_none());
+        assert!(grid.view_mut(2, 5, 1, 0).is_none());
+
+        let mut empty: Grid<u8> = Grid::new(0, 0, |_, _| 0);
+        assert!(empty.view(0, 0, 0, 0).is_none());
+        assert!(empty.view_mut(0, 0, 0, 0).is_none());
+    }
+
+    #[test]
+    fn can_get_neighbourhoods() {
+        let grid = "123\n456\n789".parse::<Grid<u8>>().unwrap();
+        let values = |cells: Vec<((usize, usize), &u8)>| cells.into_iter().map(|(_, &v)| v).collect::<Vec<u8>>();
+
+        assert_eq!(values(grid.get_orthogonal_surrounds((1, 1))), vec![2, 6, 8, 4]);
+        assert_eq!(values(grid.get_orthogonal_surrounds((0, 0))), vec![2, 4]);
+        assert_eq!(values(grid.get_moore_surrounds((1, 1))), vec![2, 3, 6, 9, 8, 7, 4, 1]);
+        assert_eq!(grid.get_moore_surrounds((0, 0)), vec![((0, 1), &2), ((1, 1), &5), ((1, 0), &4)]);
+
+        let knight = [(-2, 1), (-1, 2), (1, 2), (2, -1)];
+        assert_eq!(grid.get_stencil((2, 0), &knight), vec![((0, 1), &2), ((1, 2), &6)]);
+
+        assert_eq!(values(grid.get_wrapping_stencil((0, 0), &MOORE_DELTAS)), vec![7, 8, 2, 5, 4, 6, 3, 9]);
+        assert_eq!(grid.get_wrapping_relative(2, 2, 1, 4), Some(((0, 0), &1)));
+        assert_eq!(grid.get_wrapping_stencil((2, 0), &knight).len(), 4);
+
+        for empty in [Grid::new(0, 3, |_, _| 0u8), Grid::new(3, 0, |_, _| 0u8)] {
+            assert_eq!(empty.get_wrapping_relative(0, 0, 1, 1), None);
+            assert_eq!(empty.get_wrapping_stencil((0, 0), &MOORE_DELTAS), vec![]);
+        }
+    }
 }
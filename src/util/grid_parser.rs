@@ -0,0 +1,211 @@
+//! Parses character grids into a [`Grid`], checking the input rather than panicking on it.
+//!
+//! A [`GridParser`] is configured with how to handle rows of different lengths, and with marker characters (e.g. the
+//! start and goal) whose positions are returned alongside the grid. Any problem is reported as a [`ParseError`]
+//! pointing at the line and column of the offending character or row.
+
+use std::collections::HashMap;
+use crate::util::grid::Grid;
+use crate::util::parse::ParseError;
+
+/// How a [`GridParser`] treats rows that aren't as wide as the others
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RaggedRows {
+    /// Every row must be as wide as the first
+    Reject,
+    /// Rows shorter than the widest are filled with this character, as if it was in the input
+    Pad(char),
+}
+
+/// Options for parsing a grid, built up then applied to an input with [`GridParser::parse`]
+#[derive(Debug, Clone)]
+pub struct GridParser {
+    ragged_rows: RaggedRows,
+    /// Characters that must appear exactly once, with a name to use in errors
+    markers: Vec<(char, String)>,
+    /// Characters whose positions are recorded however many times they appear
+    tracked: Vec<char>,
+    /// What the mapping accepts, used in the error for a character it rejects
+    expecting: String,
+}
+
+impl Default for GridParser {
+    fn default() -> Self {
+        GridParser {
+            ragged_rows: RaggedRows::Reject,
+            markers: Vec::new(),
+            tracked: Vec::new(),
+            expecting: "a grid cell".to_string(),
+        }
+    }
+}
+
+/// A parsed grid, with the positions of any marker or tracked characters
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ParsedGrid<T> {
+    pub grid: Grid<T>,
+    positions: HashMap<char, Vec<(usize, usize)>>,
+}
+
+impl<T> ParsedGrid<T> {
+    /// The `(y, x)` position of a marker, or the first position of a tracked character
+    pub fn position(&self, marker: char) -> Option<(usize, usize)> {
+        self.positions(marker).first().copied()
+    }
+
+    /// Every `(y, x)` position of a marker or tracked character, in reading order
+    pub fn positions(&self, marker: char) -> &[(usize, usize)] {
+        self.positions.get(&marker).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl GridParser {
+    pub fn new() -> GridParser {
+        GridParser::default()
+    }
+
+    /// Set how rows of different widths are handled, the default is [`RaggedRows::Reject`]
+    pub fn ragged_rows(self, ragged_rows: RaggedRows) -> GridParser {
+        GridParser { ragged_rows, ..self }
+    }
+
+    /// Require `marker` to appear exactly once, `name` describes it in errors, e.g. "start"
+    pub fn marker(mut self, marker: char, name: &str) -> GridParser {
+        self.markers.push((marker, name.to_string()));
+        self
+    }
+
+    /// Record the positions of `marker`, which can appear any number of times
+    pub fn track(mut self, marker: char) -> GridParser {
+        self.tracked.push(marker);
+        self
+    }
+
+    /// Describe the characters the mapping accepts, e.g. "a height a-z" is reported as "Expected a height a-z"
+    pub fn expecting(self, expecting: &str) -> GridParser {
+        GridParser { expecting: expecting.to_string(), ..self }
+    }
+
+    /// Parse `input` into a grid, using `mapping` to turn each character into a cell, or `None` if the character
+    /// isn't valid. Markers are passed to the mapping like any other character.
+    pub fn parse<T, F>(&self, input: &str, mut mapping: F) -> Result<ParsedGrid<T>, ParseError>
+        where F: FnMut(char) -> Option<T>
+    {
+        let row_width = |line: &str| line.chars().count();
+        let width = match self.ragged_rows {
+            RaggedRows::Reject => input.lines().next().map_or(0, row_width),
+            RaggedRows::Pad(_) => input.lines().map(row_width).max().unwrap_or(0),
+        };
+
+        if width == 0 {
+            return Err(ParseError::new(input, input, "Expected at least one row"));
+        }
+
+        let mut cells = Vec::new();
+        let mut positions: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
+
+        for (y, line) in input.lines().enumerate() {
+            let padding = match self.ragged_rows {
+                _ if row_width(line) == width => 0,
+                RaggedRows::Pad(_) => width - row_width(line),
+                RaggedRows::Reject =>
+                    return Err(ParseError::new(input, line, format!("Expected a row of {} cells", width))),
+            };
+
+            for (x, (pos, chr)) in line.char_indices().enumerate() {
+                let text = &line[pos..pos + chr.len_utf8()];
+
+                let marker = self.markers.iter().find(|(marker, _)| *marker == chr);
+                if let Some((_, name)) = marker.filter(|_| positions.contains_key(&chr)) {
+                    return Err(ParseError::new(input, text, format!("Expected only one {} marker: {}", name, chr)));
+                }
+
+                if marker.is_some() || self.tracked.contains(&chr) {
+                    positions.entry(chr).or_default().push((y, x));
+                }
+
+                match mapping(chr) {
+                    Some(cell) => cells.push(cell),
+                    None => return Err(ParseError::new(input, text, format!("Expected {}", self.expecting))),
+                }
+            }
+
+            if let RaggedRows::Pad(pad) = self.ragged_rows {
+                for _ in 0..padding {
+                    match mapping(pad) {
+                        Some(cell) => cells.push(cell),
+                        None => return Err(ParseError::new(input, line, "Expected the padding to be a valid cell")),
+                    }
+                }
+            }
+        }
+
+        if let Some((marker, name)) = self.markers.iter().find(|(marker, _)| !positions.contains_key(marker)) {
+            return Err(ParseError::new(input, input, format!("Expected a {} marker: {}", name, marker)));
+        }
+
+        Ok(ParsedGrid { grid: Grid { cells, width }, positions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid_parser::{GridParser, RaggedRows};
+
+    fn digit(c: char) -> Option<u8> {
+        c.to_digit(10).map(|d| d as u8)
+    }
+
+    #[test]
+    fn can_parse_grids() {
+        let parsed = GridParser::new().parse("123\n456", digit).unwrap();
+
+        assert_eq!(parsed.grid.width, 3);
+        assert_eq!(parsed.grid.cells, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(parsed.position('1'), None);
+    }
+
+    #[test]
+    fn handles_ragged_rows() {
+        let error = GridParser::new().parse("123\n45\n6", digit).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "45"));
+        assert_eq!(error.message, "Expected a row of 3 cells");
+
+        let padded = GridParser::new().ragged_rows(RaggedRows::Pad('0')).parse("1\n234\n56", digit).unwrap();
+        assert_eq!(padded.grid.width, 3);
+        assert_eq!(padded.grid.print(), "100\n234\n560");
+
+        let bad_padding = GridParser::new().ragged_rows(RaggedRows::Pad('.')).parse("1\n234", digit).unwrap_err();
+        assert_eq!((bad_padding.line, bad_padding.text.as_str()), (1, "1"));
+    }
+
+    #[test]
+    fn finds_markers() {
+        let parser = GridParser::new().marker('S', "start").marker('E', "goal").track('#').expecting("'.' or '#'");
+        let tile = |c| match c {
+            '#' => Some(true),
+            '.' | 'S' | 'E' => Some(false),
+            _ => None,
+        };
+
+        let parsed = parser.parse("S.#\n#.E\n.##", tile).unwrap();
+        assert_eq!(parsed.position('S'), Some((0, 0)));
+        assert_eq!(parsed.position('E'), Some((1, 2)));
+        assert_eq!(parsed.positions('#'), &[(0, 2), (1, 0), (2, 1), (2, 2)]);
+        assert_eq!(parsed.grid.get(1, 0), Some(&true));
+
+        let missing = parser.parse("S.#\n#..", tile).unwrap_err();
+        assert_eq!((missing.line, missing.column, missing.text.as_str()), (1, 1, "S.#\n#.."));
+        assert_eq!(missing.message, "Expected a goal marker: E");
+
+        let duplicate = parser.parse("S.E\n#S.", tile).unwrap_err();
+        assert_eq!((duplicate.line, duplicate.column, duplicate.text.as_str()), (2, 2, "S"));
+        assert_eq!(duplicate.message, "Expected only one start marker: S");
+
+        let invalid = parser.parse("S.E\n#éx", tile).unwrap_err();
+        assert_eq!((invalid.line, invalid.column, invalid.text.as_str()), (2, 2, "é"));
+        assert_eq!(invalid.message, "Expected '.' or '#'");
+
+        assert_eq!(parser.parse("", tile).unwrap_err().message, "Expected at least one row");
+    }
+}
@@ -0,0 +1,330 @@
+//! Ranges of integers, and sets of them that are kept merged, e.g. for tracking which sections of a row are covered.
+//!
+//! An [`Interval`] includes both its start and end, as the puzzles describe ranges like `2-4` meaning 2, 3 and 4. An
+//! [`IntervalSet`] keeps its intervals sorted, and merges any that overlap or are adjacent, so `1-3` and `4-6` are
+//! stored as `1-6`. Building a set from an iterator sorts once then merges, so it scales to millions of intervals.
+//! The set operations walk both sets in order, so are linear in the number of intervals.
+
+use std::fmt::{Display, Formatter};
+
+/// A range of integers, including both ends
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Ord, PartialOrd)]
+pub struct Interval {
+    start: isize,
+    end: isize,
+}
+
+impl Interval {
+    /// An interval from `start` to `end` inclusive. Panics if `start` is after `end`.
+    pub fn new(start: isize, end: isize) -> Interval {
+        assert!(start <= end, "An interval can't start after it ends: {}-{}", start, end);
+        Interval { start, end }
+    }
+
+    /// The first value in the interval
+    pub fn start(&self) -> isize {
+        self.start
+    }
+
+    /// The last value in the interval, never before [`Interval::start`]
+    pub fn end(&self) -> isize {
+        self.end
+    }
+
+    /// An interval containing only one value
+    pub fn single(value: isize) -> Interval {
+        Interval { start: value, end: value }
+    }
+
+    /// The number of values in the interval
+    pub fn len(&self) -> usize {
+        self.start.abs_diff(self.end) + 1
+    }
+
+    /// Intervals always include at least one value
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn contains(&self, value: isize) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Does this interval include every value of the other
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Do the intervals have any values in common
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The values the intervals have in common, if any
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+
+    /// Do the intervals overlap, or are they next to each other so they could be merged into one
+    fn touches(&self, other: &Interval) -> bool {
+        self.start <= other.end.saturating_add(1) && other.start <= self.end.saturating_add(1)
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of integers, stored as the sorted, non-overlapping intervals that cover them
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item=Interval>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval> = iter.into_iter().collect();
+        intervals.sort_unstable();
+
+        IntervalSet::from_sorted(intervals)
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        IntervalSet { intervals: vec![interval] }
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet::default()
+    }
+
+    /// Merge intervals that are already sorted by their start
+    fn from_sorted<I: IntoIterator<Item=Interval>>(sorted: I) -> IntervalSet {
+        let mut intervals: Vec<Interval> = Vec::new();
+
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    /// The merged intervals, in order
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item=&Interval> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of values in the set
+    pub fn total_length(&self) -> usize {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn contains(&self, value: isize) -> bool {
+        let index = self.intervals.partition_point(|interval| interval.end < value);
+        self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Does the set include any of the interval's values
+    pub fn overlaps(&self, interval: &Interval) -> bool {
+        let index = self.intervals.partition_point(|existing| existing.end < interval.start);
+        self.intervals.get(index).is_some_and(|existing| existing.overlaps(interval))
+    }
+
+    /// Add an interval to the set, merging it with any it touches
+    pub fn insert(&mut self, interval: Interval) {
+        // The range of existing intervals that touch the new one, these are replaced with the merged interval
+        let first = self.intervals.partition_point(|existing| existing.end.saturating_add(1) < interval.start);
+        let last = self.intervals.partition_point(|existing| existing.start <= interval.end.saturating_add(1));
+
+        let merged = match self.intervals[first..last] {
+            [] => interval,
+            ref touching => Interval::new(
+                interval.start.min(touching[0].start),
+                interval.end.max(touching[touching.len() - 1].end),
+            ),
+        };
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// The values in either set
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut merged = Vec::with_capacity(self.intervals.len() + other.intervals.len());
+        let (mut a, mut b) = (self.intervals.iter().peekable(), other.intervals.iter().peekable());
+
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x <= y => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+
+            match next {
+                Some(&interval) => merged.push(interval),
+                None => break,
+            }
+        }
+
+        IntervalSet::from_sorted(merged)
+    }
+
+    /// The values in both sets
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            if let Some(common) = a.intersection(b) {
+                intervals.push(common);
+            }
+
+            // Whichever ends first can't overlap anything else in the other set
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    /// The values in this set that aren't in the other
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = Vec::new();
+        let mut others = other.intervals.iter().peekable();
+
+        for interval in &self.intervals {
+            // Skip those that end before this interval, they can't affect it or any that follow
+            while others.next_if(|removed| removed.end < interval.start).is_some() {}
+
+            // The start of the part of the interval that hasn't been checked yet, `None` once it's all removed
+            let mut start = Some(interval.start);
+            for removed in others.clone().take_while(|removed| removed.start <= interval.end) {
+                let Some(from) = start else { break };
+                if removed.start > from {
+                    intervals.push(Interval::new(from, removed.start - 1));
+                }
+                start = removed.end.checked_add(1);
+            }
+
+            if let Some(from) = start.filter(|&from| from <= interval.end) {
+                intervals.push(Interval::new(from, interval.end));
+            }
+        }
+
+        IntervalSet { intervals }
+    }
+
+    /// The values within `bounds` that aren't in the set
+    pub fn gaps(&self, bounds: Interval) -> IntervalSet {
+        IntervalSet::from(bounds).difference(self)
+    }
+}
+
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, interval) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", interval)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::interval::{Interval, IntervalSet};
+
+    fn set(intervals: &[(isize, isize)]) -> IntervalSet {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    #[test]
+    fn can_compare_intervals() {
+        let interval = Interval::new(4, 6);
+
+        assert_eq!((interval.start(), interval.end()), (4, 6));
+        assert_eq!(interval.len(), 3);
+        assert!(interval.contains(4) && interval.contains(6) && !interval.contains(7));
+        assert!(interval.contains_interval(&Interval::new(4, 5)));
+        assert!(!interval.contains_interval(&Interval::new(3, 5)));
+        assert!(interval.overlaps(&Interval::new(6, 9)));
+        assert!(!interval.overlaps(&Interval::new(7, 9)));
+        assert_eq!(interval.intersection(&Interval::new(5, 9)), Some(Interval::new(5, 6)));
+        assert_eq!(interval.intersection(&Interval::single(7)), None);
+        assert_eq!(interval.to_string(), "4-6");
+    }
+
+    #[test]
+    fn merges_intervals() {
+        assert_eq!(set(&[(5, 7), (1, 3), (4, 4), (10, 12), (11, 11)]).to_string(), "1-7,10-12");
+        assert_eq!(set(&[]).to_string(), "");
+
+        let mut intervals = set(&[(1, 3), (10, 12), (20, 22)]);
+        intervals.insert(Interval::new(5, 6));
+        assert_eq!(intervals.to_string(), "1-3,5-6,10-12,20-22");
+        intervals.insert(Interval::new(4, 9));
+        assert_eq!(intervals.to_string(), "1-12,20-22");
+        intervals.insert(Interval::new(25, 25));
+        intervals.insert(Interval::new(-5, -5));
+        assert_eq!(intervals.to_string(), "-5--5,1-12,20-22,25-25");
+        intervals.insert(Interval::new(0, 30));
+        assert_eq!(intervals.to_string(), "-5--5,0-30");
+    }
+
+    #[test]
+    fn can_query_sets() {
+        let intervals = set(&[(1, 3), (10, 12)]);
+
+        assert_eq!(intervals.total_length(), 6);
+        assert!(intervals.contains(1) && intervals.contains(12));
+        assert!(!intervals.contains(0) && !intervals.contains(5) && !intervals.contains(13));
+        assert!(intervals.overlaps(&Interval::new(3, 9)) && intervals.overlaps(&Interval::new(0, 20)));
+        assert!(!intervals.overlaps(&Interval::new(4, 9)) && !intervals.overlaps(&Interval::single(13)));
+        assert_eq!(intervals.gaps(Interval::new(0, 15)).to_string(), "0-0,4-9,13-15");
+        assert_eq!(intervals.gaps(Interval::new(2, 11)).to_string(), "4-9");
+        assert!(intervals.gaps(Interval::new(10, 12)).is_empty());
+    }
+
+    #[test]
+    fn can_combine_sets() {
+        let a = set(&[(1, 5), (10, 15), (20, 25)]);
+        let b = set(&[(3, 11), (14, 14), (30, 31)]);
+
+        assert_eq!(a.union(&b).to_string(), "1-15,20-25,30-31");
+        assert_eq!(a.intersection(&b).to_string(), "3-5,10-11,14-14");
+        assert_eq!(a.difference(&b).to_string(), "1-2,12-13,15-15,20-25");
+        assert_eq!(b.difference(&a).to_string(), "6-9,30-31");
+        assert_eq!(a.difference(&a), IntervalSet::new());
+        assert_eq!(a.union(&IntervalSet::new()), a);
+        assert!(a.intersection(&IntervalSet::new()).is_empty());
+    }
+
+    #[test]
+    fn scales_to_many_intervals() {
+        let many: IntervalSet = (0..1_000_000).rev().map(|i| Interval::new(i * 3, i * 3 + 1)).collect();
+        assert_eq!(many.intervals().len(), 1_000_000);
+        assert_eq!(many.total_length(), 2_000_000);
+
+        let filled = many.union(&(0..1_000_000).map(|i| Interval::single(i * 3 + 2)).collect());
+        assert_eq!(filled.to_string(), "0-2999999");
+        assert_eq!(many.gaps(Interval::new(0, 2_999_999)).total_length(), 1_000_000);
+    }
+}
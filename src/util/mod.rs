@@ -1 +1,8 @@
-pub(crate) mod grid;
+pub mod bit_grid;
+pub mod grid;
+pub mod grid_parser;
+pub mod interval;
+pub mod parse;
+pub mod point;
+pub mod search;
+pub mod sparse_grid;
@@ -0,0 +1,167 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Describes why, and where, a puzzle input couldn't be parsed.
+///
+/// Lines and columns are 1-based and relative to the text passed in when the error was created. Parsers that work on
+/// sections of the input (e.g. single lines) can use [`ParseError::within`] to re-base the error onto the full input.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ParseError {
+    /// The day being parsed, this is filled in by the runner
+    pub day: Option<usize>,
+    /// The line the problem was found on
+    pub line: usize,
+    /// The column, in characters, the offending text starts at
+    pub column: usize,
+    /// The text that couldn't be parsed
+    pub text: String,
+    /// A description of what was expected
+    pub message: String,
+}
+
+impl ParseError {
+    /// Build an error for `text`, which is expected to be a slice of `context`
+    pub fn new<M: Into<String>>(context: &str, text: &str, message: M) -> Self {
+        let (line, column) = locate(context, text);
+
+        ParseError { day: None, line, column, text: text.to_string(), message: message.into() }
+    }
+
+    /// Build an error at a known position, for when the full input isn't available, e.g. it is being streamed
+    pub fn at<M: Into<String>>(line: usize, column: usize, text: &str, message: M) -> Self {
+        ParseError { day: None, line, column, text: text.to_string(), message: message.into() }
+    }
+
+    /// Re-base an error raised while parsing `inner`, a slice of `outer`, so that it is positioned relative to `outer`
+    pub fn within(self, outer: &str, inner: &str) -> Self {
+        let (inner_line, inner_column) = locate(outer, inner);
+        let column = if self.line == 1 { inner_column + self.column - 1 } else { self.column };
+
+        ParseError { line: inner_line + self.line - 1, column, ..self }
+    }
+
+    /// Record which day's input this error was raised for
+    pub fn for_day(self, day: usize) -> Self {
+        ParseError { day: Some(day), ..self }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "Day {}, ", day)?;
+        }
+
+        write!(f, "line {}, column {}: {}, found '{}'", self.line, self.column, self.message, self.text)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Find the (line, column) of `text` in `context`.
+///
+/// This is found from the position of the slice in memory if it is borrowed from `context`, otherwise it falls back to
+/// searching for the first matching text, and then to the start of `context`.
+fn locate(context: &str, text: &str) -> (usize, usize) {
+    let context_start = context.as_ptr() as usize;
+    let text_start = text.as_ptr() as usize;
+
+    let offset =
+        if text_start >= context_start && text_start + text.len() <= context_start + context.len() {
+            text_start - context_start
+        } else {
+            context.find(text).unwrap_or(0)
+        };
+
+    let before = &context[..offset];
+    let line_start = before.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+/// Parse each line of the input with `parse_line`, re-basing any error onto the full input
+pub fn parse_lines<'a, T, F>(input: &'a str, parse_line: F) -> Result<Vec<T>, ParseError>
+    where F: Fn(&'a str) -> Result<T, ParseError>
+{
+    input.lines()
+         .map(|line| parse_line(line).map_err(|err| err.within(input, line)))
+         .collect()
+}
+
+/// Parse `text`, a slice of `context`, as a number
+pub fn parse_number<T: FromStr>(context: &str, text: &str) -> Result<T, ParseError> {
+    text.trim()
+        .parse::<T>()
+        .map_err(|_| ParseError::new(context, text, "Expected a number"))
+}
+
+/// Split `text`, a slice of `context`, into two at the first occurrence of `delimiter`
+pub fn split_once<'a>(context: &str, text: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter)
+        .ok_or_else(|| ParseError::new(context, text, format!("Expected '{}'", delimiter)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::parse::{ParseError, parse_lines, parse_number, split_once};
+
+    #[test]
+    fn can_locate_errors() {
+        let input = "12 34\n56 7x\n";
+        let error = ParseError::new(input, &input[9..11], "Expected a number");
+
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 4);
+        assert_eq!(error.text, "7x");
+
+        let copied = ParseError::new(input, "56", "Expected something else");
+        assert_eq!((copied.line, copied.column), (2, 1));
+
+        let missing = ParseError::new(input, "99", "Expected something else");
+        assert_eq!((missing.line, missing.column), (1, 1));
+    }
+
+    #[test]
+    fn can_rebase_errors() {
+        let input = "first line\nsecond: abc, def\nthird";
+        let inner = &input[19..];
+        let error = ParseError::new(inner, &inner[5..8], "Bad value");
+
+        assert_eq!((error.line, error.column), (1, 6));
+        assert_eq!(error.clone().within(input, inner).line, 2);
+        assert_eq!(error.within(input, inner).column, 14);
+
+        let multi_line_error = ParseError::new(inner, &inner[9..], "Bad value");
+        assert_eq!((multi_line_error.line, multi_line_error.column), (2, 1));
+        assert_eq!(multi_line_error.within(input, inner).line, 3);
+    }
+
+    #[test]
+    fn can_display_errors() {
+        let input = "move 1 from x to 3";
+        let error = ParseError::new(input, &input[12..13], "Expected a number");
+
+        assert_eq!(error.to_string(), "line 1, column 13: Expected a number, found 'x'");
+        assert_eq!(error.for_day(5).to_string(), "Day 5, line 1, column 13: Expected a number, found 'x'");
+
+        let streamed = ParseError::at(1200, 3, "x", "Expected a number");
+        assert_eq!(streamed.to_string(), "line 1200, column 3: Expected a number, found 'x'");
+    }
+
+    #[test]
+    fn can_parse_lines() {
+        let input = "1\n2\nthree\n4";
+
+        assert_eq!(parse_lines(&input[..3], |line| parse_number::<u32>(line, line)), Ok(vec![1, 2]));
+
+        let error = parse_lines(input, |line| parse_number::<u32>(line, line)).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (3, 1, "three"));
+    }
+
+    #[test]
+    fn can_split_once() {
+        assert_eq!(split_once("a-b", "a-b", "-"), Ok(("a", "b")));
+        assert_eq!(split_once("a-b", "a-b", ",").unwrap_err().message, "Expected ','");
+    }
+}
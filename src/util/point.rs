@@ -0,0 +1,373 @@
+//! Points and vectors in 2D and 3D, with signed co-ordinates so they can be used as deltas as well as positions.
+//!
+//! Points are always `x` then `y` (then `z`). In 2D `y` increases downwards, matching the puzzle inputs, so
+//! [`Direction::Up`] is `y - 1`. [`Grid`](crate::util::grid::Grid) positions are `(y, x)` tuples, use
+//! [`Point2::from_grid_position`] and [`Point2::grid_position`] to convert between the two.
+
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point, or vector, in 2D space
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Ord, PartialOrd, Default)]
+pub struct Point2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// A point, or vector, in 3D space
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Ord, PartialOrd, Default)]
+pub struct Point3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+/// The four orthogonal directions on a 2D grid
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// The eight directions to a cell's neighbours on a 2D grid, including diagonals
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum Compass {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Point2 {
+        Point2 { x, y }
+    }
+
+    /// Convert a `(y, x)` position in a [`Grid`](crate::util::grid::Grid)
+    pub fn from_grid_position((y, x): (usize, usize)) -> Point2 {
+        Point2::new(x as isize, y as isize)
+    }
+
+    /// Convert to a `(y, x)` position in a [`Grid`](crate::util::grid::Grid), `None` if either co-ordinate is negative
+    pub fn grid_position(&self) -> Option<(usize, usize)> {
+        usize::try_from(self.y).ok().zip(usize::try_from(self.x).ok())
+    }
+
+    /// The number of orthogonal steps between two points
+    pub fn manhattan_distance(&self, other: &Point2) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The number of steps between two points when diagonal steps are allowed
+    pub fn chebyshev_distance(&self, other: &Point2) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Reduce each co-ordinate to -1, 0 or 1, i.e. the single step that moves most directly along this vector
+    pub fn signum(&self) -> Point2 {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    /// The point one step away in a direction
+    pub fn step(&self, direction: Direction) -> Point2 {
+        *self + direction.delta()
+    }
+
+    /// The four points that are one orthogonal step away, in the order of [`Direction::ALL`]
+    pub fn orthogonal_neighbours(&self) -> [Point2; 4] {
+        Direction::ALL.map(|direction| *self + direction.delta())
+    }
+
+    /// The eight points that are one step away including diagonals, in the order of [`Compass::ALL`]
+    pub fn neighbours(&self) -> [Point2; 8] {
+        Compass::ALL.map(|direction| *self + direction.delta())
+    }
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    /// The vectors to the six points that share a face with a point, i.e. one step along each axis
+    pub const UNIT_DELTAS: [Point3; 6] = [
+        Point3::new(1, 0, 0),
+        Point3::new(-1, 0, 0),
+        Point3::new(0, 1, 0),
+        Point3::new(0, -1, 0),
+        Point3::new(0, 0, 1),
+        Point3::new(0, 0, -1),
+    ];
+
+    pub const fn new(x: isize, y: isize, z: isize) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// The number of steps along the axes between two points
+    pub fn manhattan_distance(&self, other: &Point3) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// The number of steps between two points when diagonal steps are allowed
+    pub fn chebyshev_distance(&self, other: &Point3) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)).max(self.z.abs_diff(other.z))
+    }
+
+    /// The six points that share a face with this one, in the order of [`Point3::UNIT_DELTAS`]
+    pub fn neighbours(&self) -> [Point3; 6] {
+        Point3::UNIT_DELTAS.map(|delta| *self + delta)
+    }
+}
+
+impl Direction {
+    /// Clockwise from up
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    /// The vector for one step in this direction
+    pub fn delta(&self) -> Point2 {
+        match self {
+            Direction::Up => Point2::new(0, -1),
+            Direction::Right => Point2::new(1, 0),
+            Direction::Down => Point2::new(0, 1),
+            Direction::Left => Point2::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        self.turn_right().opposite()
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_right().turn_right()
+    }
+}
+
+impl Compass {
+    /// Clockwise from north
+    pub const ALL: [Compass; 8] =
+        [Compass::N, Compass::NE, Compass::E, Compass::SE, Compass::S, Compass::SW, Compass::W, Compass::NW];
+
+    /// The vector for one step in this direction, north is up i.e. `y - 1`
+    pub fn delta(&self) -> Point2 {
+        match self {
+            Compass::N => Point2::new(0, -1),
+            Compass::NE => Point2::new(1, -1),
+            Compass::E => Point2::new(1, 0),
+            Compass::SE => Point2::new(1, 1),
+            Compass::S => Point2::new(0, 1),
+            Compass::SW => Point2::new(-1, 1),
+            Compass::W => Point2::new(-1, 0),
+            Compass::NW => Point2::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Compass::N,
+            Direction::Right => Compass::E,
+            Direction::Down => Compass::S,
+            Direction::Left => Compass::W,
+        }
+    }
+}
+
+impl From<(isize, isize)> for Point2 {
+    /// From an `(x, y)` tuple
+    fn from((x, y): (isize, isize)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl From<(isize, isize, isize)> for Point3 {
+    /// From an `(x, y, z)` tuple
+    fn from((x, y, z): (isize, isize, isize)) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl Display for Point2 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<isize> for Point2 {
+    type Output = Point2;
+
+    fn mul(self, scale: isize) -> Point2 {
+        Point2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point2 {
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<isize> for Point3 {
+    type Output = Point3;
+
+    fn mul(self, scale: isize) -> Point3 {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Point3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, other: Point3) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::point::{Compass, Direction, Point2, Point3};
+
+    #[test]
+    fn can_do_arithmetic() {
+        let mut point = Point2::new(3, -2);
+
+        assert_eq!(point + Point2::new(1, 1), Point2::new(4, -1));
+        assert_eq!(point - Point2::new(1, 1), Point2::new(2, -3));
+        assert_eq!(point * 3, Point2::new(9, -6));
+        assert_eq!(-point, Point2::new(-3, 2));
+
+        point += Point2::new(-3, 2);
+        assert_eq!(point, Point2::ORIGIN);
+        point -= Point2::new(1, 2);
+        assert_eq!(point, Point2::from((-1, -2)));
+
+        let point = Point3::new(1, 2, 3);
+        assert_eq!(point + Point3::new(1, 1, 1) - Point3::new(0, 0, 5), Point3::new(2, 3, -1));
+        assert_eq!(-point * 2, Point3::new(-2, -4, -6));
+    }
+
+    #[test]
+    fn can_measure_distances() {
+        let a = Point2::new(1, 5);
+        let b = Point2::new(-2, 3);
+
+        assert_eq!(a.manhattan_distance(&b), 5);
+        assert_eq!(a.chebyshev_distance(&b), 3);
+        assert_eq!(b.manhattan_distance(&a), 5);
+
+        let a = Point3::new(1, 5, 0);
+        let b = Point3::new(-2, 3, 4);
+        assert_eq!(a.manhattan_distance(&b), 9);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+    }
+
+    #[test]
+    fn can_step_in_directions() {
+        let point = Point2::new(2, 2);
+
+        assert_eq!(point.step(Direction::Up), Point2::new(2, 1));
+        assert_eq!(point.step(Direction::Left), Point2::new(1, 2));
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Right.opposite(), Direction::Left);
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.delta(), Compass::from(direction).delta());
+            assert_eq!(direction.delta(), -direction.opposite().delta());
+        }
+
+        assert_eq!(point.orthogonal_neighbours()[1], Point2::new(3, 2));
+        assert_eq!(point.neighbours()[7], Point2::new(1, 1));
+        assert!(point.neighbours().iter().all(|neighbour| neighbour.chebyshev_distance(&point) == 1));
+        assert_eq!(Point3::ORIGIN.neighbours().iter().map(|n| n.manhattan_distance(&Point3::ORIGIN)).sum::<usize>(), 6);
+
+        assert_eq!(Point2::new(-7, 4).signum(), Point2::new(-1, 1));
+    }
+
+    #[test]
+    fn can_convert_grid_positions() {
+        assert_eq!(Point2::from_grid_position((2, 5)), Point2::new(5, 2));
+        assert_eq!(Point2::new(5, 2).grid_position(), Some((2, 5)));
+        assert_eq!(Point2::new(5, -1).grid_position(), None);
+        assert_eq!(Point2::new(5, -1).to_string(), "(5, -1)");
+    }
+}
@@ -0,0 +1,222 @@
+//! Generic graph searches, parameterised by the state type and a function giving each state's successors.
+//!
+//! - [`bfs`] for when every step costs the same
+//! - [`dijkstra`] for when steps have different costs
+//! - [`a_star`] which adds a heuristic, this must never overestimate the remaining cost to a goal for the result to
+//!   be the cheapest path
+//! - [`reachable`] which finds the distance to every state that can be reached, e.g. for a flood fill
+//!
+//! The searches return a [`Found`] with the cost to reach the goal, the path is only built if it is asked for.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The result of a successful search
+#[derive(Debug, Clone)]
+pub struct Found<S> {
+    /// The total cost of the steps taken to reach the goal, for [`bfs`] this is the number of steps
+    pub cost: usize,
+    /// The goal state that was reached
+    pub goal: S,
+    /// The state each visited state was reached from, used to build the path
+    parents: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash> Found<S> {
+    /// The states visited from the start to the goal, inclusive
+    pub fn path(&self) -> Vec<S> {
+        let mut path = vec![self.goal.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Find the fewest steps from `start` to a state that meets `is_goal`
+pub fn bfs<S, I, N, G>(start: S, mut successors: N, mut is_goal: G) -> Option<Found<S>>
+    where
+        S: Clone + Eq + Hash,
+        N: FnMut(&S) -> I,
+        I: IntoIterator<Item=S>,
+        G: FnMut(&S) -> bool,
+{
+    let mut parents: HashMap<S, S> = HashMap::new();
+    let mut to_visit: VecDeque<(S, usize)> = VecDeque::new();
+    to_visit.push_back((start.clone(), 0));
+
+    while let Some((state, steps)) = to_visit.pop_front() {
+        if is_goal(&state) {
+            return Some(Found { cost: steps, goal: state, parents });
+        }
+
+        for next in successors(&state) {
+            if next != start && !parents.contains_key(&next) {
+                parents.insert(next.clone(), state.clone());
+                to_visit.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Find the number of steps from `start` to every state that can be reached from it, including `start` itself
+pub fn reachable<S, I, N>(start: S, mut successors: N) -> HashMap<S, usize>
+    where
+        S: Clone + Eq + Hash,
+        N: FnMut(&S) -> I,
+        I: IntoIterator<Item=S>,
+{
+    let mut distances: HashMap<S, usize> = HashMap::new();
+    let mut to_visit: VecDeque<(S, usize)> = VecDeque::new();
+    distances.insert(start.clone(), 0);
+    to_visit.push_back((start, 0));
+
+    while let Some((state, steps)) = to_visit.pop_front() {
+        for next in successors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), steps + 1);
+                to_visit.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Find the cheapest path from `start` to a state that meets `is_goal`, where `successors` returns each next state
+/// with the cost of the step to it
+pub fn dijkstra<S, I, N, G>(start: S, successors: N, is_goal: G) -> Option<Found<S>>
+    where
+        S: Clone + Eq + Hash + Ord,
+        N: FnMut(&S) -> I,
+        I: IntoIterator<Item=(S, usize)>,
+        G: FnMut(&S) -> bool,
+{
+    a_star(start, successors, is_goal, |_| 0)
+}
+
+/// Find the cheapest path from `start` to a state that meets `is_goal`, exploring the states that `heuristic`
+/// estimates are closest to a goal first
+pub fn a_star<S, I, N, G, H>(start: S, mut successors: N, mut is_goal: G, mut heuristic: H) -> Option<Found<S>>
+    where
+        S: Clone + Eq + Hash + Ord,
+        N: FnMut(&S) -> I,
+        I: IntoIterator<Item=(S, usize)>,
+        G: FnMut(&S) -> bool,
+        H: FnMut(&S) -> usize,
+{
+    let mut costs: HashMap<S, usize> = HashMap::new();
+    let mut parents: HashMap<S, S> = HashMap::new();
+    // Reversed to make the heap a min-heap, ties are broken by the state so that searches are repeatable
+    let mut heap: BinaryHeap<Reverse<(usize, usize, S)>> = BinaryHeap::new();
+
+    costs.insert(start.clone(), 0);
+    heap.push(Reverse((heuristic(&start), 0, start)));
+
+    while let Some(Reverse((_, cost, state))) = heap.pop() {
+        if cost > costs[&state] {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some(Found { cost, goal: state, parents });
+        }
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+
+            if costs.get(&next).is_none_or(|&known_cost| next_cost < known_cost) {
+                costs.insert(next.clone(), next_cost);
+                parents.insert(next.clone(), state.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::Grid;
+    use crate::util::search::{a_star, bfs, dijkstra, reachable};
+
+    type Position = (usize, usize);
+
+    fn sample_maze() -> Grid<char> {
+        Grid::from_string_with_mapping(
+            "S..#....\n\
+             .#.#.##.\n\
+             .#...#..\n\
+             .####.#.\n\
+             ......#E",
+            |c| c,
+        )
+    }
+
+    fn open_neighbours(maze: &Grid<char>, pos: Position) -> Vec<Position> {
+        maze.get_orthogonal_surrounds(pos).into_iter().filter(|&(_, &c)| c != '#').map(|(pos, _)| pos).collect()
+    }
+
+    #[test]
+    fn can_bfs() {
+        let maze = sample_maze();
+
+        let found = bfs((0, 0), |&pos| open_neighbours(&maze, pos), |&pos| pos == (4, 7)).unwrap();
+        assert_eq!(found.cost, 15);
+        assert_eq!(found.goal, (4, 7));
+
+        let path = found.path();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 7)));
+        assert!(path.windows(2).all(|step| open_neighbours(&maze, step[0]).contains(&step[1])));
+
+        assert!(bfs((0, 0), |&pos| open_neighbours(&maze, pos), |&pos| pos == (0, 3)).is_none());
+        assert_eq!(bfs((0, 0), |&pos| open_neighbours(&maze, pos), |_| true).unwrap().path(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn can_find_reachable_states() {
+        let maze = sample_maze();
+        let distances = reachable((0, 0), |&pos| open_neighbours(&maze, pos));
+
+        assert_eq!(distances.len(), maze.iter().filter(|&(_, &c)| c != '#').count());
+        assert_eq!(distances.get(&(0, 0)), Some(&0));
+        assert_eq!(distances.get(&(3, 5)), Some(&10));
+        assert_eq!(distances.get(&(4, 7)), Some(&15));
+        assert_eq!(distances.get(&(0, 3)), None);
+    }
+
+    #[test]
+    fn can_find_cheapest_paths() {
+        // Moving down costs more than moving right
+        let successors = |&(y, x): &Position| {
+            [((y + 1, x), 3), ((y, x + 1), 1)].into_iter().filter(|&((y, x), _)| y <= 2 && x <= 2)
+        };
+
+        let found = dijkstra((0, 0), successors, |&pos| pos == (2, 2)).unwrap();
+        assert_eq!(found.cost, 8);
+
+        let found = a_star((0, 0), successors, |&pos| pos == (2, 2), |&(y, x)| (2 - y) * 3 + (2 - x)).unwrap();
+        assert_eq!(found.cost, 8);
+        assert_eq!(found.path().len(), 5);
+
+        let maze = sample_maze();
+        let found = a_star(
+            (0, 0),
+            |&pos| open_neighbours(&maze, pos).into_iter().map(|next| (next, 1)),
+            |&pos| pos == (4, 7),
+            |&(y, x)| 4 - y + 7 - x,
+        ).unwrap();
+        assert_eq!(found.cost, 15);
+        assert_eq!(found.path().len(), 16);
+
+        assert!(dijkstra((0, 0), successors, |&pos| pos == (3, 3)).is_none());
+    }
+}
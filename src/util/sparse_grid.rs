@@ -0,0 +1,266 @@
+//! An unbounded 2D grid that only stores the cells that have been set, for simulations that can grow in any
+//! direction. Co-ordinates are signed and given as `(y, x)` to match [`Grid`].
+
+use std::collections::HashMap;
+use crate::util::grid::Grid;
+
+/// The `(y, x)` co-ordinates of a dense grid's top left cell, when it is converted to or from a [`SparseGrid`]
+pub type Origin = (isize, isize);
+
+/// The smallest rectangle containing every set cell of a [`SparseGrid`], inclusive at both ends
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Bounds {
+    pub min_y: isize,
+    pub min_x: isize,
+    pub max_y: isize,
+    pub max_x: isize,
+}
+
+impl Bounds {
+    /// A bounding box containing just one cell
+    fn around(y: isize, x: isize) -> Bounds {
+        Bounds { min_y: y, min_x: x, max_y: y, max_x: x }
+    }
+
+    /// Grow the bounding box to include a cell
+    fn include(&mut self, y: isize, x: isize) {
+        self.min_y = self.min_y.min(y);
+        self.min_x = self.min_x.min(x);
+        self.max_y = self.max_y.max(y);
+        self.max_x = self.max_x.max(x);
+    }
+
+    /// Is the cell on the edge of the bounding box, i.e. could removing it shrink the box
+    fn is_on_edge(&self, y: isize, x: isize) -> bool {
+        y == self.min_y || y == self.max_y || x == self.min_x || x == self.max_x
+    }
+
+    pub fn height(&self) -> usize {
+        self.min_y.abs_diff(self.max_y) + 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.min_x.abs_diff(self.max_x) + 1
+    }
+
+    /// The number of cells in the bounding box, set or not
+    pub fn area(&self) -> usize {
+        self.height() * self.width()
+    }
+
+    pub fn contains(&self, y: isize, x: isize) -> bool {
+        (self.min_y..=self.max_y).contains(&y) && (self.min_x..=self.max_x).contains(&x)
+    }
+}
+
+/// A representation of an unbounded 2D grid, storing a value for each cell that has been set
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+    /// Kept up to date as cells are inserted, and only recalculated when a cell on the edge is removed
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid { cells: HashMap::new(), bounds: None }
+    }
+}
+
+impl<T> FromIterator<((isize, isize), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item=((isize, isize), T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for ((y, x), val) in iter {
+            grid.insert(y, x, val);
+        }
+
+        grid
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a sparse grid from a dense one, with its top left cell at `origin`, i.e. `(y, x)`. Cells that `mapping`
+    /// returns `None` for are left unset.
+    pub fn from_grid<U, F>(grid: &Grid<U>, (origin_y, origin_x): Origin, mapping: F) -> Self
+        where F: Fn(&U) -> Option<T>
+    {
+        grid.iter()
+            .flat_map(|((y, x), val)| mapping(val).map(|val| ((origin_y + y as isize, origin_x + x as isize), val)))
+            .collect()
+    }
+
+    /// The number of cells that are set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The bounding box of the set cells, `None` if the grid is empty
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Return the value at the given co-ordinates, if it has been set
+    pub fn get(&self, y: isize, x: isize) -> Option<&T> {
+        self.cells.get(&(y, x))
+    }
+
+    pub fn contains(&self, y: isize, x: isize) -> bool {
+        self.cells.contains_key(&(y, x))
+    }
+
+    /// Set the value in a given cell, returning the value it replaced if there was one
+    pub fn insert(&mut self, y: isize, x: isize, val: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(bounds) => bounds.include(y, x),
+            None => self.bounds = Some(Bounds::around(y, x)),
+        }
+
+        self.cells.insert((y, x), val)
+    }
+
+    /// Unset a cell, returning its value if it was set
+    pub fn remove(&mut self, y: isize, x: isize) -> Option<T> {
+        let removed = self.cells.remove(&(y, x));
+
+        if removed.is_some() && self.bounds.is_some_and(|bounds| bounds.is_on_edge(y, x)) {
+            self.bounds = self.cells.keys().fold(None, |bounds: Option<Bounds>, &(y, x)| match bounds {
+                Some(mut bounds) => {
+                    bounds.include(y, x);
+                    Some(bounds)
+                }
+                None => Some(Bounds::around(y, x)),
+            });
+        }
+
+        removed
+    }
+
+    /// Iterate over the set cells, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item=((isize, isize), &T)> {
+        self.cells.iter().map(|(&pos, val)| (pos, val))
+    }
+
+    /// Render the bounding box as a string, with a line per row, using `cell_renderer` for each cell whether it is set
+    /// or not
+    pub fn print_with<F>(&self, cell_renderer: F) -> String
+        where F: Fn(Option<&T>) -> String
+    {
+        let Some(bounds) = self.bounds else { return String::new() };
+
+        (bounds.min_y..=bounds.max_y)
+            .map(|y| (bounds.min_x..=bounds.max_x).map(|x| cell_renderer(self.get(y, x))).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Copy the bounding box into a dense grid, returned with the origin `(min_y, min_x)` of its top left cell so
+    /// that [`SparseGrid::from_grid`] can put the cells back where they were. `None` if the grid is empty.
+    pub fn to_grid(&self) -> Option<(Origin, Grid<Option<T>>)> where T: Clone {
+        let bounds = self.bounds?;
+        let grid = Grid::new(bounds.width(), bounds.height(), |x, y| {
+            self.get(bounds.min_y + y as isize, bounds.min_x + x as isize).cloned()
+        });
+
+        Some(((bounds.min_y, bounds.min_x), grid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::Grid;
+    use crate::util::sparse_grid::{Bounds, SparseGrid};
+
+    fn render(grid: &SparseGrid<char>) -> String {
+        grid.print_with(|cell| cell.copied().unwrap_or('.').to_string())
+    }
+
+    #[test]
+    fn can_set_and_get() {
+        let mut grid = SparseGrid::new();
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(render(&grid), "");
+
+        assert_eq!(grid.insert(0, 0, 'a'), None);
+        assert_eq!(grid.insert(-2, 3, 'b'), None);
+        assert_eq!(grid.insert(0, 0, 'c'), Some('a'));
+
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.get(0, 0), Some(&'c'));
+        assert_eq!(grid.get(-2, 3), Some(&'b'));
+        assert_eq!(grid.get(1, 1), None);
+        assert!(grid.contains(-2, 3));
+        assert!(!grid.contains(3, -2));
+    }
+
+    #[test]
+    fn tracks_bounds() {
+        let mut grid: SparseGrid<char> = [((0, 0), 'a'), ((-2, 3), 'b'), ((1, -1), 'c')].into_iter().collect();
+
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds, Bounds { min_y: -2, min_x: -1, max_y: 1, max_x: 3 });
+        assert_eq!((bounds.height(), bounds.width(), bounds.area()), (4, 5, 20));
+        assert!(bounds.contains(-2, -1));
+        assert!(!bounds.contains(2, 0));
+
+        assert_eq!(grid.remove(0, 0), Some('a'));
+        assert_eq!(grid.bounds(), Some(bounds));
+
+        assert_eq!(grid.remove(-2, 3), Some('b'));
+        assert_eq!(grid.bounds(), Some(Bounds { min_y: 1, min_x: -1, max_y: 1, max_x: -1 }));
+
+        assert_eq!(grid.remove(5, 5), None);
+        assert_eq!(grid.remove(1, -1), Some('c'));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn can_print() {
+        let grid: SparseGrid<char> = [((0, 0), 'a'), ((-2, 3), 'b'), ((1, -1), 'c')].into_iter().collect();
+
+        assert_eq!(render(&grid), ["....b", ".....", ".a...", "c...."].join("\n"));
+    }
+
+    #[test]
+    fn can_convert_to_and_from_dense_grids() {
+        let dense = Grid::from_string_with_mapping("..#\n#..\n...", |c| c);
+        let sparse = SparseGrid::from_grid(&dense, (0, 0), |&c| (c == '#').then_some(c));
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.bounds(), Some(Bounds { min_y: 0, min_x: 0, max_y: 1, max_x: 2 }));
+        assert_eq!(render(&sparse), "..#\n#..");
+
+        let (origin, grid) = sparse.to_grid().unwrap();
+        assert_eq!(origin, (0, 0));
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get(0, 2), Some(&Some('#')));
+        assert_eq!(grid.get(0, 0), Some(&None));
+
+        let round_trip = SparseGrid::from_grid(&grid, origin, |cell| *cell);
+        assert_eq!(round_trip, sparse);
+
+        assert_eq!(SparseGrid::<char>::new().to_grid(), None);
+    }
+
+    #[test]
+    fn keeps_the_origin_when_converting() {
+        let sparse: SparseGrid<char> = vec![((-3, -2), 'a'), ((-1, 1), 'b'), ((0, -2), 'c')].into_iter().collect();
+
+        let (origin, grid) = sparse.to_grid().unwrap();
+        assert_eq!(origin, (-3, -2));
+        assert_eq!((grid.width, grid.height()), (4, 4));
+        assert_eq!(grid.get(2, 3), Some(&Some('b')));
+
+        let round_trip = SparseGrid::from_grid(&grid, origin, |cell| *cell);
+        assert_eq!(round_trip, sparse);
+        assert_eq!(round_trip.get(-1, 1), Some(&'b'));
+    }
+}